@@ -8,9 +8,18 @@ use wavedrom::Figure;
 fuzz_target!(|data: WaveJson| {
     let figure = Figure::from(data);
 
-    let Figure::Signal(figure) = figure;
-
-    let assembled = figure.assemble();
     let mut writer = Vec::new();
-    let _ = assembled.write_svg(&mut writer);
+
+    match figure {
+        Figure::Signal(figure) => {
+            let assembled = figure.assemble();
+            let _ = assembled.write_svg(&mut writer);
+        }
+        Figure::Register(figure) => {
+            let _ = figure.write_svg(&mut writer);
+        }
+        Figure::Assign(figure) => {
+            let _ = figure.write_svg(&mut writer);
+        }
+    }
 });
@@ -0,0 +1,15 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use wavedrom::wavejson::WaveJson;
+use wavedrom::Figure;
+
+fuzz_target!(|data: WaveJson| {
+    let figure = Figure::from(data);
+
+    let wavejson = figure.to_wavejson();
+    let roundtrip = Figure::from(wavejson.clone()).to_wavejson();
+
+    assert_eq!(wavejson.to_json5().unwrap(), roundtrip.to_json5().unwrap());
+});
@@ -1,15 +1,71 @@
+use std::collections::HashMap;
 use std::fmt::Display;
+use std::path::{Component, Path, PathBuf};
 
 use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag};
-use wavedrom::json5::Error as JsonError;
+use wavedrom::reg::FieldString;
+use wavedrom::signal::SignalFigureSection;
+use wavedrom::skin::SkinRegistry;
+use wavedrom::Error as JsonError;
 use wavedrom::Figure;
 
+/// The configuration of how diagrams are inserted into a chapter
+#[derive(Debug)]
+pub struct Config {
+    /// The options to render the diagrams with
+    pub options: wavedrom::Options,
+    /// The skins that diagrams can select with their `config.skin`
+    pub skins: SkinRegistry,
+    /// Whether to show the source of every diagram below it. Otherwise, the source is only shown
+    /// for `wavedrom [with_source]` code blocks.
+    pub with_source: bool,
+    /// The name of the element that wraps a diagram
+    pub element: String,
+    /// The class of the element that wraps a diagram
+    pub class: String,
+    /// Where the SVGs of the diagrams are put
+    pub output: Output,
+}
+
+/// Where the SVGs of the diagrams are put
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// Put the SVG markup into the chapter
+    #[default]
+    Inline,
+    /// Write every SVG to a file in the `wavedrom` directory of the book's source directory and
+    /// refer to it with an `<img>`. The name of a file is a hash of its content and files that
+    /// already exist with the same content are not written again.
+    Files {
+        /// The source directory of the book
+        src_dir: PathBuf,
+    },
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            options: wavedrom::Options::default(),
+            skins: SkinRegistry::default(),
+            with_source: false,
+            element: "pre".to_string(),
+            class: "wavedrom".to_string(),
+            output: Output::Inline,
+        }
+    }
+}
+
 #[derive(Debug)]
 pub enum InsertionError {
+    /// The WaveJson is invalid. The location of the error is a line of the chapter.
     Json(JsonError),
+    /// No longer returned, as every valid WaveJson forms a figure. Invalid WaveJson is reported
+    /// as [`InsertionError::Json`] instead.
     InvalidFigure,
     WriteSvg,
     InvalidUtf8,
+    /// The SVG file could not be written
+    WriteFile(PathBuf, std::io::Error),
 }
 
 impl From<JsonError> for InsertionError {
@@ -22,20 +78,125 @@ impl From<JsonError> for InsertionError {
 impl Display for InsertionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            Self::Json(err) => write!(f, "{err}"),
+            Self::Json(err) => write!(f, "{}", err.snippet()),
             Self::InvalidFigure => write!(f, "Failed to form a figure from the given WaveJson"),
             Self::WriteSvg => write!(f, "Failed to write svg of WaveDrom figure"),
             Self::InvalidUtf8 => write!(f, "Wavedrom returned invalid UTF-8"),
+            Self::WriteFile(path, err) => {
+                write!(f, "Failed to write '{}'. Reason: {err}", path.display())
+            }
         }
     }
 }
 
 impl std::error::Error for InsertionError {}
 
+/// An [`InsertionError`] of the WaveDrom code block at a line of the chapter
+#[derive(Debug)]
+pub struct DiagramError {
+    line: usize,
+    error: InsertionError,
+}
+
+impl DiagramError {
+    /// Returns the one-based line of the chapter at which the code block starts.
+    #[inline]
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// Returns what went wrong.
+    #[inline]
+    pub fn error(&self) -> &InsertionError {
+        &self.error
+    }
+
+    /// Returns what went wrong without the line of the code block.
+    #[inline]
+    pub fn into_error(self) -> InsertionError {
+        self.error
+    }
+}
+
+impl Display for DiagramError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.error {
+            // The snippet already shows the lines of the chapter
+            InsertionError::Json(_) => write!(f, "{}", self.error),
+            error => write!(f, "{error} (figure at line {})", self.line),
+        }
+    }
+}
+
+impl std::error::Error for DiagramError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Returns the HTML that can be shown in place of a diagram that failed to render.
+pub fn inline_error(error: &DiagramError) -> String {
+    let mut message = String::new();
+    for c in error.to_string().chars() {
+        match c {
+            '<' => message.push_str("&lt;"),
+            '>' => message.push_str("&gt;"),
+            '&' => message.push_str("&amp;"),
+            c => message.push(c),
+        }
+    }
+
+    format!(
+        r#"<pre class="wavedrom-error" style="border: 2px solid #C33; padding: 8px;">{message}</pre>"#
+    )
+}
+
+/// Escape the characters that have a special meaning in an HTML attribute value.
+fn escape_attribute(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Returns a 64-bit FNV-1a hash, which is stable between builds.
+fn content_hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+/// Replace all the WaveDrom code blocks in the markdown `content` with inline diagrams.
+///
+/// This fails at the first diagram that does not render. Use [`insert_wavedrom_with`] to
+/// configure the diagrams and to handle errors.
 pub fn insert_wavedrom(
     content: &str,
     options: &wavedrom::Options,
 ) -> Result<String, InsertionError> {
+    let config = Config {
+        options: options.clone(),
+        ..Config::default()
+    };
+
+    insert_wavedrom_with(content, Path::new(""), &config, Err).map_err(DiagramError::into_error)
+}
+
+/// Replace all the WaveDrom code blocks in the markdown `content` of the chapter at
+/// `chapter_path` with diagrams. The `chapter_path` is relative to the source directory of the
+/// book.
+///
+/// When a diagram fails to render, `on_error` is called with the error. It either returns the
+/// markup that replaces the code block instead (e.g. [`inline_error`]) or an error to stop.
+pub fn insert_wavedrom_with(
+    content: &str,
+    chapter_path: &Path,
+    config: &Config,
+    mut on_error: impl FnMut(DiagramError) -> Result<String, DiagramError>,
+) -> Result<String, DiagramError> {
+    // The number of times each id prefix is used on the page
+    let mut id_prefixes = HashMap::new();
+
     let mut opts = Options::empty();
     opts.insert(Options::ENABLE_TABLES);
     opts.insert(Options::ENABLE_FOOTNOTES);
@@ -50,6 +211,9 @@ pub fn insert_wavedrom(
     for (e, span) in Parser::new_ext(content, opts).into_offset_iter() {
         if let Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(code))) = e {
             if code.as_ref().trim_start().starts_with("wavedrom") {
+                let source_code_tag =
+                    span.start..span.start + content[span.start..].find('\n').unwrap_or(span.end);
+
                 if code.as_ref().trim() == "wavedrom" {
                     wavedrom_block_start = Some(span.start);
                 } else {
@@ -60,17 +224,17 @@ pub fn insert_wavedrom(
                         let control_flags = rest[1..rest.len() - 1].trim();
                         for control_flag in control_flags.split(',') {
                             if control_flag.trim() == "with_source" {
-                                keep_source_code_tag = Some(
-                                    span.start
-                                        ..span.start
-                                            + content[span.start..].find('\n').unwrap_or(span.end),
-                                )
+                                keep_source_code_tag = Some(source_code_tag.clone())
                             }
                         }
 
                         wavedrom_block_start = Some(span.start);
                     }
                 }
+
+                if config.with_source && wavedrom_block_start.is_some() {
+                    keep_source_code_tag = Some(source_code_tag);
+                }
             }
 
             continue;
@@ -97,34 +261,39 @@ pub fn insert_wavedrom(
 
             let block_end = span.end;
 
+            // The diagram starts on the line after the opening fence
+            let line_offset = content[..current_spans.first().map_or(block_start, |s| s.start)]
+                .matches('\n')
+                .count();
+
             let mut diagram_content = String::with_capacity(block_end - block_start);
             for span in std::mem::take(&mut current_spans).into_iter() {
                 diagram_content.push_str(&content[span]);
             }
 
-            let mut wavedrom_code = Vec::new();
-
-            let wavedrom_figure =
-                Figure::from_json5(&diagram_content).map_err(|_| InsertionError::InvalidFigure)?;
-
-            match wavedrom_figure {
-                Figure::Signal(wavedrom_figure) => {
-                    wavedrom_figure
-                        .assemble_with_options(options)
-                        .write_svg_with_options(&mut wavedrom_code, options)
-                        .map_err(|_| InsertionError::WriteSvg)?;
-                }
-                Figure::Register(register) => todo!(),
-            }
+            let block =
+                match render_diagram(&diagram_content, chapter_path, config, &mut id_prefixes) {
+                    Ok(diagram) => format!(
+                        r#"<{element} class="{class}">{diagram}</{element}>"#,
+                        element = config.element,
+                        class = escape_attribute(&config.class),
+                    ),
+                    Err(error) => {
+                        let error = match error {
+                            InsertionError::Json(err) => {
+                                InsertionError::Json(err.with_line_offset(line_offset))
+                            }
+                            error => error,
+                        };
 
-            let wavedrom_code =
-                String::from_utf8(wavedrom_code).map_err(|_| InsertionError::InvalidUtf8)?;
+                        on_error(DiagramError {
+                            line: content[..block_start].matches('\n').count() + 1,
+                            error,
+                        })?
+                    }
+                };
 
-            diagrams.push((
-                block_start..block_end,
-                wavedrom_code,
-                keep_source_code_tag.take(),
-            ));
+            diagrams.push((block_start..block_end, block, keep_source_code_tag.take()));
         }
     }
 
@@ -133,11 +302,11 @@ pub fn insert_wavedrom(
 
     for (span, block, keep_source_code) in diagrams.into_iter() {
         out.push_str(&content[end_prev..span.start]);
-        out.push_str(r#"<pre class="wavedrom">"#);
         out.push_str(&block);
-        out.push_str("</pre>");
 
         if let Some(tag_span) = keep_source_code {
+            // The code block has to start on a new line after the HTML block
+            out.push_str("\n\n");
             out.push_str(&content[span.start..tag_span.start]);
             out.push_str("```json");
             out.push_str(&content[tag_span.end..span.end]);
@@ -151,6 +320,173 @@ pub fn insert_wavedrom(
     Ok(out)
 }
 
+/// Remove the diagram files in the `wavedrom` directory of `src_dir` that none of the `chapters`
+/// refer to anymore.
+///
+/// This is used with [`Output::Files`] after all the chapters of the book have been processed.
+/// Only files that are named like a diagram file are removed.
+pub fn remove_unused_files<'a>(
+    src_dir: &Path,
+    chapters: impl IntoIterator<Item = &'a str>,
+) -> std::io::Result<()> {
+    let dir = src_dir.join("wavedrom");
+    let entries = match std::fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    let chapters = chapters.into_iter().collect::<Vec<_>>();
+    for entry in entries {
+        let file_name = entry?.file_name();
+        let Some(file_name) = file_name.to_str() else {
+            continue;
+        };
+
+        let is_diagram_file = file_name.strip_suffix(".svg").is_some_and(|hash| {
+            hash.len() == 16 && hash.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+        });
+        let reference = format!("wavedrom/{file_name}");
+        if is_diagram_file && !chapters.iter().any(|chapter| chapter.contains(&reference)) {
+            std::fs::remove_file(dir.join(file_name))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Render the WaveJson of a code block into the markup of the diagram for the [`Output`].
+///
+/// The `id_prefixes` count how often each id prefix is already used on the page.
+fn render_diagram(
+    diagram_content: &str,
+    chapter_path: &Path,
+    config: &Config,
+    id_prefixes: &mut HashMap<String, usize>,
+) -> Result<String, InsertionError> {
+    let figure = Figure::from_json5(diagram_content).map_err(InsertionError::Json)?;
+    let svg = render_svg(&figure, diagram_content, config, id_prefixes)?;
+
+    let Output::Files { src_dir } = &config.output else {
+        return Ok(svg);
+    };
+
+    let file_name = format!("{:016x}.svg", content_hash(svg.as_bytes()));
+    let path = src_dir.join("wavedrom").join(&file_name);
+
+    // Rewriting an unchanged file would make `mdbook serve` rebuild the book again
+    if std::fs::read(&path).map_or(true, |content| content != svg.as_bytes()) {
+        std::fs::create_dir_all(src_dir.join("wavedrom"))
+            .and_then(|_| std::fs::write(&path, &svg))
+            .map_err(|err| InsertionError::WriteFile(path, err))?;
+    }
+
+    // The page of a chapter is placed at the same depth as its source
+    let depth = chapter_path.parent().map_or(0, |parent| {
+        parent
+            .components()
+            .filter(|component| matches!(component, Component::Normal(_)))
+            .count()
+    });
+
+    Ok(format!(
+        r#"<img src="{}wavedrom/{file_name}" alt="{}">"#,
+        "../".repeat(depth),
+        escape_attribute(&alt_text(&figure)),
+    ))
+}
+
+/// Render a figure into an SVG.
+fn render_svg(
+    figure: &Figure,
+    diagram_content: &str,
+    config: &Config,
+    id_prefixes: &mut HashMap<String, usize>,
+) -> Result<String, InsertionError> {
+    let mut wavedrom_code = Vec::new();
+
+    let mut options = config.skins.figure_options(figure, &config.options);
+    // Every figure on a page needs its own ids, also when the same figure occurs more than once
+    let id_prefix = wavedrom::Options::id_prefix_for(diagram_content, &options);
+    let occurrence = id_prefixes.entry(id_prefix.clone()).or_insert(0);
+    options.id_prefix.push_str(&id_prefix);
+    if *occurrence > 0 {
+        options.id_prefix.push_str(&format!("{occurrence}-"));
+    }
+    *occurrence += 1;
+    let options = &options;
+
+    match figure {
+        Figure::Signal(figure) => figure
+            .assemble_with_options(options)
+            .write_svg_with_options(&mut wavedrom_code, options),
+        Figure::Register(register) => register.write_svg_with_options(&mut wavedrom_code, options),
+        Figure::Assign(assign) => assign.write_svg_with_options(&mut wavedrom_code, options),
+    }
+    .map_err(|_| InsertionError::WriteSvg)?;
+
+    String::from_utf8(wavedrom_code).map_err(|_| InsertionError::InvalidUtf8)
+}
+
+/// Describe a figure for the `alt` attribute of its image with its header text and the names
+/// of its signals, fields or outputs.
+fn alt_text(figure: &Figure) -> String {
+    fn signal_names(sections: &[SignalFigureSection], names: &mut Vec<String>) {
+        for section in sections {
+            match section {
+                SignalFigureSection::Signal(signal) => {
+                    names.push(signal.get_name().to_plain_string())
+                }
+                SignalFigureSection::Group(group) => signal_names(group.items(), names),
+            }
+        }
+    }
+
+    let (kind, header, mut names) = match figure {
+        Figure::Signal(figure) => {
+            let mut names = Vec::new();
+            signal_names(figure.sections(), &mut names);
+
+            let header = figure.get_header_text().map(|text| text.to_plain_string());
+            ("Timing diagram", header, names)
+        }
+        Figure::Register(register) => {
+            let names = register
+                .lanes()
+                .iter()
+                .flat_map(|lane| lane.bit_ranges())
+                .filter_map(|field| match field.get_name() {
+                    Some(FieldString::Text(name)) => Some(name.clone()),
+                    _ => None,
+                })
+                .collect();
+            ("Register", None, names)
+        }
+        Figure::Assign(assign) => {
+            let names = assign
+                .assignments()
+                .iter()
+                .map(|assignment| assignment.output().to_string())
+                .collect();
+            ("Logic circuit", None, names)
+        }
+    };
+
+    names.retain(|name| !name.trim().is_empty());
+
+    let mut alt = kind.to_string();
+    if let Some(header) = header.filter(|header| !header.trim().is_empty()) {
+        alt.push_str(": ");
+        alt.push_str(header.trim());
+    }
+    if !names.is_empty() {
+        alt.push_str(" with ");
+        alt.push_str(&names.join(", "));
+    }
+
+    alt
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -242,4 +578,220 @@ ABC:
             .trim()
         )
     }
+
+    #[test]
+    fn unique_ids() {
+        let content = r#"
+```wavedrom
+{ "signal": [{ "wave": "x.1" }] }
+```
+
+```wavedrom
+{ "signal": [{ "wave": "x.0" }] }
+```
+        "#;
+
+        let replaced_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &Config::default(), Err)
+                .unwrap();
+
+        let ids = pattern_ids(&replaced_content);
+
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert!(replaced_content.contains(&format!("url(#{})", ids[1])));
+    }
+
+    fn pattern_ids(content: &str) -> Vec<&str> {
+        content
+            .match_indices(r#"<pattern id=""#)
+            .map(|(i, start)| {
+                let id = &content[i + start.len()..];
+                &id[..id.find('"').unwrap()]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn repeated_diagram_ids() {
+        let content = r#"
+```wavedrom
+{ "signal": [{ "wave": "x.1" }] }
+```
+
+```wavedrom
+{ "signal": [{ "wave": "x.1" }] }
+```
+        "#;
+
+        let replaced_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &Config::default(), Err)
+                .unwrap();
+
+        let ids = pattern_ids(&replaced_content);
+        assert_eq!(ids.len(), 2);
+        assert_ne!(ids[0], ids[1]);
+        assert!(replaced_content.contains(&format!("url(#{})", ids[0])));
+        assert!(replaced_content.contains(&format!("url(#{})", ids[1])));
+    }
+
+    #[test]
+    fn skin_ids() {
+        let content = r#"
+```wavedrom
+{ "signal": [{ "wave": "x.1" }] }
+```
+        "#;
+
+        let dark = Config {
+            options: wavedrom::skin::builtin::dark().options(),
+            ..Config::default()
+        };
+
+        // The same diagram with another skin can be on the same page, e.g. in a theme switcher
+        let default_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &Config::default(), Err)
+                .unwrap();
+        let dark_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &dark, Err).unwrap();
+
+        let default_ids = pattern_ids(&default_content);
+        let dark_ids = pattern_ids(&dark_content);
+        assert_eq!(default_ids.len(), 1);
+        assert_eq!(dark_ids.len(), 1);
+        assert_ne!(default_ids[0], dark_ids[0]);
+    }
+
+    #[test]
+    fn register_insertion() {
+        let content = r#"
+```wavedrom
+{ reg: [{ bits: 8, name: "data" }] }
+```
+        "#;
+
+        let replaced_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &Config::default(), Err)
+                .unwrap();
+
+        assert!(replaced_content.contains(r#"<pre class="wavedrom"><svg"#));
+        assert!(replaced_content.contains("<tspan>data</tspan>"));
+    }
+
+    #[test]
+    fn configured_wrapper() {
+        let content = r#"
+```wavedrom
+{ signal: [{ wave: "01" }] }
+```
+        "#;
+
+        let config = Config {
+            with_source: true,
+            element: "div".to_string(),
+            class: "diagram".to_string(),
+            ..Config::default()
+        };
+        let replaced_content =
+            insert_wavedrom_with(content, Path::new("chapter.md"), &config, Err).unwrap();
+
+        assert!(replaced_content.contains(r#"<div class="diagram"><svg"#));
+        assert!(replaced_content
+            .ends_with("</svg></div>\n\n```json\n{ signal: [{ wave: \"01\" }] }\n```\n        "));
+    }
+
+    #[test]
+    fn files_output() {
+        let content = r#"
+```wavedrom
+{ signal: [{ name: "clk", wave: "p.." }, { name: "a&b", wave: "01." }], head: { text: "Bus" } }
+```
+        "#;
+
+        let src_dir = std::env::temp_dir().join(format!("mdbook-wavedrom-{}", std::process::id()));
+        let config = Config {
+            output: Output::Files {
+                src_dir: src_dir.clone(),
+            },
+            ..Config::default()
+        };
+        let replaced_content =
+            insert_wavedrom_with(content, Path::new("part/chapter.md"), &config, Err).unwrap();
+
+        let files = || {
+            std::fs::read_dir(src_dir.join("wavedrom"))
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect::<Vec<_>>()
+        };
+        let file_names = files();
+        assert_eq!(file_names.len(), 1);
+        assert!(replaced_content.contains(&format!(
+            r#"<pre class="wavedrom"><img src="../wavedrom/{}" alt="Timing diagram: Bus with clk, a&amp;b"></pre>"#,
+            file_names[0]
+        )));
+
+        // An unchanged diagram does not touch its file again
+        let path = src_dir.join("wavedrom").join(&file_names[0]);
+        let modified = || std::fs::metadata(&path).unwrap().modified().unwrap();
+        let first_modified = modified();
+        std::thread::sleep(std::time::Duration::from_millis(50));
+        let second_content =
+            insert_wavedrom_with(content, Path::new("part/chapter.md"), &config, Err).unwrap();
+        assert_eq!(second_content, replaced_content);
+        assert_eq!(modified(), first_modified);
+
+        // Only the diagram files that no chapter refers to are removed
+        let wavedrom_dir = src_dir.join("wavedrom");
+        std::fs::write(wavedrom_dir.join("0123456789abcdef.svg"), "<svg></svg>").unwrap();
+        std::fs::write(wavedrom_dir.join("notes.txt"), "").unwrap();
+        remove_unused_files(&src_dir, [replaced_content.as_str()]).unwrap();
+
+        let mut remaining = files();
+        remaining.sort();
+        std::fs::remove_dir_all(&src_dir).unwrap();
+        assert_eq!(remaining, [file_names[0].clone(), "notes.txt".to_string()]);
+    }
+
+    #[test]
+    fn error_line() {
+        let content = r#"# Header
+
+Text
+
+```wavedrom
+{
+    signal: [
+        { wave: "01", data: 5 },
+    ]
+}
+```
+"#;
+
+        let error = insert_wavedrom_with(content, Path::new("chapter.md"), &Config::default(), Err)
+            .unwrap_err();
+
+        assert_eq!(error.line(), 5);
+        let InsertionError::Json(json_error) = error.error() else {
+            panic!("expected a json error");
+        };
+        assert_eq!(json_error.location().line, 8);
+
+        // Without a callback, the first error stops the insertion
+        assert!(matches!(
+            insert_wavedrom(content, &wavedrom::Options::default()),
+            Err(InsertionError::Json(_))
+        ));
+
+        let replaced_content = insert_wavedrom_with(
+            content,
+            Path::new("chapter.md"),
+            &Config::default(),
+            |err| Ok(inline_error(&err)),
+        )
+        .unwrap();
+
+        assert!(replaced_content.contains(r#"<pre class="wavedrom-error""#));
+        assert!(replaced_content.contains("8 |         { wave: \"01\", data: 5 },"));
+    }
 }
@@ -69,11 +69,24 @@ fn handle_supports(pre: &dyn Preprocessor, sub_args: &ArgMatches) -> ! {
 
 mod nop_lib {
     use mdbook::BookItem;
-    use mdbook_wavedrom_rs::insert_wavedrom;
-    use wavedrom::skin::Skin;
+    use mdbook_wavedrom_rs::{
+        inline_error, insert_wavedrom_with, remove_unused_files, Config, Output,
+    };
+    use std::path::Path;
+    use wavedrom::skin::{builtin, Skin};
 
     use super::*;
 
+    /// What to do with a diagram that fails to render
+    #[derive(Debug, Default, Clone, Copy)]
+    enum OnError {
+        /// Stop building the book
+        #[default]
+        Fail,
+        /// Show the error in place of the diagram
+        Inline,
+    }
+
     pub struct WavedromPreProcessor;
 
     impl WavedromPreProcessor {
@@ -82,53 +95,184 @@ mod nop_lib {
         }
     }
 
+    /// Read the `[preprocessor.wavedrom-rs]` table of `book.toml` for the book at `root` that is
+    /// built by `renderer`.
+    fn read_config(
+        book_config: &mdbook::Config,
+        renderer: &str,
+        root: &Path,
+    ) -> Result<(Config, OnError), Error> {
+        let mut config = Config::default();
+        let mut on_error = OnError::default();
+
+        // The preprocessor is usually registered as `wavedrom-rs`, but also accept `wavedrom`
+        let table = book_config.get_preprocessor("wavedrom-rs");
+        let alias = book_config.get_preprocessor("wavedrom");
+        if table.is_some() && alias.is_some() {
+            return Err(Error::msg(
+                "Both `[preprocessor.wavedrom-rs]` and `[preprocessor.wavedrom]` are configured. \
+                 Remove one of them.",
+            ));
+        }
+
+        let Some(table) = table.or(alias) else {
+            return Ok((config, on_error));
+        };
+
+        // Keys in `[preprocessor.wavedrom-rs.renderer.<name>]` replace the top-level keys for that
+        // renderer
+        let overrides = table
+            .get("renderer")
+            .and_then(|renderers| renderers.get(renderer));
+        let get = |key: &str| {
+            overrides
+                .and_then(|overrides| overrides.get(key))
+                .or_else(|| table.get(key))
+        };
+        let get_str = |key: &str| -> Result<Option<&str>, Error> {
+            get(key)
+                .map(|value| {
+                    value
+                        .as_str()
+                        .ok_or_else(|| Error::msg(format!("WaveDrom `{key}` should be a string")))
+                })
+                .transpose()
+        };
+
+        if let Some(skin) = get_str("skin")? {
+            // The skin is either the name of a built-in skin or a path to a skin file
+            if let Some(skin) = builtin::get(skin) {
+                config.options.merge_in(skin.0);
+            } else {
+                let skin_path = root.join(skin);
+                let skin = std::fs::read_to_string(&skin_path).map_err(|err| {
+                    Error::msg(format!(
+                        "Failed to read content from WaveDrom skin file '{}'. Reason: {err}",
+                        skin_path.display()
+                    ))
+                })?;
+                let skin = Skin::from_json5(&skin).map_err(|err| {
+                    Error::msg(format!(
+                        "Failed to parse WaveDrom skin content. Reason: {err}"
+                    ))
+                })?;
+                config.options.merge_in(skin.0);
+            }
+        }
+
+        if let Some(value) = get("with-source") {
+            config.with_source = value
+                .as_bool()
+                .ok_or_else(|| Error::msg("WaveDrom `with-source` should be a boolean"))?;
+        }
+
+        if let Some(element) = get_str("element")? {
+            if element.is_empty()
+                || !element
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '-')
+            {
+                return Err(Error::msg(format!(
+                    "WaveDrom `element` should be an element name, got \"{element}\""
+                )));
+            }
+            config.element = element.to_string();
+        }
+
+        if let Some(class) = get_str("class")? {
+            config.class = class.to_string();
+        }
+
+        if let Some(output) = get_str("output")? {
+            config.output = match output {
+                "inline" => Output::Inline,
+                "files" => Output::Files {
+                    src_dir: root.join(&book_config.book.src),
+                },
+                _ => {
+                    return Err(Error::msg(
+                        "WaveDrom `output` should be \"inline\" or \"files\"",
+                    ))
+                }
+            };
+        }
+
+        if let Some(value) = get_str("on-error")? {
+            on_error = match value {
+                "fail" => OnError::Fail,
+                "inline" => OnError::Inline,
+                _ => {
+                    return Err(Error::msg(
+                        "WaveDrom `on-error` should be \"fail\" or \"inline\"",
+                    ))
+                }
+            };
+        }
+
+        Ok((config, on_error))
+    }
+
     impl Preprocessor for WavedromPreProcessor {
         fn name(&self) -> &str {
             "wavedrom"
         }
 
         fn run(&self, ctx: &PreprocessorContext, mut book: Book) -> Result<Book, Error> {
-            let mut options = wavedrom::Options::default();
+            let (config, on_error) = read_config(&ctx.config, &ctx.renderer, &ctx.root)?;
 
-            if let Some(config) = ctx.config.get_preprocessor(self.name()) {
-                if let Some(skin_path) = config.get("skin") {
-                    let Some(skin_path) = skin_path.as_str() else {
-                        eprintln!("[ERROR]: WaveDrom skin has invalid value type");
-                        std::process::exit(1)
-                    };
+            let mut error = None;
+            book.for_each_mut(|item| match item {
+                BookItem::Separator | BookItem::PartTitle(_) => {}
+                BookItem::Chapter(_) if error.is_some() => {}
+                BookItem::Chapter(chapter) => {
+                    let path = chapter
+                        .source_path
+                        .as_ref()
+                        .map_or_else(|| chapter.name.clone(), |path| path.display().to_string());
 
-                    let skin = match std::fs::read_to_string(skin_path) {
-                        Ok(content) => content,
-                        Err(err) => {
-                            eprintln!("[ERROR]: Failed to read content from WaveDrom skin file. Reason: {err}");
-                            std::process::exit(1);
-                        }
-                    };
+                    // Draft chapters have no path, but they have no content either
+                    let chapter_path = chapter.path.as_deref().unwrap_or(Path::new(""));
 
-                    match Skin::from_json5(&skin) {
-                        Ok(skin) => options.merge_in(skin.0),
-                        Err(err) => {
-                            eprintln!(
-                                "[ERROR]: Failed to parse WaveDrom skin content. Reason: {err}"
-                            );
-                            std::process::exit(1);
+                    let result = insert_wavedrom_with(&chapter.content, chapter_path, &config, |err| {
+                        match on_error {
+                            OnError::Fail => Err(err),
+                            OnError::Inline => {
+                                eprintln!(
+                                    "[WARNING]: Failed to render WaveDrom diagram in '{path}'. Reason:\n{err}"
+                                );
+                                Ok(inline_error(&err))
+                            }
                         }
-                    }
-                }
-            }
+                    });
 
-            book.for_each_mut(|item| match item {
-                BookItem::Separator | BookItem::PartTitle(_) => {}
-                BookItem::Chapter(chapter) => {
-                    match insert_wavedrom(&chapter.content, &options) {
+                    match result {
                         Ok(new_content) => chapter.content = new_content,
                         Err(err) => {
-                            eprintln!("Failed to render wavedrom. Reason: {err}");
+                            error = Some(Error::msg(format!(
+                                "Failed to render WaveDrom diagram in '{path}'. Reason:\n{err}"
+                            )));
                         }
                     }
                 }
             });
 
+            if let Some(error) = error {
+                return Err(error);
+            }
+
+            // Diagrams that changed or were removed leave their old files behind
+            if let Output::Files { src_dir } = &config.output {
+                let chapters = book.iter().filter_map(|item| match item {
+                    BookItem::Chapter(chapter) => Some(chapter.content.as_str()),
+                    BookItem::Separator | BookItem::PartTitle(_) => None,
+                });
+                remove_unused_files(src_dir, chapters).map_err(|err| {
+                    Error::msg(format!(
+                        "Failed to remove unused WaveDrom diagram files. Reason: {err}"
+                    ))
+                })?;
+            }
+
             Ok(book)
         }
 
@@ -136,4 +280,58 @@ mod nop_lib {
             renderer != "not-supported"
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn read(book_toml: &str, renderer: &str) -> Result<(Config, OnError), Error> {
+            read_config(&book_toml.parse().unwrap(), renderer, Path::new("book"))
+        }
+
+        #[test]
+        fn renderer_overrides() {
+            let book_toml = r#"
+[preprocessor.wavedrom-rs]
+skin = "dark"
+with-source = true
+
+[preprocessor.wavedrom-rs.renderer.pdf]
+skin = "print"
+on-error = "inline"
+"#;
+
+            let (html, on_error) = read(book_toml, "html").unwrap();
+            assert!(html.with_source);
+            assert!(matches!(on_error, OnError::Fail));
+            assert_eq!(
+                html.options.background,
+                builtin::dark().options().background
+            );
+
+            let (pdf, on_error) = read(book_toml, "pdf").unwrap();
+            assert!(pdf.with_source);
+            assert!(matches!(on_error, OnError::Inline));
+            assert_eq!(
+                pdf.options.background,
+                builtin::print().options().background
+            );
+            assert_ne!(html.options.background, pdf.options.background);
+        }
+
+        #[test]
+        fn preprocessor_tables() {
+            let (config, _) = read("[preprocessor.wavedrom]\nclass = \"diagram\"", "html").unwrap();
+            assert_eq!(config.class, "diagram");
+
+            let (config, _) = read("", "html").unwrap();
+            assert_eq!(config.class, Config::default().class);
+
+            assert!(read(
+                "[preprocessor.wavedrom-rs]\n[preprocessor.wavedrom]",
+                "html"
+            )
+            .is_err());
+        }
+    }
 }
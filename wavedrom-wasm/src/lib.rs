@@ -1,5 +1,6 @@
 use std::alloc::Layout;
 
+use wavedrom::skin::SkinRegistry;
 use wavedrom::wavejson::WaveJson;
 use wavedrom::Figure;
 
@@ -39,7 +40,11 @@ fn render_internal(json: &str) -> Result<Vec<u8>, RenderError> {
     let mut buffer = vec![0; 9];
 
     {
-        let options = &*OPTIONS.lock().unwrap();
+        let mut options = SkinRegistry::builtin().figure_options(&figure, &OPTIONS.lock().unwrap());
+        // Every figure on a page needs its own ids
+        let id_prefix = wavedrom::Options::id_prefix_for(json, &options);
+        options.id_prefix.push_str(&id_prefix);
+        let options = &options;
         match figure {
             Figure::Signal(figure) => {
                 let Ok(_) = figure
@@ -54,6 +59,11 @@ fn render_internal(json: &str) -> Result<Vec<u8>, RenderError> {
                     return Err(RenderError::WriteError);
                 };
             }
+            Figure::Assign(figure) => {
+                let Ok(_) = figure.write_svg_with_options(&mut buffer, options) else {
+                    return Err(RenderError::WriteError);
+                };
+            }
         }
     }
 
@@ -1,6 +1,6 @@
 use std::sync::{LazyLock, Mutex};
 
-use wavedrom::skin::Skin;
+use wavedrom::skin::{builtin, Skin};
 use wavedrom::Color;
 use wavedrom::Options;
 
@@ -210,8 +210,10 @@ pub static OPTIONS: LazyLock<Mutex<Options>> = LazyLock::new(|| Mutex::new(Optio
 
 #[inline]
 pub fn merge_in_skin_internal(json: &str) -> Result<(), ()> {
-    let Ok(skin) = Skin::from_json5(json) else {
-        return Err(());
+    // Besides the contents of a skin file, the name of a built-in skin is also accepted
+    let skin = match builtin::get(json.trim()) {
+        Some(skin) => skin,
+        None => Skin::from_json5(json).map_err(|_| ())?,
     };
 
     OPTIONS.lock().unwrap().merge_in(skin.0);
@@ -0,0 +1,222 @@
+//! The logic to render logic-circuit schematics
+//!
+//! An [`AssignFigure`] contains a set of [`Assignment`]s. Every assignment drives a named output
+//! with a tree of logic gates. The gates are drawn from left to right, with the inputs on the left
+//! and the named output on the right.
+
+pub mod options;
+mod render;
+
+/// A figure containing a set of logic-circuit [`Assignment`]s
+#[derive(Debug, Clone, Default)]
+pub struct AssignFigure {
+    assignments: Vec<Assignment>,
+}
+
+/// An assignment of a gate expression to a named output
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    output: String,
+    expr: GateExpr,
+}
+
+/// An expression that consists of a tree of gates with named inputs as leaves
+#[derive(Debug, Clone)]
+pub enum GateExpr {
+    /// A named input signal
+    Input(String),
+    /// A gate with a set of input expressions
+    Gate(Gate),
+}
+
+/// A logic gate with its set of inputs
+#[derive(Debug, Clone)]
+pub struct Gate {
+    kind: GateKind,
+    inputs: Vec<GateExpr>,
+}
+
+/// The type of a logic gate
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GateKind {
+    /// Inverter. Operator is `~`.
+    Not,
+    /// Buffer. Operator is `=`.
+    Buffer,
+    /// Logical and. Operator is `&`.
+    And,
+    /// Logical nand. Operator is `~&`.
+    Nand,
+    /// Logical or. Operator is `|`.
+    Or,
+    /// Logical nor. Operator is `~|`.
+    Nor,
+    /// Logical exclusive or. Operator is `^`.
+    Xor,
+    /// Logical exclusive nor. Operator is `~^`.
+    Xnor,
+    /// A box with a custom name. Any other operator.
+    Named(String),
+}
+
+impl AssignFigure {
+    /// Create a new empty [`AssignFigure`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`AssignFigure`] with a set of [`Assignment`]s.
+    #[inline]
+    pub fn with(assignments: Vec<Assignment>) -> Self {
+        Self { assignments }
+    }
+
+    /// Add an [`Assignment`] to the [`AssignFigure`].
+    #[inline]
+    pub fn add_assignment(mut self, assignment: Assignment) -> Self {
+        self.assignments.push(assignment);
+        self
+    }
+
+    /// Add a set of [`Assignment`]s to the [`AssignFigure`].
+    #[inline]
+    pub fn add_assignments(mut self, assignments: impl IntoIterator<Item = Assignment>) -> Self {
+        self.assignments.extend(assignments);
+        self
+    }
+
+    /// Returns the [`Assignment`]s of the [`AssignFigure`].
+    #[inline]
+    pub fn assignments(&self) -> &[Assignment] {
+        &self.assignments
+    }
+}
+
+impl Assignment {
+    /// Create a new [`Assignment`] that drives `output` with `expr`.
+    #[inline]
+    pub fn new(output: impl Into<String>, expr: GateExpr) -> Self {
+        Self {
+            output: output.into(),
+            expr,
+        }
+    }
+
+    /// Returns the name of the output of the [`Assignment`].
+    #[inline]
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Returns the expression that drives the output of the [`Assignment`].
+    #[inline]
+    pub fn expr(&self) -> &GateExpr {
+        &self.expr
+    }
+}
+
+impl GateExpr {
+    /// Create a new named input [`GateExpr`].
+    #[inline]
+    pub fn input(name: impl Into<String>) -> Self {
+        Self::Input(name.into())
+    }
+
+    /// Create a new [`GateExpr`] for a gate of `kind` with a set of `inputs`.
+    #[inline]
+    pub fn gate(kind: GateKind, inputs: impl IntoIterator<Item = GateExpr>) -> Self {
+        Self::Gate(Gate::new(kind, inputs))
+    }
+
+    /// Returns the amount of gate levels between the inputs and the output of the expression.
+    pub fn depth(&self) -> u32 {
+        match self {
+            Self::Input(_) => 0,
+            Self::Gate(gate) => {
+                1 + gate
+                    .inputs
+                    .iter()
+                    .map(GateExpr::depth)
+                    .max()
+                    .unwrap_or_default()
+            }
+        }
+    }
+
+    /// Returns the amount of named inputs of the expression.
+    pub fn num_leaves(&self) -> u32 {
+        match self {
+            Self::Input(_) => 1,
+            Self::Gate(gate) => gate
+                .inputs
+                .iter()
+                .map(GateExpr::num_leaves)
+                .sum::<u32>()
+                .max(1),
+        }
+    }
+}
+
+impl Gate {
+    /// Create a new [`Gate`] of `kind` with a set of `inputs`.
+    #[inline]
+    pub fn new(kind: GateKind, inputs: impl IntoIterator<Item = GateExpr>) -> Self {
+        Self {
+            kind,
+            inputs: inputs.into_iter().collect(),
+        }
+    }
+
+    /// Returns the type of the [`Gate`].
+    #[inline]
+    pub fn kind(&self) -> &GateKind {
+        &self.kind
+    }
+
+    /// Returns the inputs of the [`Gate`].
+    #[inline]
+    pub fn inputs(&self) -> &[GateExpr] {
+        &self.inputs
+    }
+}
+
+impl GateKind {
+    /// Create a [`GateKind`] from a WaveDrom operator.
+    ///
+    /// Any operator that is not known is seen as a [`GateKind::Named`] box.
+    pub fn from_operator(op: &str) -> Self {
+        match op {
+            "~" => Self::Not,
+            "=" => Self::Buffer,
+            "&" => Self::And,
+            "~&" => Self::Nand,
+            "|" => Self::Or,
+            "~|" => Self::Nor,
+            "^" => Self::Xor,
+            "~^" => Self::Xnor,
+            _ => Self::Named(op.to_string()),
+        }
+    }
+
+    /// Returns the WaveDrom operator for the [`GateKind`].
+    pub fn operator(&self) -> &str {
+        match self {
+            Self::Not => "~",
+            Self::Buffer => "=",
+            Self::And => "&",
+            Self::Nand => "~&",
+            Self::Or => "|",
+            Self::Nor => "~|",
+            Self::Xor => "^",
+            Self::Xnor => "~^",
+            Self::Named(name) => name,
+        }
+    }
+
+    /// Returns whether the output of the gate is inverted.
+    #[inline]
+    pub fn is_inverted(&self) -> bool {
+        matches!(self, Self::Not | Self::Nand | Self::Nor | Self::Xnor)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::Color;
+
+define_options! {
+    /// The options used while rendering an assign figure
+    AssignOptions,
+
+    /// A subset of the [`AssignOptions`]
+    PartialAssignOptions {
+        /// The width of a single gate
+        gate_width: u32 => 32,
+        /// The vertical distance between two inputs. A gate is this high for every input it has.
+        input_spacing: u32 => 20,
+        /// The horizontal distance between two gate levels
+        level_spacing: u32 => 32,
+        /// The vertical distance between two assignments
+        assignment_spacing: u32 => 16,
+        /// The radius of the inversion bubble
+        bubble_radius: u32 => 3,
+
+        /// The spacing between a label and its wire
+        label_spacing: u32 => 4,
+        /// The font size of the input and output labels
+        label_fontsize: u32 => 14,
+        /// The color of the input and output labels
+        label_color: Color => Color::BLACK,
+        /// The font size of the text in named gates
+        gate_fontsize: u32 => 12,
+
+        /// The line color of wires and gates
+        line_color: Color => Color::BLACK,
+        /// The fill color of gates
+        gate_fill: Color => Color::WHITE,
+    }
+}
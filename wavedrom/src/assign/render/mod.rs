@@ -0,0 +1,542 @@
+use std::io;
+
+use crate::backend::{
+    to_display_num, Backend, EpsBackend, Path, PathStyle, PdfBackend, SvgBackend, TextAnchor,
+    TextBaseline, TextLayout, KAPPA,
+};
+use crate::text::RichText;
+use crate::{Font, Options};
+
+use super::{AssignFigure, Gate, GateExpr, GateKind};
+
+/// The positions of the inputs and outputs and the size of a figure
+struct Extents {
+    /// The start of the input wires
+    inputs_x: f64,
+    /// The start of the output wires
+    outputs_x: f64,
+    figure_width: f64,
+    figure_height: f64,
+}
+
+/// A shape that is drawn on top of the wires
+enum Shape {
+    Path(Path, PathStyle),
+    Text((f64, f64), RichText, TextLayout<'static>),
+}
+
+/// The state that is kept while laying out a single assignment.
+struct Layout<'a> {
+    options: &'a Options,
+
+    inputs_x: f64,
+    top: f64,
+    next_leaf: u32,
+
+    wires: Vec<Path>,
+    gates: Vec<Shape>,
+}
+
+impl AssignFigure {
+    /// Render a [`AssignFigure`] into a `writer`.
+    #[inline]
+    pub fn write_svg(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_svg_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` with a set of options.
+    pub fn write_svg_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(
+            &mut SvgBackend::new(writer).id_prefix(&options.id_prefix),
+            options,
+        )
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as a PDF document.
+    #[inline]
+    pub fn write_pdf(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_pdf_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as a PDF document with a set of options.
+    pub fn write_pdf_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut PdfBackend::new(writer), options)
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as an Encapsulated PostScript file.
+    #[inline]
+    pub fn write_eps(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_eps_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as an Encapsulated PostScript file with a set of
+    /// options.
+    pub fn write_eps_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut EpsBackend::new(writer), options)
+    }
+
+    /// Render a [`AssignFigure`] with a [`Backend`].
+    #[inline]
+    pub fn render(&self, backend: &mut impl Backend) -> io::Result<()> {
+        self.render_with_options(backend, &Options::default())
+    }
+
+    /// Render a [`AssignFigure`] with a [`Backend`] and a set of options.
+    pub fn render_with_options(
+        &self,
+        backend: &mut impl Backend,
+        options: &Options,
+    ) -> io::Result<()> {
+        let assign = &options.assign;
+        let padding = &options.padding;
+
+        let Extents {
+            inputs_x,
+            outputs_x,
+            figure_width,
+            figure_height,
+        } = self.extents(options);
+
+        backend.begin(figure_width, figure_height, options.background)?;
+
+        let wire_style = PathStyle::new().stroke(assign.line_color, 1);
+
+        let mut top = f64::from(padding.figure_top);
+        for assignment in &self.assignments {
+            let mut layout = Layout {
+                options,
+                inputs_x,
+                top,
+                next_leaf: 0,
+                wires: Vec::new(),
+                gates: Vec::new(),
+            };
+
+            let (x, y, _) = layout.place(&assignment.expr);
+
+            layout.wires.push(
+                Path::new()
+                    .move_to(to_display_num(x), to_display_num(y))
+                    .horizontal_to(to_display_num(outputs_x)),
+            );
+            layout.gates.push(Shape::Text(
+                (
+                    to_display_num(outputs_x + f64::from(assign.label_spacing)),
+                    to_display_num(y),
+                ),
+                RichText::from(assignment.output.as_str()),
+                label_layout(options, assign.label_fontsize, TextAnchor::Start),
+            ));
+
+            for wire in &layout.wires {
+                backend.draw_path(wire, &wire_style)?;
+            }
+            for shape in &layout.gates {
+                match shape {
+                    Shape::Path(path, style) => backend.draw_path(path, style)?,
+                    Shape::Text(at, text, text_layout) => {
+                        backend.draw_text(*at, text, text_layout)?
+                    }
+                }
+            }
+
+            top += f64::from(
+                assignment.expr.num_leaves() * assign.input_spacing + assign.assignment_spacing,
+            );
+        }
+
+        backend.finish()
+    }
+
+    /// Returns the positions of the inputs and outputs and the size of the figure.
+    fn extents(&self, options: &Options) -> Extents {
+        let assign = &options.assign;
+        let padding = &options.padding;
+
+        let font = Font::default();
+
+        let mut input_label_width = 0;
+        for assignment in &self.assignments {
+            assignment.expr.for_each_input(&mut |name| {
+                input_label_width = u32::max(
+                    input_label_width,
+                    font.get_text_width(name, assign.label_fontsize),
+                );
+            });
+        }
+        let output_label_width = self
+            .assignments
+            .iter()
+            .map(|assignment| font.get_text_width(&assignment.output, assign.label_fontsize))
+            .max()
+            .unwrap_or_default();
+
+        let max_depth = self
+            .assignments
+            .iter()
+            .map(|assignment| assignment.expr.depth())
+            .max()
+            .unwrap_or_default();
+
+        let inputs_x = f64::from(padding.figure_left + input_label_width + assign.label_spacing);
+        let outputs_x = inputs_x + output_x_offset(max_depth, options);
+
+        let schematic_height = self
+            .assignments
+            .iter()
+            .map(|assignment| assignment.expr.num_leaves() * assign.input_spacing)
+            .sum::<u32>()
+            + self.assignments.len().saturating_sub(1) as u32 * assign.assignment_spacing;
+
+        let figure_width = to_display_num(
+            outputs_x + f64::from(assign.label_spacing + output_label_width + padding.figure_right),
+        );
+        let figure_height = padding.figure_top + schematic_height + padding.figure_bottom;
+
+        Extents {
+            inputs_x,
+            outputs_x,
+            figure_width,
+            figure_height: f64::from(figure_height),
+        }
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as a PNG image.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    #[inline]
+    pub fn write_png(&self, writer: &mut impl io::Write, scale: f32) -> io::Result<()> {
+        self.write_png_with_options(writer, &Options::default(), scale)
+    }
+
+    /// Render a [`AssignFigure`] into a `writer` as a PNG image with a set of options.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    pub fn write_png_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+        scale: f32,
+    ) -> io::Result<()> {
+        let mut svg = Vec::new();
+        self.write_svg_with_options(&mut svg, options)?;
+        crate::png::write_png(&svg, writer, scale)
+    }
+}
+
+/// The distance between the start of the input wires and the start of the output wires.
+fn output_x_offset(depth: u32, options: &Options) -> f64 {
+    let assign = &options.assign;
+    f64::from((depth + 1) * assign.level_spacing + depth * assign.gate_width)
+}
+
+impl GateExpr {
+    fn for_each_input(&self, f: &mut impl FnMut(&str)) {
+        match self {
+            Self::Input(name) => f(name),
+            Self::Gate(gate) => {
+                for input in &gate.inputs {
+                    input.for_each_input(f);
+                }
+            }
+        }
+    }
+}
+
+/// Returns the layout of a label text that is vertically centered on its point.
+fn label_layout(options: &Options, font_size: u32, anchor: TextAnchor) -> TextLayout<'static> {
+    TextLayout::new(font_size, options.assign.label_color)
+        .anchor(anchor)
+        .baseline(TextBaseline::Middle)
+}
+
+impl Layout<'_> {
+    /// Place an expression and return the point where its output is together with its
+    /// [depth][GateExpr::depth].
+    fn place(&mut self, expr: &GateExpr) -> (f64, f64, u32) {
+        match expr {
+            GateExpr::Input(name) => {
+                let (x, y) = self.place_input(name);
+                (x, y, 0)
+            }
+            GateExpr::Gate(gate) => self.place_gate(gate),
+        }
+    }
+
+    fn place_input(&mut self, name: &str) -> (f64, f64) {
+        let assign = &self.options.assign;
+
+        let y = self.top
+            + f64::from(self.next_leaf * assign.input_spacing)
+            + f64::from(assign.input_spacing) / 2.;
+        self.next_leaf += 1;
+
+        if !name.is_empty() {
+            self.gates.push(Shape::Text(
+                (
+                    to_display_num(self.inputs_x - f64::from(assign.label_spacing)),
+                    to_display_num(y),
+                ),
+                RichText::from(name),
+                label_layout(self.options, assign.label_fontsize, TextAnchor::End),
+            ));
+        }
+
+        (self.inputs_x, y)
+    }
+
+    fn place_gate(&mut self, gate: &Gate) -> (f64, f64, u32) {
+        let assign = &self.options.assign;
+
+        // The depth of the gate follows from the depths of its inputs, which are placed first
+        let mut depth = 1;
+        let input_points = if gate.inputs.is_empty() {
+            // A gate without inputs still needs to take up some space
+            let (_, y) = self.place_input("");
+            vec![(None, y)]
+        } else {
+            gate.inputs
+                .iter()
+                .map(|input| {
+                    let (x, y, input_depth) = self.place(input);
+                    depth = depth.max(input_depth + 1);
+                    (Some(x), y)
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let num_inputs = input_points.len() as u32;
+        let first_y = input_points.first().map_or(0., |(_, y)| *y);
+        let last_y = input_points.last().map_or(0., |(_, y)| *y);
+
+        let center_y = (first_y + last_y) / 2.;
+        let width = f64::from(assign.gate_width);
+        let spacing = f64::from(assign.input_spacing);
+        // Leave a small margin so that gates on adjacent rows do not touch
+        let height = f64::from(num_inputs) * spacing - spacing / 4.;
+        let x = self.inputs_x + output_x_offset(depth - 1, self.options);
+        let y = center_y - height / 2.;
+
+        // Draw the wires towards the gate inputs
+        for (i, (from_x, from_y)) in input_points.into_iter().enumerate() {
+            let Some(from_x) = from_x else {
+                continue;
+            };
+
+            let pin_y = center_y + (f64::from(i as u32) - f64::from(num_inputs - 1) / 2.) * spacing;
+
+            let mut wire = Path::new().move_to(to_display_num(from_x), to_display_num(from_y));
+            if from_y != pin_y {
+                wire = wire
+                    .horizontal_to(to_display_num(x - f64::from(assign.level_spacing) / 2.))
+                    .vertical_to(to_display_num(pin_y));
+            }
+            self.wires.push(wire.horizontal_to(to_display_num(x)));
+        }
+
+        self.write_gate_shape(&gate.kind, x, y, width, height);
+
+        let mut out_x = x + width;
+
+        if gate.kind.is_inverted() {
+            let radius = f64::from(assign.bubble_radius);
+            self.gates.push(Shape::Path(
+                Path::circle(
+                    to_display_num(out_x + radius),
+                    to_display_num(center_y),
+                    radius,
+                ),
+                PathStyle::new()
+                    .fill(assign.gate_fill)
+                    .stroke(assign.line_color, 1),
+            ));
+
+            out_x += 2. * radius;
+        }
+
+        (out_x, center_y, depth)
+    }
+
+    fn write_gate_shape(&mut self, kind: &GateKind, x: f64, y: f64, width: f64, height: f64) {
+        let assign = &self.options.assign;
+
+        let style = PathStyle::new()
+            .fill(assign.gate_fill)
+            .stroke(assign.line_color, 1);
+
+        let (x, y, w, h) = (
+            to_display_num(x),
+            to_display_num(y),
+            to_display_num(width),
+            to_display_num(height),
+        );
+        let center_y = to_display_num(y + h / 2.);
+        let bottom = to_display_num(y + h);
+
+        match kind {
+            GateKind::Not | GateKind::Buffer => {
+                let path = Path::new()
+                    .move_to(x, y)
+                    .line_to(to_display_num(x + w), center_y)
+                    .line_to(x, bottom)
+                    .close();
+
+                self.gates.push(Shape::Path(path, style));
+            }
+            GateKind::And | GateKind::Nand => {
+                // The front of the gate is half an ellipse made of two quarters
+                let (rx, ry) = (to_display_num(w / 2.), to_display_num(h / 2.));
+                let cx = x + rx;
+                let path = Path::new()
+                    .move_to(x, y)
+                    .horizontal_to(cx)
+                    .curve_to(
+                        (cx + KAPPA * rx, y),
+                        (cx + rx, center_y - KAPPA * ry),
+                        (cx + rx, center_y),
+                    )
+                    .curve_to(
+                        (cx + rx, center_y + KAPPA * ry),
+                        (cx + KAPPA * rx, bottom),
+                        (cx, bottom),
+                    )
+                    .horizontal_to(x)
+                    .close();
+
+                self.gates.push(Shape::Path(path, style));
+            }
+            GateKind::Or | GateKind::Nor | GateKind::Xor | GateKind::Xnor => {
+                let cx = to_display_num(x + w * 0.8);
+                let back_x = to_display_num(x + w / 4.);
+                let path = Path::new()
+                    .move_to(x, y)
+                    .horizontal_by(to_display_num(w / 4.))
+                    .quad_to((cx, y), (to_display_num(x + w), center_y))
+                    .quad_to((cx, bottom), (back_x, bottom))
+                    .horizontal_to(x)
+                    .quad_to((back_x, center_y), (x, y))
+                    .close();
+
+                self.gates.push(Shape::Path(path, style));
+
+                if matches!(kind, GateKind::Xor | GateKind::Xnor) {
+                    let ex = to_display_num(x - 4.);
+                    let path = Path::new()
+                        .move_to(ex, bottom)
+                        .quad_to((to_display_num(x + w / 4. - 4.), center_y), (ex, y));
+
+                    self.gates.push(Shape::Path(
+                        path,
+                        PathStyle::new().stroke(assign.line_color, 1),
+                    ));
+                }
+            }
+            GateKind::Named(name) => {
+                self.gates.push(Shape::Path(Path::rect(x, y, w, h), style));
+
+                if !name.is_empty() {
+                    self.gates.push(Shape::Text(
+                        (to_display_num(x + w / 2.), center_y),
+                        RichText::from(name.as_str()),
+                        label_layout(self.options, assign.gate_fontsize, TextAnchor::Middle),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(options: &Options) -> Layout<'_> {
+        Layout {
+            options,
+            inputs_x: 0.,
+            top: 0.,
+            next_leaf: 0,
+            wires: Vec::new(),
+            gates: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn gate_depth() {
+        let options = Options::default();
+        let assign = &options.assign;
+
+        // The `and` gate is two levels deep, because its second input is an `or` gate
+        let expr = GateExpr::gate(
+            GateKind::And,
+            [
+                GateExpr::input("a"),
+                GateExpr::gate(GateKind::Or, [GateExpr::input("b"), GateExpr::input("c")]),
+            ],
+        );
+        assert_eq!(expr.depth(), 2);
+        assert_eq!(expr.num_leaves(), 3);
+
+        let mut layout = layout(&options);
+        let (x, y, depth) = layout.place(&expr);
+
+        assert_eq!(depth, 2);
+        assert_eq!(layout.next_leaf, 3);
+        assert_eq!(
+            x,
+            output_x_offset(1, &options) + f64::from(assign.gate_width)
+        );
+        // The output is centered between the `a` input and the output of the `or` gate
+        assert_eq!(y, 1.25 * f64::from(assign.input_spacing));
+    }
+
+    #[test]
+    fn inverted_gate() {
+        let options = Options::default();
+        let assign = &options.assign;
+
+        let expr = GateExpr::gate(GateKind::Not, [GateExpr::input("a")]);
+
+        let mut layout = layout(&options);
+        let (x, y, depth) = layout.place(&expr);
+
+        assert_eq!(depth, 1);
+        assert_eq!(
+            x,
+            output_x_offset(0, &options)
+                + f64::from(assign.gate_width)
+                + 2. * f64::from(assign.bubble_radius)
+        );
+        assert_eq!(y, f64::from(assign.input_spacing) / 2.);
+    }
+
+    #[test]
+    fn deep_nesting() {
+        let options = Options::default();
+
+        let mut expr = GateExpr::input("a");
+        for _ in 0..100 {
+            expr = GateExpr::gate(GateKind::Buffer, [expr]);
+        }
+
+        let mut layout = layout(&options);
+        let (_, _, depth) = layout.place(&expr);
+
+        assert_eq!(depth, 100);
+        assert_eq!(layout.wires.len(), 100);
+    }
+}
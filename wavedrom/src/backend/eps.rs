@@ -0,0 +1,290 @@
+use std::io;
+
+use super::{
+    hatch_lines, place_spans, write_path_operators, write_string_literal, Backend, Fill, Num, Path,
+    PathStyle, Rgb, TextLayout,
+};
+use crate::text::RichText;
+use crate::Color;
+
+/// The number of points per unit
+const SCALE: f64 = 0.75;
+
+const PATH_OPERATORS: [&str; 4] = ["moveto", "lineto", "curveto", "closepath"];
+
+/// The standard fonts that are used for regular, bold, oblique and bold oblique text
+const FONTS: [&str; 4] = [
+    "Helvetica",
+    "Helvetica-Bold",
+    "Helvetica-Oblique",
+    "Helvetica-BoldOblique",
+];
+
+/// Define a copy of a font with the ISO Latin 1 encoding where the apostrophe and the backtick
+/// are not replaced by curly quotes.
+const PROLOG: &str = "/wavedrom-latin1 { findfont dup length dict begin { 1 index /FID ne { def } { pop pop } ifelse } forall /Encoding ISOLatin1Encoding 256 array copy dup 39 /quotesingle put dup 96 /grave put def currentdict end definefont pop } bind def";
+
+/// Returns the ISO Latin 1 code of a character where unknown characters become `?`.
+fn latin1_code(c: char) -> u8 {
+    match c {
+        ' '..='~' | '\u{A0}'..='\u{FF}' => c as u8,
+        _ => b'?',
+    }
+}
+
+/// Mix a `color` with an `opacity` onto a white background.
+fn blend_with_white(color: Color, opacity: f64) -> Color {
+    let blend = |c: u8| (255. - (255. - f64::from(c)) * opacity).round() as u8;
+
+    Color {
+        red: blend(color.red),
+        green: blend(color.green),
+        blue: blend(color.blue),
+    }
+}
+
+/// A [`Backend`] that writes an Encapsulated PostScript file
+///
+/// The text uses the standard Helvetica fonts of PostScript. Characters outside of ISO Latin 1
+/// are replaced with a `?`. PostScript has no transparency, so translucent fills are blended with
+/// white.
+#[derive(Debug)]
+pub struct EpsBackend<W: io::Write> {
+    writer: W,
+}
+
+impl<W: io::Write> EpsBackend<W> {
+    /// Create a new [`EpsBackend`] that writes into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    fn fill_path(&mut self, path: &Path, fill: Fill, opacity: f64) -> io::Result<()> {
+        let writer = &mut self.writer;
+
+        match fill {
+            Fill::Solid(color) => {
+                write!(
+                    writer,
+                    "newpath {} setrgbcolor ",
+                    Rgb(blend_with_white(color, opacity))
+                )?;
+                write_path_operators(writer, path, PATH_OPERATORS)?;
+                writeln!(writer, "fill")
+            }
+            Fill::Hatched { color, background } => {
+                if let Some(background) = background {
+                    write!(
+                        writer,
+                        "newpath {} setrgbcolor ",
+                        Rgb(blend_with_white(background, opacity))
+                    )?;
+                    write_path_operators(writer, path, PATH_OPERATORS)?;
+                    writeln!(writer, "fill")?;
+                }
+
+                let Some(bbox) = path.bounding_box() else {
+                    return Ok(());
+                };
+
+                write!(writer, "gsave newpath ")?;
+                write_path_operators(writer, path, PATH_OPERATORS)?;
+                write!(
+                    writer,
+                    "clip newpath {} setrgbcolor 1 setlinewidth [] 0 setdash ",
+                    Rgb(blend_with_white(color, opacity))
+                )?;
+                for ((x1, y1), (x2, y2)) in hatch_lines(bbox) {
+                    write!(
+                        writer,
+                        "{} {} moveto {} {} lineto ",
+                        Num(x1),
+                        Num(y1),
+                        Num(x2),
+                        Num(y2)
+                    )?;
+                }
+                writeln!(writer, "stroke grestore")
+            }
+        }
+    }
+}
+
+impl<W: io::Write> Backend for EpsBackend<W> {
+    fn begin(&mut self, width: f64, height: f64, background: Option<Color>) -> io::Result<()> {
+        let writer = &mut self.writer;
+
+        writeln!(writer, "%!PS-Adobe-3.0 EPSF-3.0")?;
+        writeln!(
+            writer,
+            "%%BoundingBox: 0 0 {} {}",
+            (width * SCALE).ceil(),
+            (height * SCALE).ceil()
+        )?;
+        writeln!(
+            writer,
+            "%%HiResBoundingBox: 0 0 {} {}",
+            Num(width * SCALE),
+            Num(height * SCALE)
+        )?;
+        writeln!(writer, "%%Creator: wavedrom")?;
+        writeln!(writer, "%%LanguageLevel: 2")?;
+        writeln!(writer, "%%Pages: 1")?;
+        writeln!(writer, "%%EndComments")?;
+        writeln!(writer, "%%BeginProlog")?;
+        writeln!(writer, "{PROLOG}")?;
+        writeln!(writer, "%%EndProlog")?;
+        writeln!(writer, "%%BeginSetup")?;
+        for font in FONTS {
+            writeln!(writer, "/{font}-Latin1 /{font} wavedrom-latin1")?;
+        }
+        writeln!(writer, "%%EndSetup")?;
+        writeln!(writer, "%%Page: 1 1")?;
+
+        // Flip the y-axis so that the origin is in the top-left corner
+        writeln!(
+            writer,
+            "gsave 0 {} translate {SCALE} -{SCALE} scale 4 setmiterlimit",
+            Num(height * SCALE)
+        )?;
+
+        if let Some(background) = background {
+            writeln!(
+                writer,
+                "{} setrgbcolor 0 0 {} {} rectfill",
+                Rgb(background),
+                Num(width),
+                Num(height)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn begin_group(&mut self, (x, y): (f64, f64)) -> io::Result<()> {
+        writeln!(self.writer, "gsave {} {} translate", Num(x), Num(y))
+    }
+
+    fn end_group(&mut self) -> io::Result<()> {
+        writeln!(self.writer, "grestore")
+    }
+
+    fn draw_path(&mut self, path: &Path, style: &PathStyle) -> io::Result<()> {
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        if let Some(fill) = style.fill {
+            self.fill_path(path, fill, style.fill_opacity)?;
+        }
+
+        if let Some(stroke) = style.stroke {
+            let writer = &mut self.writer;
+
+            write!(
+                writer,
+                "gsave newpath {} setrgbcolor {} setlinewidth ",
+                Rgb(stroke.color),
+                Num(stroke.width)
+            )?;
+            if let Some(dash) = stroke.dash {
+                write!(writer, "[{}] 0 setdash ", Num(dash))?;
+            }
+            write_path_operators(writer, path, PATH_OPERATORS)?;
+            writeln!(writer, "stroke grestore")?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_text(
+        &mut self,
+        at: (f64, f64),
+        text: &RichText,
+        layout: &TextLayout,
+    ) -> io::Result<()> {
+        let writer = &mut self.writer;
+
+        for span in place_spans(text, layout) {
+            let font = FONTS[usize::from(span.is_bold) + 2 * usize::from(span.is_italic)];
+            let encoded = span.text.chars().map(latin1_code).collect::<Vec<u8>>();
+
+            write!(
+                writer,
+                "gsave {} {} translate {} rotate {} setrgbcolor ",
+                Num(at.0),
+                Num(at.1),
+                layout.rotation,
+                Rgb(span.color),
+            )?;
+
+            if let Some((y, thickness)) = span.decoration_line() {
+                write!(
+                    writer,
+                    "{} setlinewidth newpath {} {} moveto {} {} lineto stroke ",
+                    Num(thickness),
+                    Num(span.x),
+                    Num(y),
+                    Num(span.x + span.width),
+                    Num(y),
+                )?;
+            }
+
+            // The glyphs are flipped back up
+            write!(
+                writer,
+                "{} {} translate 1 -1 scale /{font}-Latin1 findfont {} scalefont setfont 0 0 moveto ",
+                Num(span.x),
+                Num(span.y),
+                Num(span.font_size),
+            )?;
+            write_string_literal(writer, &encoded)?;
+            writeln!(writer, " show grestore")?;
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.writer, "grestore")?;
+        writeln!(self.writer, "showpage")?;
+        writeln!(self.writer, "%%EOF")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn document_structure() {
+        let mut eps = Vec::new();
+        let mut backend = EpsBackend::new(&mut eps);
+
+        backend.begin(100., 50.5, None).unwrap();
+        backend
+            .draw_text(
+                (10., 10.),
+                &RichText::from("a\u{2192}b"),
+                &TextLayout::new(12, Color::BLACK),
+            )
+            .unwrap();
+        backend.finish().unwrap();
+
+        let eps = String::from_utf8(eps).unwrap();
+        let mut lines = eps.lines();
+
+        assert_eq!(lines.next(), Some("%!PS-Adobe-3.0 EPSF-3.0"));
+        // The bounding box is in whole points that contain the figure
+        assert_eq!(lines.next(), Some("%%BoundingBox: 0 0 75 38"));
+        assert_eq!(lines.next(), Some("%%HiResBoundingBox: 0 0 75 37.875"));
+
+        for font in FONTS {
+            assert!(eps.contains(&format!("/{font}-Latin1 /{font} wavedrom-latin1\n")));
+        }
+        assert!(
+            eps.contains("/Helvetica-Latin1 findfont 12 scalefont setfont 0 0 moveto (a?b) show")
+        );
+        assert!(eps.ends_with("showpage\n%%EOF\n"));
+    }
+}
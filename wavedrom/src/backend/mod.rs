@@ -0,0 +1,829 @@
+//! Backends that draw figures into different file formats
+//!
+//! All figures are drawn with a small set of primitives: [`Path`]s that are filled and stroked,
+//! and [`RichText`] that is placed at a point. A [`Backend`] turns these primitives into a file
+//! format. This crate comes with backends for [SVG][SvgBackend], [PDF][PdfBackend],
+//! [EPS][EpsBackend] and [TikZ][TikzBackend].
+//!
+//! The coordinate system of all backends has its origin in the top-left corner with the `y` axis
+//! pointing down. One unit is one SVG pixel, which is `0.75` points in the PDF, EPS and TikZ
+//! backends.
+//!
+//! # Examples
+//!
+//! ```
+//! use wavedrom::backend::PdfBackend;
+//! use wavedrom::signal::{Signal, SignalFigure};
+//! use wavedrom::Options;
+//!
+//! let figure = SignalFigure::new().add_signal(Signal::with_cycle_str("p...").name("clk"));
+//! let options = Options::default();
+//!
+//! let mut pdf = Vec::new();
+//! figure
+//!     .assemble_with_options(&options)
+//!     .render_with_options(&mut PdfBackend::new(&mut pdf), &options)?;
+//! # assert!(pdf.starts_with(b"%PDF"));
+//! # <Result<(), std::io::Error>>::Ok(())
+//! ```
+
+use std::fmt::Display;
+use std::io;
+
+use crate::text::RichText;
+use crate::{Color, Font};
+
+mod eps;
+mod pdf;
+mod svg;
+mod tikz;
+
+pub use eps::EpsBackend;
+pub use pdf::PdfBackend;
+pub use svg::SvgBackend;
+pub use tikz::TikzBackend;
+
+/// The distance of the control points of a cubic bézier curve that approximates a quarter circle
+/// with a radius of `1`.
+pub(crate) const KAPPA: f64 = 0.552_284_749_8;
+
+const DISPLAY_PRECISION: u8 = 3;
+const DISPLAY_PRECISION_ROUNDING: f64 = {
+    let mut n = 10.;
+    let mut i = 0;
+    loop {
+        if i >= DISPLAY_PRECISION {
+            break n;
+        }
+
+        n *= 10.;
+        i += 1;
+    }
+};
+
+/// Round a coordinate so that figures do not contain floating point noise.
+pub(crate) fn to_display_num(n: f64) -> f64 {
+    (n * DISPLAY_PRECISION_ROUNDING).round() / DISPLAY_PRECISION_ROUNDING
+}
+
+/// The height of the lowercase letters of Helvetica in em
+const X_HEIGHT: f64 = 0.523;
+/// The height of the capital letters of Helvetica in em
+const CAP_HEIGHT: f64 = 0.718;
+
+/// The target of the drawing primitives of a figure
+///
+/// The figures call [`Backend::begin`] once, then draw all their shapes and finally call
+/// [`Backend::finish`].
+pub trait Backend {
+    /// Start a figure of `width` by `height` units with an optional `background`.
+    fn begin(&mut self, width: f64, height: f64, background: Option<Color>) -> io::Result<()>;
+
+    /// Start a group of primitives that are moved by `translation`.
+    ///
+    /// Groups can be nested and every group is closed with [`Backend::end_group`].
+    fn begin_group(&mut self, translation: (f64, f64)) -> io::Result<()>;
+
+    /// Close the last group started with [`Backend::begin_group`].
+    fn end_group(&mut self) -> io::Result<()>;
+
+    /// Draw a [`Path`] with a [`PathStyle`].
+    fn draw_path(&mut self, path: &Path, style: &PathStyle) -> io::Result<()>;
+
+    /// Draw a [`RichText`] at a point with a [`TextLayout`].
+    fn draw_text(&mut self, at: (f64, f64), text: &RichText, layout: &TextLayout)
+        -> io::Result<()>;
+
+    /// Finish the figure.
+    fn finish(&mut self) -> io::Result<()>;
+}
+
+/// A command of a [`Path`] with absolute coordinates
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PathSegment {
+    /// Start a new subpath at a point.
+    MoveTo((f64, f64)),
+    /// Draw a straight line to a point.
+    LineTo((f64, f64)),
+    /// Draw a cubic bézier curve with two control points to a point.
+    CurveTo((f64, f64), (f64, f64), (f64, f64)),
+    /// Draw a straight line back to the start of the subpath.
+    Close,
+}
+
+/// A shape made up of lines and curves
+///
+/// The relative methods (e.g. [`Path::line_by`]) are relative to the end of the previous segment.
+///
+/// # Examples
+///
+/// ```
+/// use wavedrom::backend::Path;
+///
+/// // A triangle
+/// let path = Path::new().move_to(0, 0).line_by(10, 10).horizontal_by(-20).close();
+/// # assert_eq!(path.segments().len(), 4);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>,
+    start: (f64, f64),
+    current: (f64, f64),
+}
+
+impl Path {
+    /// Create a new empty [`Path`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a rectangle with its top-left corner at `(x, y)`.
+    pub fn rect(
+        x: impl Into<f64>,
+        y: impl Into<f64>,
+        width: impl Into<f64>,
+        height: impl Into<f64>,
+    ) -> Self {
+        let width = width.into();
+        let height = height.into();
+
+        Self::new()
+            .move_to(x, y)
+            .horizontal_by(width)
+            .vertical_by(height)
+            .horizontal_by(-width)
+            .close()
+    }
+
+    /// Create a circle around `(cx, cy)`.
+    pub fn circle(cx: impl Into<f64>, cy: impl Into<f64>, radius: impl Into<f64>) -> Self {
+        let (cx, cy, r) = (cx.into(), cy.into(), radius.into());
+        let k = r * KAPPA;
+
+        Self::new()
+            .move_to(cx + r, cy)
+            .curve_to((cx + r, cy + k), (cx + k, cy + r), (cx, cy + r))
+            .curve_to((cx - k, cy + r), (cx - r, cy + k), (cx - r, cy))
+            .curve_to((cx - r, cy - k), (cx - k, cy - r), (cx, cy - r))
+            .curve_to((cx + k, cy - r), (cx + r, cy - k), (cx + r, cy))
+            .close()
+    }
+
+    /// Returns the segments of the [`Path`].
+    #[inline]
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// Returns whether the [`Path`] has no segments.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Returns the end of the last segment of the [`Path`].
+    #[inline]
+    pub fn current(&self) -> (f64, f64) {
+        self.current
+    }
+
+    /// Start a new subpath at `(x, y)`.
+    pub fn move_to(mut self, x: impl Into<f64>, y: impl Into<f64>) -> Self {
+        let point = (x.into(), y.into());
+
+        // Consecutive moves only need the last one
+        if let Some(PathSegment::MoveTo(last)) = self.segments.last_mut() {
+            *last = point;
+        } else {
+            self.segments.push(PathSegment::MoveTo(point));
+        }
+
+        self.start = point;
+        self.current = point;
+        self
+    }
+
+    /// Start a new subpath at an offset of `(dx, dy)`.
+    #[inline]
+    pub fn move_by(self, dx: impl Into<f64>, dy: impl Into<f64>) -> Self {
+        let (x, y) = self.current;
+        self.move_to(x + dx.into(), y + dy.into())
+    }
+
+    /// Draw a line to `(x, y)`.
+    pub fn line_to(mut self, x: impl Into<f64>, y: impl Into<f64>) -> Self {
+        let point = (x.into(), y.into());
+        self.segments.push(PathSegment::LineTo(point));
+        self.current = point;
+        self
+    }
+
+    /// Draw a line to an offset of `(dx, dy)`.
+    #[inline]
+    pub fn line_by(self, dx: impl Into<f64>, dy: impl Into<f64>) -> Self {
+        let (x, y) = self.current;
+        self.line_to(x + dx.into(), y + dy.into())
+    }
+
+    /// Draw a horizontal line to `x`.
+    #[inline]
+    pub fn horizontal_to(self, x: impl Into<f64>) -> Self {
+        let y = self.current.1;
+        self.line_to(x, y)
+    }
+
+    /// Draw a horizontal line over `dx`.
+    #[inline]
+    pub fn horizontal_by(self, dx: impl Into<f64>) -> Self {
+        self.line_by(dx, 0)
+    }
+
+    /// Draw a vertical line to `y`.
+    #[inline]
+    pub fn vertical_to(self, y: impl Into<f64>) -> Self {
+        let x = self.current.0;
+        self.line_to(x, y)
+    }
+
+    /// Draw a vertical line over `dy`.
+    #[inline]
+    pub fn vertical_by(self, dy: impl Into<f64>) -> Self {
+        self.line_by(0, dy)
+    }
+
+    /// Draw a cubic bézier curve to `end` with the control points `c1` and `c2`.
+    pub fn curve_to(mut self, c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) -> Self {
+        self.segments.push(PathSegment::CurveTo(c1, c2, end));
+        self.current = end;
+        self
+    }
+
+    /// Draw a cubic bézier curve where all points are offsets from the current point.
+    #[inline]
+    pub fn curve_by(self, c1: (f64, f64), c2: (f64, f64), end: (f64, f64)) -> Self {
+        let (x, y) = self.current;
+        self.curve_to(
+            (x + c1.0, y + c1.1),
+            (x + c2.0, y + c2.1),
+            (x + end.0, y + end.1),
+        )
+    }
+
+    /// Draw a quadratic bézier curve to `end` with the control point `c`.
+    pub fn quad_to(self, c: (f64, f64), end: (f64, f64)) -> Self {
+        // Every quadratic bézier curve is also a cubic bézier curve
+        let (x, y) = self.current;
+        self.curve_to(
+            (x + 2. / 3. * (c.0 - x), y + 2. / 3. * (c.1 - y)),
+            (
+                end.0 + 2. / 3. * (c.0 - end.0),
+                end.1 + 2. / 3. * (c.1 - end.1),
+            ),
+            end,
+        )
+    }
+
+    /// Draw a line back to the start of the current subpath.
+    pub fn close(mut self) -> Self {
+        self.segments.push(PathSegment::Close);
+        self.current = self.start;
+        self
+    }
+
+    /// Returns the smallest rectangle as `(x_min, y_min, x_max, y_max)` that contains all the
+    /// points and control points of the [`Path`].
+    pub(crate) fn bounding_box(&self) -> Option<(f64, f64, f64, f64)> {
+        self.segments
+            .iter()
+            .flat_map(|segment| match *segment {
+                PathSegment::MoveTo(p) | PathSegment::LineTo(p) => vec![p],
+                PathSegment::CurveTo(c1, c2, end) => vec![c1, c2, end],
+                PathSegment::Close => Vec::new(),
+            })
+            .fold(None, |bbox, (x, y)| {
+                let (x_min, y_min, x_max, y_max) = bbox.unwrap_or((x, y, x, y));
+                Some((x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y)))
+            })
+    }
+}
+
+/// The way the inside of a [`Path`] is painted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Fill {
+    /// Paint with a single color.
+    Solid(Color),
+    /// Paint diagonal lines with `color` on top of an optional `background`.
+    Hatched {
+        /// The color of the lines
+        color: Color,
+        /// The color between the lines
+        background: Option<Color>,
+    },
+}
+
+impl From<Color> for Fill {
+    #[inline]
+    fn from(color: Color) -> Self {
+        Self::Solid(color)
+    }
+}
+
+/// The outline of a [`Path`]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stroke {
+    /// The color of the outline
+    pub color: Color,
+    /// The width of the outline
+    pub width: f64,
+    /// The length of the dashes and the gaps between them for a dashed outline
+    pub dash: Option<f64>,
+}
+
+/// The way a [`Path`] is painted
+///
+/// By default, a [`Path`] is neither filled nor stroked.
+///
+/// # Examples
+///
+/// ```
+/// use wavedrom::backend::PathStyle;
+/// use wavedrom::Color;
+///
+/// let style = PathStyle::new().fill(Color::WHITE).stroke(Color::BLACK, 1);
+/// # let _ = style;
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PathStyle {
+    /// The way the inside of the path is painted
+    pub fill: Option<Fill>,
+    /// The opacity of the fill between `0` and `1`
+    pub fill_opacity: f64,
+    /// The outline of the path
+    pub stroke: Option<Stroke>,
+}
+
+impl Default for PathStyle {
+    fn default() -> Self {
+        Self {
+            fill: None,
+            fill_opacity: 1.,
+            stroke: None,
+        }
+    }
+}
+
+impl PathStyle {
+    /// Create a new [`PathStyle`] that does not paint anything.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Paint the inside of the path.
+    #[inline]
+    pub fn fill(mut self, fill: impl Into<Fill>) -> Self {
+        self.fill = Some(fill.into());
+        self
+    }
+
+    /// Set the opacity of the fill between `0` and `1`.
+    #[inline]
+    pub fn fill_opacity(mut self, opacity: f64) -> Self {
+        self.fill_opacity = opacity.clamp(0., 1.);
+        self
+    }
+
+    /// Paint the outline of the path.
+    #[inline]
+    pub fn stroke(mut self, color: Color, width: impl Into<f64>) -> Self {
+        self.stroke = Some(Stroke {
+            color,
+            width: width.into(),
+            dash: None,
+        });
+        self
+    }
+
+    /// Dash the outline of the path with dashes and gaps of `length`.
+    ///
+    /// This does nothing if the path has no outline.
+    #[inline]
+    pub fn dash(mut self, length: impl Into<f64>) -> Self {
+        if let Some(stroke) = &mut self.stroke {
+            stroke.dash = Some(length.into());
+        }
+        self
+    }
+}
+
+/// The horizontal alignment of a text relative to its point
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    /// The text starts at the point.
+    #[default]
+    Start,
+    /// The text is centered around the point.
+    Middle,
+    /// The text ends at the point.
+    End,
+}
+
+/// The vertical alignment of a text relative to its point
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextBaseline {
+    /// The baseline of the text is at the point.
+    #[default]
+    Alphabetic,
+    /// The middle of the lowercase letters is at the point.
+    Middle,
+    /// The top of the capital letters is at the point.
+    Hanging,
+}
+
+/// The placement and default style of a [`RichText`]
+///
+/// The spans of the [`RichText`] can override the font size, weight and style and the color.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TextLayout<'a> {
+    /// The font family or `None` for the embedded font
+    pub font_family: Option<&'a str>,
+    /// The font weight (e.g. `bold`)
+    pub font_weight: Option<&'a str>,
+    /// The font size in units
+    pub font_size: u32,
+    /// The color of the text
+    pub color: Color,
+    /// The horizontal alignment
+    pub anchor: TextAnchor,
+    /// The vertical alignment
+    pub baseline: TextBaseline,
+    /// The clockwise rotation around the point in degrees
+    pub rotation: i32,
+}
+
+impl<'a> TextLayout<'a> {
+    /// Create a new [`TextLayout`] with the embedded font that starts at the alphabetic baseline.
+    #[inline]
+    pub fn new(font_size: u32, color: Color) -> Self {
+        Self {
+            font_family: None,
+            font_weight: None,
+            font_size,
+            color,
+            anchor: TextAnchor::default(),
+            baseline: TextBaseline::default(),
+            rotation: 0,
+        }
+    }
+
+    /// Set the font family.
+    #[inline]
+    pub fn font_family(mut self, font_family: &'a str) -> Self {
+        self.font_family = Some(font_family);
+        self
+    }
+
+    /// Set the font weight.
+    #[inline]
+    pub fn font_weight(mut self, font_weight: &'a str) -> Self {
+        self.font_weight = Some(font_weight);
+        self
+    }
+
+    /// Set the horizontal alignment.
+    #[inline]
+    pub fn anchor(mut self, anchor: TextAnchor) -> Self {
+        self.anchor = anchor;
+        self
+    }
+
+    /// Set the vertical alignment.
+    #[inline]
+    pub fn baseline(mut self, baseline: TextBaseline) -> Self {
+        self.baseline = baseline;
+        self
+    }
+
+    /// Set the clockwise rotation around the point in degrees.
+    #[inline]
+    pub fn rotation(mut self, rotation: i32) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    /// Shorthand for a text that is centered around its point.
+    #[inline]
+    pub fn centered(self) -> Self {
+        self.anchor(TextAnchor::Middle)
+            .baseline(TextBaseline::Middle)
+    }
+}
+
+/// A number that is formatted with at most three decimals
+pub(crate) struct Num(pub f64);
+
+impl Display for Num {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = format!("{:.3}", self.0);
+        let s = s.trim_end_matches('0').trim_end_matches('.');
+
+        if s == "-0" {
+            write!(f, "0")
+        } else {
+            write!(f, "{s}")
+        }
+    }
+}
+
+/// A line that is drawn along a span of text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Decoration {
+    Underline,
+    Overline,
+    LineThrough,
+}
+
+/// A span of a [`RichText`] that is placed relative to the point of the text
+///
+/// This is used by the backends that need to position every span themselves. The coordinates
+/// are in the frame of the text before its rotation.
+struct PlacedSpan<'a> {
+    text: &'a str,
+    /// The start of the span on the baseline
+    x: f64,
+    y: f64,
+    width: f64,
+    font_size: f64,
+    is_bold: bool,
+    is_italic: bool,
+    color: Color,
+    decoration: Option<Decoration>,
+}
+
+impl PlacedSpan<'_> {
+    /// Returns the `y` and the thickness of the decoration line.
+    fn decoration_line(&self) -> Option<(f64, f64)> {
+        let offset = match self.decoration? {
+            Decoration::Underline => 0.1,
+            Decoration::Overline => -0.8,
+            Decoration::LineThrough => -0.3,
+        };
+
+        Some((self.y + offset * self.font_size, 0.05 * self.font_size))
+    }
+}
+
+/// Returns whether a CSS font weight is drawn with a bold font.
+fn is_bold(weight: Option<&str>) -> bool {
+    weight.is_some_and(|weight| match weight {
+        "bold" | "bolder" => true,
+        weight => weight.parse::<u32>().is_ok_and(|weight| weight >= 600),
+    })
+}
+
+/// Lay out the spans of a [`RichText`] with the metrics of the embedded font.
+fn place_spans<'a>(text: &'a RichText, layout: &TextLayout) -> Vec<PlacedSpan<'a>> {
+    let font = Font::default();
+
+    let mut spans: Vec<PlacedSpan> = text
+        .spans()
+        .iter()
+        .map(|span| {
+            let style = span.style();
+            let font_size = f64::from(style.font_size.unwrap_or(layout.font_size));
+
+            let baseline_shift = match style.baseline_shift.as_deref() {
+                Some("sub") => 0.2 * font_size,
+                Some("super") => -0.4 * font_size,
+                _ => 0.,
+            };
+
+            PlacedSpan {
+                text: span.text(),
+                x: 0.,
+                y: baseline_shift,
+                width: font.get_em_text_width(span.text()) * font_size,
+                font_size,
+                is_bold: is_bold(style.font_weight.as_deref().or(layout.font_weight)),
+                is_italic: matches!(style.font_style.as_deref(), Some("italic" | "oblique")),
+                color: style
+                    .fill
+                    .as_deref()
+                    .and_then(|fill| fill.parse().ok())
+                    .unwrap_or(layout.color),
+                decoration: match style.text_decoration.as_deref() {
+                    Some("underline") => Some(Decoration::Underline),
+                    Some("overline") => Some(Decoration::Overline),
+                    Some("line-through") => Some(Decoration::LineThrough),
+                    _ => None,
+                },
+            }
+        })
+        .collect();
+
+    let width: f64 = spans.iter().map(|span| span.width).sum();
+    let font_size = f64::from(layout.font_size);
+
+    let mut x = match layout.anchor {
+        TextAnchor::Start => 0.,
+        TextAnchor::Middle => -width / 2.,
+        TextAnchor::End => -width,
+    };
+    let y = match layout.baseline {
+        TextBaseline::Alphabetic => 0.,
+        TextBaseline::Middle => X_HEIGHT / 2. * font_size,
+        TextBaseline::Hanging => CAP_HEIGHT * font_size,
+    };
+
+    for span in &mut spans {
+        span.x = x;
+        span.y += y;
+        x += span.width;
+    }
+
+    spans
+}
+
+/// Returns the lines of a diagonal hatching with a spacing of `4` units that covers a bounding box.
+fn hatch_lines(
+    (x_min, y_min, x_max, y_max): (f64, f64, f64, f64),
+) -> impl Iterator<Item = ((f64, f64), (f64, f64))> {
+    // The lines follow `x + y = c`, which are `4 * sqrt(2)` apart in `c` for a spacing of `4`.
+    let step = 4. * std::f64::consts::SQRT_2;
+    let start = ((x_min + y_min) / step).floor() as i64;
+    let end = ((x_max + y_max) / step).ceil() as i64;
+
+    (start..=end).map(move |i| {
+        let c = i as f64 * step;
+        ((c - y_min, y_min), (c - y_max, y_max))
+    })
+}
+
+/// A color that is formatted as the red, green and blue components between `0` and `1` as used by
+/// PDF and PostScript.
+struct Rgb(Color);
+
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Color { red, green, blue } = self.0;
+        let component = |c: u8| Num(f64::from(c) / 255.);
+
+        write!(
+            f,
+            "{} {} {}",
+            component(red),
+            component(green),
+            component(blue)
+        )
+    }
+}
+
+/// Write the segments of a [`Path`] with the `operators` for move, line, curve and close.
+fn write_path_operators(
+    writer: &mut impl io::Write,
+    path: &Path,
+    [move_to, line_to, curve_to, close]: [&str; 4],
+) -> io::Result<()> {
+    for segment in path.segments() {
+        match *segment {
+            PathSegment::MoveTo((x, y)) => write!(writer, "{} {} {move_to} ", Num(x), Num(y))?,
+            PathSegment::LineTo((x, y)) => write!(writer, "{} {} {line_to} ", Num(x), Num(y))?,
+            PathSegment::CurveTo((x1, y1), (x2, y2), (x, y)) => write!(
+                writer,
+                "{} {} {} {} {} {} {curve_to} ",
+                Num(x1),
+                Num(y1),
+                Num(x2),
+                Num(y2),
+                Num(x),
+                Num(y)
+            )?,
+            PathSegment::Close => write!(writer, "{close} ")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `bytes` as a string literal that is shared between PDF and PostScript.
+fn write_string_literal(writer: &mut impl io::Write, bytes: &[u8]) -> io::Result<()> {
+    write!(writer, "(")?;
+    for &byte in bytes {
+        match byte {
+            b'(' | b')' | b'\\' => write!(writer, "\\{}", char::from(byte))?,
+            0x20..=0x7E => writer.write_all(&[byte])?,
+            _ => write!(writer, "\\{byte:03o}")?,
+        }
+    }
+    write!(writer, ")")
+}
+
+/// Rotate an offset `(x, y)` clockwise by `rotation` degrees.
+fn rotate((x, y): (f64, f64), rotation: i32) -> (f64, f64) {
+    let (sin, cos) = f64::from(rotation).to_radians().sin_cos();
+    (cos * x - sin * y, sin * x + cos * y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::TextStyle;
+
+    #[test]
+    fn path_segments() {
+        let path = Path::new()
+            .move_to(1, 2)
+            .move_by(1, 1)
+            .horizontal_by(3)
+            .vertical_to(7)
+            .line_by(-2, -1)
+            .close();
+
+        // The consecutive moves are merged into one
+        assert_eq!(
+            path.segments(),
+            [
+                PathSegment::MoveTo((2., 3.)),
+                PathSegment::LineTo((5., 3.)),
+                PathSegment::LineTo((5., 7.)),
+                PathSegment::LineTo((3., 6.)),
+                PathSegment::Close,
+            ]
+        );
+        assert_eq!(path.current(), (2., 3.));
+        assert_eq!(path.bounding_box(), Some((2., 3., 5., 7.)));
+
+        assert_eq!(Path::new().bounding_box(), None);
+        assert_eq!(
+            Path::rect(1, 2, 3, 4).bounding_box(),
+            Some((1., 2., 4., 6.))
+        );
+        assert_eq!(
+            Path::circle(0, 0, 2).bounding_box(),
+            Some((-2., -2., 2., 2.))
+        );
+    }
+
+    #[test]
+    fn quad_to_cubic() {
+        let path = Path::new().move_to(0, 0).quad_to((3., 3.), (6., 0.));
+
+        assert_eq!(
+            path.segments()[1],
+            PathSegment::CurveTo((2., 2.), (4., 2.), (6., 0.))
+        );
+    }
+
+    #[test]
+    fn hatch_lines_cover_bbox() {
+        let bbox = (0., 0., 20., 10.);
+        let lines = hatch_lines(bbox).collect::<Vec<_>>();
+
+        let step = 4. * std::f64::consts::SQRT_2;
+        for ((x1, y1), (x2, y2)) in &lines {
+            // Every line goes diagonally from the top to the bottom of the box
+            assert_eq!(*y1, 0.);
+            assert_eq!(*y2, 10.);
+            assert!((x1 - x2 - 10.).abs() < 1e-9);
+        }
+
+        for pair in lines.windows(2) {
+            assert!(((pair[1].0 .0 - pair[0].0 .0) - step).abs() < 1e-9);
+        }
+
+        // The lines reach past both the top-left and the bottom-right corner
+        let ((first_x, _), _) = lines[0];
+        let (_, (last_x, _)) = lines[lines.len() - 1];
+        assert!(first_x <= 0.);
+        assert!(last_x >= 20.);
+    }
+
+    #[test]
+    fn place_spans_anchor_and_style() {
+        let text = RichText::new()
+            .span("ab", TextStyle::new())
+            .span("cd", TextStyle::new().bold());
+
+        let layout = TextLayout::new(10, Color::BLACK).anchor(TextAnchor::Middle);
+        let spans = place_spans(&text, &layout);
+
+        assert_eq!(spans.len(), 2);
+        let width = spans[0].width + spans[1].width;
+        assert!((spans[0].x + width / 2.).abs() < 1e-9);
+        assert!((spans[1].x - (spans[0].x + spans[0].width)).abs() < 1e-9);
+        assert!(!spans[0].is_bold);
+        assert!(spans[1].is_bold);
+        assert_eq!(spans[0].y, 0.);
+
+        let layout = TextLayout::new(10, Color::BLACK)
+            .anchor(TextAnchor::End)
+            .baseline(TextBaseline::Hanging)
+            .font_weight("700");
+        let spans = place_spans(&text, &layout);
+
+        assert!((spans[1].x + spans[1].width).abs() < 1e-9);
+        assert!(spans.iter().all(|span| span.is_bold));
+        assert!((spans[0].y - CAP_HEIGHT * 10.).abs() < 1e-9);
+    }
+}
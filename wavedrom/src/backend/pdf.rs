@@ -0,0 +1,467 @@
+use std::io::{self, Write};
+
+use super::{
+    hatch_lines, place_spans, rotate, write_path_operators, write_string_literal, Backend, Fill,
+    Num, Path, PathStyle, Rgb, TextLayout,
+};
+use crate::text::RichText;
+use crate::{Color, Font};
+
+/// The number of points per unit
+const SCALE: f64 = 0.75;
+
+const PATH_OPERATORS: [&str; 4] = ["m", "l", "c", "h"];
+
+/// The PDF standard fonts that are used for regular, bold, oblique and bold oblique text
+const FONTS: [&str; 4] = [
+    "Helvetica",
+    "Helvetica-Bold",
+    "Helvetica-Oblique",
+    "Helvetica-BoldOblique",
+];
+
+/// The characters for the codes `0x80` to `0x9F` of the `WinAnsiEncoding`
+const WIN_ANSI_SPECIALS: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Returns the character of a code of the `WinAnsiEncoding`.
+fn win_ansi_char(code: u8) -> Option<char> {
+    match code {
+        0x20..=0x7E | 0xA0..=0xFF => Some(char::from(code)),
+        0x80..=0x9F => WIN_ANSI_SPECIALS[usize::from(code - 0x80)],
+        _ => None,
+    }
+}
+
+/// Returns the code of a character in the `WinAnsiEncoding` where unknown characters become `?`.
+fn win_ansi_code(c: char) -> u8 {
+    match u8::try_from(u32::from(c)) {
+        Ok(code @ (0x20..=0x7E | 0xA0..=0xFF)) => code,
+        _ => WIN_ANSI_SPECIALS
+            .iter()
+            .position(|special| *special == Some(c))
+            .map_or(b'?', |i| 0x80 + i as u8),
+    }
+}
+
+/// A [`Backend`] that writes a single page PDF document
+///
+/// The text uses the standard Helvetica fonts of PDF with the character widths of the embedded
+/// [`Font`]. Therefore, a PDF viewer does not need to substitute any fonts. Characters outside of
+/// the `WinAnsiEncoding` are replaced with a `?`.
+///
+/// The document is only written to the writer when the figure is finished.
+#[derive(Debug)]
+pub struct PdfBackend<W: io::Write> {
+    writer: W,
+    width: f64,
+    height: f64,
+    content: Vec<u8>,
+    /// Whether each of the [`FONTS`] is used
+    used_fonts: [bool; 4],
+    /// The fill opacities that need a graphics state
+    opacities: Vec<String>,
+}
+
+impl<W: io::Write> PdfBackend<W> {
+    /// Create a new [`PdfBackend`] that writes into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            width: 0.,
+            height: 0.,
+            content: Vec::new(),
+            used_fonts: [false; 4],
+            opacities: Vec::new(),
+        }
+    }
+
+    fn fill_path(&mut self, path: &Path, fill: Fill) -> io::Result<()> {
+        let content = &mut self.content;
+
+        match fill {
+            Fill::Solid(color) => {
+                write!(content, "{} rg ", Rgb(color))?;
+                write_path_operators(content, path, PATH_OPERATORS)?;
+                writeln!(content, "f")
+            }
+            Fill::Hatched { color, background } => {
+                if let Some(background) = background {
+                    write!(content, "{} rg ", Rgb(background))?;
+                    write_path_operators(content, path, PATH_OPERATORS)?;
+                    writeln!(content, "f")?;
+                }
+
+                let Some(bbox) = path.bounding_box() else {
+                    return Ok(());
+                };
+
+                write!(content, "q ")?;
+                write_path_operators(content, path, PATH_OPERATORS)?;
+                write!(content, "W n {} RG 1 w [] 0 d ", Rgb(color))?;
+                for ((x1, y1), (x2, y2)) in hatch_lines(bbox) {
+                    write!(
+                        content,
+                        "{} {} m {} {} l ",
+                        Num(x1),
+                        Num(y1),
+                        Num(x2),
+                        Num(y2)
+                    )?;
+                }
+                writeln!(content, "S Q")
+            }
+        }
+    }
+
+    /// Write all the objects of the document and returns them in order.
+    fn objects(&self) -> Vec<Vec<u8>> {
+        let font = Font::default();
+
+        let mut objects = vec![
+            b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+            b"<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_vec(),
+            Vec::new(),
+            Vec::new(),
+        ];
+
+        // All text is laid out with the widths of the embedded font. Giving every font these
+        // widths makes the viewer place the glyphs where the layout expects them, also for the
+        // bold variants that are wider.
+        let widths = (0x20..=0xFF)
+            .map(|code| {
+                win_ansi_char(code).map_or(0., |c| {
+                    (font.get_em_text_width(&c.to_string()) * 1000.).round()
+                })
+            })
+            .map(|width| Num(width).to_string())
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        let mut font_resources = String::new();
+        for (i, name) in FONTS.iter().enumerate() {
+            if !self.used_fonts[i] {
+                continue;
+            }
+
+            let font_id = objects.len() + 1;
+            font_resources.push_str(&format!("/F{i} {font_id} 0 R "));
+
+            let stem_v = if i % 2 == 1 { 140 } else { 88 };
+            let italic_angle = if i >= 2 { -12 } else { 0 };
+            objects.push(
+                format!(
+                    "<< /Type /Font /Subtype /Type1 /BaseFont /{name} /Encoding /WinAnsiEncoding /FirstChar 32 /LastChar 255 /Widths [{widths}] /FontDescriptor {descriptor_id} 0 R >>",
+                    descriptor_id = font_id + 1,
+                )
+                .into_bytes(),
+            );
+            objects.push(
+                format!(
+                    "<< /Type /FontDescriptor /FontName /{name} /Flags 32 /FontBBox [-166 -225 1000 931] /ItalicAngle {italic_angle} /Ascent 718 /Descent -207 /CapHeight 718 /XHeight 523 /StemV {stem_v} >>"
+                )
+                .into_bytes(),
+            );
+        }
+
+        let graphics_states = self
+            .opacities
+            .iter()
+            .enumerate()
+            .map(|(i, opacity)| format!("/GS{i} << /Type /ExtGState /ca {opacity} >> "))
+            .collect::<String>();
+
+        objects[2] = format!(
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {width} {height}] /Resources << /Font << {font_resources}>> /ExtGState << {graphics_states}>> >> /Contents 4 0 R >>",
+            width = Num(self.width * SCALE),
+            height = Num(self.height * SCALE),
+        )
+        .into_bytes();
+
+        let mut stream = format!("<< /Length {} >>\nstream\n", self.content.len()).into_bytes();
+        stream.extend_from_slice(&self.content);
+        stream.extend_from_slice(b"endstream");
+        objects[3] = stream;
+
+        objects
+    }
+}
+
+impl<W: io::Write> Backend for PdfBackend<W> {
+    fn begin(&mut self, width: f64, height: f64, background: Option<Color>) -> io::Result<()> {
+        self.width = width;
+        self.height = height;
+
+        // Flip the y-axis so that the origin is in the top-left corner
+        writeln!(
+            self.content,
+            "{SCALE} 0 0 -{SCALE} 0 {} cm 4 M",
+            Num(height * SCALE)
+        )?;
+
+        if let Some(background) = background {
+            writeln!(
+                self.content,
+                "{} rg 0 0 {} {} re f",
+                Rgb(background),
+                Num(width),
+                Num(height)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn begin_group(&mut self, (x, y): (f64, f64)) -> io::Result<()> {
+        writeln!(self.content, "q 1 0 0 1 {} {} cm", Num(x), Num(y))
+    }
+
+    fn end_group(&mut self) -> io::Result<()> {
+        writeln!(self.content, "Q")
+    }
+
+    fn draw_path(&mut self, path: &Path, style: &PathStyle) -> io::Result<()> {
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        writeln!(self.content, "q")?;
+
+        if let Some(fill) = style.fill {
+            if style.fill_opacity < 1. {
+                let opacity = Num(style.fill_opacity).to_string();
+                let index = match self.opacities.iter().position(|o| *o == opacity) {
+                    Some(index) => index,
+                    None => {
+                        self.opacities.push(opacity);
+                        self.opacities.len() - 1
+                    }
+                };
+
+                writeln!(self.content, "/GS{index} gs")?;
+            }
+
+            self.fill_path(path, fill)?;
+        }
+
+        if let Some(stroke) = style.stroke {
+            let content = &mut self.content;
+
+            write!(content, "{} RG {} w ", Rgb(stroke.color), Num(stroke.width))?;
+            if let Some(dash) = stroke.dash {
+                write!(content, "[{}] 0 d ", Num(dash))?;
+            }
+            write_path_operators(content, path, PATH_OPERATORS)?;
+            writeln!(content, "S")?;
+        }
+
+        writeln!(self.content, "Q")
+    }
+
+    fn draw_text(
+        &mut self,
+        at: (f64, f64),
+        text: &RichText,
+        layout: &TextLayout,
+    ) -> io::Result<()> {
+        let (sin, cos) = f64::from(layout.rotation).to_radians().sin_cos();
+
+        for span in place_spans(text, layout) {
+            let font = usize::from(span.is_bold) + 2 * usize::from(span.is_italic);
+            self.used_fonts[font] = true;
+
+            let (dx, dy) = rotate((span.x, span.y), layout.rotation);
+            let encoded = span.text.chars().map(win_ansi_code).collect::<Vec<u8>>();
+
+            let content = &mut self.content;
+
+            // The text matrix also flips the glyphs back up
+            write!(
+                content,
+                "BT /F{font} {size} Tf {color} rg {cos} {sin} {sin} {neg_cos} {x} {y} Tm ",
+                size = Num(span.font_size),
+                color = Rgb(span.color),
+                cos = Num(cos),
+                sin = Num(sin),
+                neg_cos = Num(-cos),
+                x = Num(at.0 + dx),
+                y = Num(at.1 + dy),
+            )?;
+            write_string_literal(content, &encoded)?;
+            writeln!(content, " Tj ET")?;
+
+            if let Some((y, thickness)) = span.decoration_line() {
+                let (x1, y1) = rotate((span.x, y), layout.rotation);
+                let (x2, y2) = rotate((span.x + span.width, y), layout.rotation);
+
+                writeln!(
+                    content,
+                    "q {} RG {} w {} {} m {} {} l S Q",
+                    Rgb(span.color),
+                    Num(thickness),
+                    Num(at.0 + x1),
+                    Num(at.1 + y1),
+                    Num(at.0 + x2),
+                    Num(at.1 + y2),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        let mut document = Vec::new();
+        // The binary comment marks the file as binary for transfer programs
+        document.extend_from_slice(b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n");
+
+        let objects = self.objects();
+        let mut offsets = Vec::with_capacity(objects.len());
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(document.len());
+            writeln!(document, "{} 0 obj", i + 1)?;
+            document.extend_from_slice(object);
+            document.extend_from_slice(b"\nendobj\n");
+        }
+
+        let xref_offset = document.len();
+        write!(
+            document,
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        )?;
+        for offset in offsets {
+            writeln!(document, "{offset:010} 00000 n ")?;
+        }
+        write!(
+            document,
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref_offset}\n%%EOF\n",
+            objects.len() + 1
+        )?;
+
+        self.writer.write_all(&document)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::TextAnchor;
+    use crate::text::TextStyle;
+
+    fn document() -> Vec<u8> {
+        let mut pdf = Vec::new();
+        let mut backend = PdfBackend::new(&mut pdf);
+
+        backend.begin(100., 50., Some(Color::WHITE)).unwrap();
+        backend
+            .draw_path(
+                &Path::rect(10, 10, 20, 20),
+                &PathStyle::new().stroke(Color::BLACK, 1),
+            )
+            .unwrap();
+        backend
+            .draw_text(
+                (50., 25.),
+                &RichText::new()
+                    .span("a(b)", TextStyle::new())
+                    .span("c", TextStyle::new().bold()),
+                &TextLayout::new(12, Color::BLACK).anchor(TextAnchor::Middle),
+            )
+            .unwrap();
+        backend.finish().unwrap();
+
+        pdf
+    }
+
+    #[test]
+    fn xref_offsets() {
+        let pdf = document();
+        // The offsets count bytes, so the binary comment is kept as is
+        let text = |offset: usize| String::from_utf8_lossy(&pdf[offset..]).into_owned();
+
+        let startxref = pdf
+            .windows(10)
+            .rposition(|window| window == b"startxref\n")
+            .unwrap()
+            + 10;
+        let xref_offset: usize = text(startxref).lines().next().unwrap().parse().unwrap();
+        let xref = text(xref_offset);
+        assert!(xref.starts_with("xref\n"));
+
+        let mut lines = xref.lines().skip(1);
+        let size: usize = lines
+            .next()
+            .unwrap()
+            .split(' ')
+            .nth(1)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!(xref.contains(&format!("/Size {size} ")));
+
+        let entries = lines.take(size).collect::<Vec<_>>();
+        assert_eq!(entries[0], "0000000000 65535 f ");
+        for (i, entry) in entries.iter().enumerate().skip(1) {
+            let offset: usize = entry[..10].parse().unwrap();
+            assert!(text(offset).starts_with(&format!("{i} 0 obj\n")));
+        }
+    }
+
+    #[test]
+    fn font_objects() {
+        let pdf = String::from_utf8_lossy(&document()).into_owned();
+
+        // Only the used fonts are defined and all of them have the widths of the layout
+        assert!(pdf.contains("/F0 "));
+        assert!(pdf.contains("/F1 "));
+        assert!(!pdf.contains("/F2 "));
+        assert!(!pdf.contains("/F3 "));
+
+        let fonts = pdf
+            .lines()
+            .filter(|line| line.starts_with("<< /Type /Font "))
+            .collect::<Vec<_>>();
+        assert_eq!(fonts.len(), 2);
+        assert!(fonts[0].contains("/BaseFont /Helvetica "));
+        assert!(fonts[1].contains("/BaseFont /Helvetica-Bold "));
+        for font in fonts {
+            assert!(font.contains("/FirstChar 32 /LastChar 255 /Widths ["));
+            assert!(font.contains("/FontDescriptor "));
+        }
+
+        assert!(pdf.contains(r"(a\(b\)) Tj"));
+    }
+}
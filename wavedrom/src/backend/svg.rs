@@ -0,0 +1,252 @@
+use std::io;
+
+use super::{
+    Backend, Fill, Num, Path, PathSegment, PathStyle, TextAnchor, TextBaseline, TextLayout,
+};
+use crate::escape::escape_str;
+use crate::text::RichText;
+use crate::{Color, Font};
+
+/// A [`Backend`] that writes an SVG image
+///
+/// The text of the SVG uses the font family of the embedded font by default.
+///
+/// The SVG defines patterns that are referred to by id. When several SVGs are inlined into the
+/// same HTML page, each of them needs its own [`SvgBackend::id_prefix`].
+#[derive(Debug)]
+pub struct SvgBackend<W: io::Write> {
+    writer: W,
+    font_family: String,
+    id_prefix: String,
+    /// The hatched fills that already have a pattern definition
+    hatches: Vec<(Color, Option<Color>)>,
+}
+
+impl<W: io::Write> SvgBackend<W> {
+    /// Create a new [`SvgBackend`] that writes into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            font_family: Font::default()
+                .get_font_family_name()
+                .unwrap_or_else(|| "Helvetica".to_string()),
+            id_prefix: String::new(),
+            hatches: Vec::new(),
+        }
+    }
+
+    /// Set the prefix that is put in front of all ids in the SVG.
+    pub fn id_prefix(mut self, id_prefix: impl Into<String>) -> Self {
+        self.id_prefix = id_prefix.into();
+        self
+    }
+
+    /// Returns the id of the pattern for a hatched fill and defines the pattern if it is used for
+    /// the first time.
+    fn hatch_id(&mut self, color: Color, background: Option<Color>) -> io::Result<String> {
+        let index = match self.hatches.iter().position(|h| *h == (color, background)) {
+            Some(index) => index,
+            None => {
+                self.hatches.push((color, background));
+                self.hatches.len() - 1
+            }
+        };
+
+        let id = if index == 0 {
+            format!("{}x-bg", self.id_prefix)
+        } else {
+            format!("{}x-bg{index}", self.id_prefix)
+        };
+        let id = escape_str(&id).into_owned();
+
+        if index + 1 == self.hatches.len() {
+            let writer = &mut self.writer;
+
+            write!(
+                writer,
+                r##"<defs><pattern id="{id}" patternUnits="userSpaceOnUse" width="4" height="10" patternTransform="rotate(45)">"##,
+            )?;
+
+            if let Some(background) = background {
+                write!(
+                    writer,
+                    r##"<rect x="0" y="0" width="4" height="10" fill="{background}"/>"##
+                )?;
+            }
+
+            write!(
+                writer,
+                r##"<line x1="0" y="0" x2="0" y2="10" stroke="{color}" stroke-width="1"/></pattern></defs>"##,
+            )?;
+        }
+
+        Ok(id)
+    }
+}
+
+impl<W: io::Write> Backend for SvgBackend<W> {
+    fn begin(&mut self, width: f64, height: f64, background: Option<Color>) -> io::Result<()> {
+        write!(
+            self.writer,
+            r#"<svg version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewport="0 0 {width} {height}" overflow="hidden" width="{width}" height="{height}">"#,
+            width = Num(width),
+            height = Num(height),
+        )?;
+
+        if let Some(background) = background {
+            write!(
+                self.writer,
+                r##"<rect width="100%" height="100%" fill="{background}"/>"##
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn begin_group(&mut self, (x, y): (f64, f64)) -> io::Result<()> {
+        if x == 0. && y == 0. {
+            write!(self.writer, "<g>")
+        } else {
+            write!(
+                self.writer,
+                r#"<g transform="translate({x},{y})">"#,
+                x = Num(x),
+                y = Num(y)
+            )
+        }
+    }
+
+    fn end_group(&mut self) -> io::Result<()> {
+        write!(self.writer, "</g>")
+    }
+
+    fn draw_path(&mut self, path: &Path, style: &PathStyle) -> io::Result<()> {
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        let fill = match style.fill {
+            Some(Fill::Solid(color)) => color.to_string(),
+            Some(Fill::Hatched { color, background }) => {
+                format!("url(#{})", self.hatch_id(color, background)?)
+            }
+            None => "none".to_string(),
+        };
+
+        let writer = &mut self.writer;
+
+        write!(writer, r#"<path d=""#)?;
+        let mut current = (0., 0.);
+        for segment in path.segments() {
+            match *segment {
+                PathSegment::MoveTo((x, y)) => write!(writer, "M{},{}", Num(x), Num(y))?,
+                PathSegment::LineTo((x, y)) if y == current.1 => write!(writer, "H{}", Num(x))?,
+                PathSegment::LineTo((x, y)) if x == current.0 => write!(writer, "V{}", Num(y))?,
+                PathSegment::LineTo((x, y)) => write!(writer, "L{},{}", Num(x), Num(y))?,
+                PathSegment::CurveTo((x1, y1), (x2, y2), (x, y)) => write!(
+                    writer,
+                    "C{},{} {},{} {},{}",
+                    Num(x1),
+                    Num(y1),
+                    Num(x2),
+                    Num(y2),
+                    Num(x),
+                    Num(y)
+                )?,
+                PathSegment::Close => write!(writer, "z")?,
+            }
+
+            current = match *segment {
+                PathSegment::MoveTo(p) | PathSegment::LineTo(p) | PathSegment::CurveTo(_, _, p) => {
+                    p
+                }
+                PathSegment::Close => current,
+            };
+        }
+        write!(writer, r#"" fill="{fill}""#)?;
+
+        if style.fill.is_some() && style.fill_opacity < 1. {
+            write!(writer, r#" fill-opacity="{}""#, Num(style.fill_opacity))?;
+        }
+
+        match style.stroke {
+            Some(stroke) => {
+                write!(
+                    writer,
+                    r#" stroke="{color}" stroke-width="{width}""#,
+                    color = stroke.color,
+                    width = Num(stroke.width),
+                )?;
+
+                if let Some(dash) = stroke.dash {
+                    write!(writer, r#" stroke-dasharray="{}""#, Num(dash))?;
+                }
+            }
+            None => write!(writer, r#" stroke="none""#)?,
+        }
+
+        write!(writer, "/>")
+    }
+
+    fn draw_text(
+        &mut self,
+        (x, y): (f64, f64),
+        text: &RichText,
+        layout: &TextLayout,
+    ) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let writer = &mut self.writer;
+
+        write!(writer, r#"<text x="{}" y="{}""#, Num(x), Num(y))?;
+
+        match layout.anchor {
+            TextAnchor::Start => {}
+            TextAnchor::Middle => write!(writer, r#" text-anchor="middle""#)?,
+            TextAnchor::End => write!(writer, r#" text-anchor="end""#)?,
+        }
+
+        match layout.baseline {
+            TextBaseline::Alphabetic => {}
+            TextBaseline::Middle => write!(writer, r#" dominant-baseline="middle""#)?,
+            TextBaseline::Hanging => write!(writer, r#" dominant-baseline="hanging""#)?,
+        }
+
+        write!(
+            writer,
+            r#" font-family="{}""#,
+            escape_str(layout.font_family.unwrap_or(&self.font_family))
+        )?;
+
+        if let Some(font_weight) = layout.font_weight {
+            write!(writer, r#" font-weight="{}""#, escape_str(font_weight))?;
+        }
+
+        write!(
+            writer,
+            r#" font-size="{font_size}" fill="{color}" letter-spacing="0""#,
+            font_size = layout.font_size,
+            color = layout.color,
+        )?;
+
+        if layout.rotation != 0 {
+            write!(
+                writer,
+                r#" transform="rotate({rotation},{x},{y})""#,
+                rotation = layout.rotation,
+                x = Num(x),
+                y = Num(y),
+            )?;
+        }
+
+        write!(writer, ">")?;
+        text.write_svg_spans(writer)?;
+        write!(writer, "</text>")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        write!(self.writer, "</svg>")
+    }
+}
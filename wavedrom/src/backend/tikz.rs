@@ -0,0 +1,376 @@
+use std::io;
+
+use super::{
+    hatch_lines, is_bold, Backend, Fill, Num, Path, PathSegment, PathStyle, TextAnchor,
+    TextBaseline, TextLayout,
+};
+use crate::text::{RichText, TextSpan};
+use crate::Color;
+
+/// The number of points per unit
+const SCALE: f64 = 0.75;
+
+/// A color as an `xcolor` expression
+struct TikzColor(Color);
+
+impl std::fmt::Display for TikzColor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Color { red, green, blue } = self.0;
+        write!(f, "{{rgb,255:red,{red};green,{green};blue,{blue}}}")
+    }
+}
+
+/// Escape the characters that have a special meaning in LaTeX.
+fn escape_latex(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str(r"\textbackslash{}"),
+            '~' => escaped.push_str(r"\textasciitilde{}"),
+            '^' => escaped.push_str(r"\textasciicircum{}"),
+            '{' | '}' | '#' | '$' | '%' | '&' | '_' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
+
+/// Returns the TikZ node anchor that corresponds to a text alignment.
+fn node_anchor(anchor: TextAnchor, baseline: TextBaseline) -> &'static str {
+    match (baseline, anchor) {
+        (TextBaseline::Alphabetic, TextAnchor::Start) => "base west",
+        (TextBaseline::Alphabetic, TextAnchor::Middle) => "base",
+        (TextBaseline::Alphabetic, TextAnchor::End) => "base east",
+        (TextBaseline::Middle, TextAnchor::Start) => "west",
+        (TextBaseline::Middle, TextAnchor::Middle) => "center",
+        (TextBaseline::Middle, TextAnchor::End) => "east",
+        (TextBaseline::Hanging, TextAnchor::Start) => "north west",
+        (TextBaseline::Hanging, TextAnchor::Middle) => "north",
+        (TextBaseline::Hanging, TextAnchor::End) => "north east",
+    }
+}
+
+/// Returns the LaTeX command that selects a font size in units.
+fn font_size_command(font_size: u32) -> String {
+    let size = f64::from(font_size) * SCALE;
+    format!(
+        r"\fontsize{{{}pt}}{{{}pt}}\selectfont",
+        Num(size),
+        Num(size * 1.2)
+    )
+}
+
+/// Write a [`TextSpan`] as LaTeX markup.
+fn write_latex_span(
+    writer: &mut impl io::Write,
+    span: &TextSpan,
+    layout: &TextLayout,
+) -> io::Result<()> {
+    let style = span.style();
+    let mut text = escape_latex(span.text());
+
+    if is_bold(style.font_weight.as_deref()) && !is_bold(layout.font_weight) {
+        text = format!(r"\textbf{{{text}}}");
+    }
+    if matches!(style.font_style.as_deref(), Some("italic" | "oblique")) {
+        text = format!(r"\textit{{{text}}}");
+    }
+    match style.text_decoration.as_deref() {
+        Some("underline") => text = format!(r"\underline{{{text}}}"),
+        Some("overline") => text = format!(r"$\overline{{\mbox{{{text}}}}}$"),
+        _ => {}
+    }
+    match style.baseline_shift.as_deref() {
+        Some("sub") => text = format!(r"\textsubscript{{{text}}}"),
+        Some("super") => text = format!(r"\textsuperscript{{{text}}}"),
+        _ => {}
+    }
+    if let Some(color) = style
+        .fill
+        .as_deref()
+        .and_then(|fill| fill.parse::<Color>().ok())
+    {
+        text = format!(
+            r"\textcolor[RGB]{{{},{},{}}}{{{text}}}",
+            color.red, color.green, color.blue
+        );
+    }
+    if let Some(font_size) = style.font_size {
+        text = format!("{{{}{text}}}", font_size_command(font_size));
+    }
+
+    write!(writer, "{text}")
+}
+
+/// Write the segments of a [`Path`] in the TikZ path syntax.
+fn write_tikz_path(writer: &mut impl io::Write, path: &Path) -> io::Result<()> {
+    for (i, segment) in path.segments().iter().enumerate() {
+        if i != 0 {
+            write!(writer, " ")?;
+        }
+
+        match *segment {
+            PathSegment::MoveTo((x, y)) => write!(writer, "({},{})", Num(x), Num(y))?,
+            PathSegment::LineTo((x, y)) => write!(writer, "-- ({},{})", Num(x), Num(y))?,
+            PathSegment::CurveTo((x1, y1), (x2, y2), (x, y)) => write!(
+                writer,
+                ".. controls ({},{}) and ({},{}) .. ({},{})",
+                Num(x1),
+                Num(y1),
+                Num(x2),
+                Num(y2),
+                Num(x),
+                Num(y)
+            )?,
+            PathSegment::Close => write!(writer, "-- cycle")?,
+        }
+    }
+
+    Ok(())
+}
+
+/// A [`Backend`] that writes a TikZ picture for LaTeX documents
+///
+/// The picture is a `tikzpicture` environment that can be included into a document that loads the
+/// `tikz` package. All text is placed in nodes that are typeset by LaTeX with the font of the
+/// document. Therefore, the text can be slightly wider or narrower than in the other backends.
+///
+/// Bold, italic, colored, underlined, overlined, subscript and superscript spans of a
+/// [`RichText`] are supported. Other text decorations are ignored.
+///
+/// # Examples
+///
+/// A standalone document can be made by wrapping the picture.
+///
+/// ```latex
+/// \documentclass{standalone}
+/// \usepackage{tikz}
+/// \begin{document}
+/// \input{figure.tex}
+/// \end{document}
+/// ```
+#[derive(Debug)]
+pub struct TikzBackend<W: io::Write> {
+    writer: W,
+}
+
+impl<W: io::Write> TikzBackend<W> {
+    /// Create a new [`TikzBackend`] that writes into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+}
+
+impl<W: io::Write> Backend for TikzBackend<W> {
+    fn begin(&mut self, width: f64, height: f64, background: Option<Color>) -> io::Result<()> {
+        let writer = &mut self.writer;
+
+        // Flip the y-axis so that the origin is in the top-left corner
+        writeln!(
+            writer,
+            r"\begin{{tikzpicture}}[x={SCALE}pt, y=-{SCALE}pt, miter limit=4]"
+        )?;
+        writeln!(
+            writer,
+            r"\useasboundingbox (0,0) rectangle ({},{});",
+            Num(width),
+            Num(height)
+        )?;
+
+        if let Some(background) = background {
+            writeln!(
+                writer,
+                r"\fill[fill={}] (0,0) rectangle ({},{});",
+                TikzColor(background),
+                Num(width),
+                Num(height)
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn begin_group(&mut self, (x, y): (f64, f64)) -> io::Result<()> {
+        writeln!(
+            self.writer,
+            r"\begin{{scope}}[shift={{({},{})}}]",
+            Num(x),
+            Num(y)
+        )
+    }
+
+    fn end_group(&mut self) -> io::Result<()> {
+        writeln!(self.writer, r"\end{{scope}}")
+    }
+
+    fn draw_path(&mut self, path: &Path, style: &PathStyle) -> io::Result<()> {
+        if path.is_empty() {
+            return Ok(());
+        }
+
+        let writer = &mut self.writer;
+        let opacity = |key: &str| {
+            if style.fill_opacity < 1. {
+                format!(", {key} opacity={}", Num(style.fill_opacity))
+            } else {
+                String::new()
+            }
+        };
+
+        match style.fill {
+            Some(Fill::Solid(color)) => {
+                write!(
+                    writer,
+                    r"\fill[fill={}{}] ",
+                    TikzColor(color),
+                    opacity("fill")
+                )?;
+                write_tikz_path(writer, path)?;
+                writeln!(writer, ";")?;
+            }
+            Some(Fill::Hatched { color, background }) => {
+                if let Some(background) = background {
+                    write!(
+                        writer,
+                        r"\fill[fill={}{}] ",
+                        TikzColor(background),
+                        opacity("fill")
+                    )?;
+                    write_tikz_path(writer, path)?;
+                    writeln!(writer, ";")?;
+                }
+
+                if let Some(bbox) = path.bounding_box() {
+                    write!(writer, r"\begin{{scope}}\clip ")?;
+                    write_tikz_path(writer, path)?;
+                    write!(
+                        writer,
+                        r"; \draw[draw={}, line width={SCALE}pt{}]",
+                        TikzColor(color),
+                        opacity("draw")
+                    )?;
+                    for ((x1, y1), (x2, y2)) in hatch_lines(bbox) {
+                        write!(
+                            writer,
+                            " ({},{}) -- ({},{})",
+                            Num(x1),
+                            Num(y1),
+                            Num(x2),
+                            Num(y2)
+                        )?;
+                    }
+                    writeln!(writer, r"; \end{{scope}}")?;
+                }
+            }
+            None => {}
+        }
+
+        if let Some(stroke) = style.stroke {
+            write!(
+                writer,
+                r"\draw[draw={}, line width={}pt",
+                TikzColor(stroke.color),
+                Num(stroke.width * SCALE)
+            )?;
+            if let Some(dash) = stroke.dash {
+                let dash = Num(dash * SCALE);
+                write!(writer, ", dash pattern=on {dash}pt off {dash}pt")?;
+            }
+            write!(writer, "] ")?;
+            write_tikz_path(writer, path)?;
+            writeln!(writer, ";")?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_text(
+        &mut self,
+        (x, y): (f64, f64),
+        text: &RichText,
+        layout: &TextLayout,
+    ) -> io::Result<()> {
+        if text.is_empty() {
+            return Ok(());
+        }
+
+        let writer = &mut self.writer;
+
+        write!(
+            writer,
+            r"\node[anchor={}, inner sep=0pt, text={}, font={}",
+            node_anchor(layout.anchor, layout.baseline),
+            TikzColor(layout.color),
+            font_size_command(layout.font_size),
+        )?;
+        if is_bold(layout.font_weight) {
+            write!(writer, r"\bfseries")?;
+        }
+        if layout.rotation != 0 {
+            // TikZ rotates counter-clockwise
+            write!(writer, ", rotate={}", (-layout.rotation).rem_euclid(360))?;
+        }
+        write!(writer, "] at ({},{}) {{", Num(x), Num(y))?;
+
+        for span in text.spans() {
+            write_latex_span(writer, span, layout)?;
+        }
+
+        writeln!(writer, "}};")
+    }
+
+    fn finish(&mut self) -> io::Result<()> {
+        writeln!(self.writer, r"\end{{tikzpicture}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escaping() {
+        assert_eq!(escape_latex("a_b"), r"a\_b");
+        assert_eq!(escape_latex("{50%}"), r"\{50\%\}");
+        assert_eq!(
+            escape_latex(r"\n~^"),
+            r"\textbackslash{}n\textasciitilde{}\textasciicircum{}"
+        );
+        assert_eq!(escape_latex("äb"), "äb");
+    }
+
+    #[test]
+    fn picture() {
+        let mut tikz = Vec::new();
+        let mut backend = TikzBackend::new(&mut tikz);
+
+        backend.begin(20., 10., None).unwrap();
+        backend
+            .draw_path(
+                &Path::new().move_to(0, 5).horizontal_by(20),
+                &PathStyle::new().stroke(Color::BLACK, 1),
+            )
+            .unwrap();
+        backend
+            .draw_text(
+                (10., 5.),
+                &RichText::from("D_0"),
+                &TextLayout::new(12, Color::BLACK).centered(),
+            )
+            .unwrap();
+        backend.finish().unwrap();
+
+        let tikz = String::from_utf8(tikz).unwrap();
+        assert!(tikz.starts_with(r"\begin{tikzpicture}[x=0.75pt, y=-0.75pt"));
+        assert!(tikz.contains(
+            r"\draw[draw={rgb,255:red,0;green,0;blue,0}, line width=0.75pt] (0,5) -- (20,5);"
+        ));
+        assert!(tikz.contains(r"at (10,5) {D\_0};"));
+        assert!(tikz.ends_with("\\end{tikzpicture}\n"));
+    }
+}
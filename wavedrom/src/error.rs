@@ -0,0 +1,236 @@
+//! The error that is produced when loading a WaveJson file
+use std::fmt::{self, Display};
+
+/// An error produced while parsing a [WaveJson][crate::wavejson] file.
+///
+/// Next to a message, the error contains the position of the value that caused the error, the
+/// JSON path to that value (e.g. `signal[3].wave`) and possibly a hint on how to fix the error.
+///
+/// The [`Display`] implementation gives a single line description. Use [`Error::snippet`] to
+/// display the error together with the offending line of the source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    message: String,
+    location: Location,
+    path: Option<String>,
+    hint: Option<String>,
+    source_line: String,
+}
+
+/// A one-based line and column in a WaveJson file. The column is counted in characters.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    /// The one-based line number
+    pub line: usize,
+    /// The one-based column number
+    pub column: usize,
+}
+
+/// A diagnostic displayed together with the line of the source that it refers to.
+///
+/// Created with [`Error::snippet`] or [`Lint::snippet`][crate::signal::lint::Lint::snippet].
+#[derive(Debug, Clone)]
+pub struct Snippet {
+    level: &'static str,
+    message: String,
+    location: Option<Location>,
+    path: Option<String>,
+    hint: Option<String>,
+    source_line: String,
+}
+
+impl Error {
+    pub(crate) fn new(
+        message: String,
+        location: Location,
+        path: Option<String>,
+        hint: Option<String>,
+        source_line: String,
+    ) -> Self {
+        Self {
+            message,
+            location,
+            path,
+            hint,
+            source_line,
+        }
+    }
+
+    /// Create an error from the position that `serde_json` reports for an error in `src`.
+    #[cfg(feature = "serde_json")]
+    pub(crate) fn from_serde_json(src: &str, error: &serde_json::Error) -> Self {
+        let message = error.to_string();
+        // The message of `serde_json` ends with the position, which we keep separately
+        let message = match message.rsplit_once(" at line ") {
+            Some((message, _)) => message.to_string(),
+            None => message,
+        };
+
+        let location = Location {
+            line: error.line().max(1),
+            column: error.column().max(1),
+        };
+        let source_line = src
+            .lines()
+            .nth(location.line - 1)
+            .unwrap_or_default()
+            .to_string();
+
+        Self::new(message, location, None, None, source_line)
+    }
+
+    /// Returns the description of the error.
+    #[inline]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Returns the position of the value that caused the error.
+    #[inline]
+    pub fn location(&self) -> Location {
+        self.location
+    }
+
+    /// Returns the JSON path to the value that caused the error (e.g. `signal[3].wave`).
+    ///
+    /// This is `None` if the error is caused by the top-level value.
+    #[inline]
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// Returns a human-readable hint on how to fix the error, if there is one.
+    #[inline]
+    pub fn hint(&self) -> Option<&str> {
+        self.hint.as_deref()
+    }
+
+    /// Move the location of the error down by `offset` lines.
+    ///
+    /// This is useful when the WaveJson is embedded in a larger document (e.g. a code block in a
+    /// Markdown file) and the error should point to the line in that document.
+    #[inline]
+    pub fn with_line_offset(mut self, offset: usize) -> Self {
+        self.location.line += offset;
+        self
+    }
+
+    /// Display the error together with the line of the source where the error occurred.
+    pub fn snippet(&self) -> Snippet {
+        Snippet {
+            level: "error",
+            message: self.message.clone(),
+            location: Some(self.location),
+            path: self.path.clone(),
+            hint: self.hint.clone(),
+            source_line: self.source_line.clone(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at line {}, column {}",
+            self.message, self.location.line, self.location.column
+        )?;
+
+        if let Some(path) = &self.path {
+            write!(f, " (in `{path}`)")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl Snippet {
+    #[cfg(feature = "json5")]
+    pub(crate) fn new(
+        level: &'static str,
+        message: String,
+        location: Option<Location>,
+        path: Option<String>,
+        source_line: String,
+    ) -> Self {
+        Self {
+            level,
+            message,
+            location,
+            path,
+            hint: None,
+            source_line,
+        }
+    }
+}
+
+impl Display for Snippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Snippet {
+            level,
+            message,
+            location,
+            path,
+            hint,
+            source_line,
+        } = self;
+
+        write!(f, "{level}: {message}")?;
+
+        let Some(location) = location else {
+            if let Some(path) = path {
+                write!(f, "\n --> `{path}`")?;
+            }
+
+            return Ok(());
+        };
+
+        let line_number = location.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+
+        write!(
+            f,
+            "\n{gutter}--> line {}, column {}",
+            location.line, location.column
+        )?;
+        if let Some(path) = path {
+            write!(f, " in `{path}`")?;
+        }
+        writeln!(f)?;
+
+        writeln!(f, "{gutter} |")?;
+        writeln!(f, "{line_number} | {source_line}")?;
+
+        // Keep tabs so that the marker lines up with the source line
+        let indent = source_line
+            .chars()
+            .take(location.column.saturating_sub(1))
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        write!(f, "{gutter} | {indent}^")?;
+
+        if let Some(hint) = hint {
+            write!(f, "\n{gutter} = hint: {hint}")?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "serde_json"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serde_json_location() {
+        let src = "{\n  \"signal\": [}\n}";
+        let error = serde_json::from_str::<serde_json::Value>(src).unwrap_err();
+        let error = Error::from_serde_json(src, &error);
+
+        assert_eq!(error.message(), "expected value");
+        assert_eq!((error.location().line, error.location().column), (2, 14));
+        assert_eq!(error.snippet().source_line, "  \"signal\": [}");
+    }
+}
@@ -0,0 +1,3 @@
+//! Exporters that turn figures into the input of other tools.
+
+pub mod vcd;
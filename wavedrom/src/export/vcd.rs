@@ -0,0 +1,528 @@
+//! Export of a [`SignalFigure`] as a Value Change Dump (VCD) file.
+//!
+//! This allows a hand-drawn protocol diagram to be used as the stimulus or golden reference of a
+//! simulator testbench. Every cycle takes a fixed number of timescale ticks and the periods and
+//! phases of the signals are kept. The groups of the figure become nested scopes.
+//!
+//! The states of a signal map to the following values.
+//!
+//! | State                       | Value                                         |
+//! |-----------------------------|-----------------------------------------------|
+//! | `0`, `l`, `L`, `d`          | `0`                                           |
+//! | `1`, `h`, `H`, `u`          | `1`                                           |
+//! | `z`                         | `z`                                           |
+//! | `x`                         | `x`                                           |
+//! | `p`, `P`, `n`, `N`          | A clock toggling halfway each period          |
+//! | `=`, `2` to `9`             | The numeric label of the box or otherwise `x` |
+//!
+//! A signal with boxes becomes a vector with enough bits for its largest label. Labels can be
+//! decimal or have a `0x` or `0b` prefix. A [`Signal::bus`] uses its own width and values.
+//!
+//! ```
+//! use wavedrom::export::vcd::VcdExportOptions;
+//! use wavedrom::signal::{Signal, SignalFigure};
+//!
+//! let figure = SignalFigure::new().add_signals([
+//!     Signal::with_cycle_str("p...").name("clk"),
+//!     Signal::with_cycle_str("x=.=").name("data").add_data_fields(["0x1F", "3"]),
+//! ]);
+//!
+//! let mut vcd = Vec::new();
+//! figure.write_vcd(&mut vcd, &VcdExportOptions::new()).unwrap();
+//! ```
+
+use std::io;
+
+use crate::import::vcd::VcdTime;
+use crate::signal::{CycleState, Signal, SignalFigure, SignalFigureSection, SignalPath};
+
+/// The options used to write a [`SignalFigure`] as a VCD file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcdExportOptions {
+    timescale: VcdTime,
+    timestep: u32,
+    scope: String,
+}
+
+/// A declaration in the scope hierarchy of the VCD file.
+enum Declaration {
+    Scope(String, Vec<Declaration>),
+    Variable(Variable),
+}
+
+/// A variable with its value changes in timescale ticks.
+struct Variable {
+    name: String,
+    width: u32,
+    is_vector: bool,
+    changes: Vec<(u64, String)>,
+    end: u64,
+}
+
+impl Default for VcdExportOptions {
+    fn default() -> Self {
+        Self {
+            timescale: VcdTime::from_femtoseconds(1_000_000),
+            timestep: 10,
+            scope: "top".to_string(),
+        }
+    }
+}
+
+impl VcdExportOptions {
+    /// Create a new [`VcdExportOptions`] with a timescale of `1ns` and `10` ticks per cycle.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the time of one tick.
+    ///
+    /// VCD files only allow `1`, `10` or `100` of a unit. Other times are written with a smaller
+    /// timescale and the ticks are scaled to match.
+    #[inline]
+    pub fn timescale(mut self, timescale: VcdTime) -> Self {
+        self.timescale = timescale;
+        self
+    }
+
+    /// Set the number of ticks per cycle. A timestep of zero is replaced by one tick.
+    #[inline]
+    pub fn timestep(mut self, timestep: u32) -> Self {
+        self.timestep = timestep.max(1);
+        self
+    }
+
+    /// Set the name of the scope that contains all the signals.
+    #[inline]
+    pub fn scope(mut self, scope: impl Into<String>) -> Self {
+        self.scope = scope.into();
+        self
+    }
+
+    /// Get the time of one tick.
+    #[inline]
+    pub fn get_timescale(&self) -> VcdTime {
+        self.timescale
+    }
+
+    /// Get the number of ticks per cycle.
+    #[inline]
+    pub fn get_timestep(&self) -> u32 {
+        self.timestep
+    }
+
+    /// Get the name of the scope that contains all the signals.
+    #[inline]
+    pub fn get_scope(&self) -> &str {
+        &self.scope
+    }
+}
+
+impl SignalFigure {
+    /// Write the [`SignalFigure`] as a VCD file.
+    ///
+    /// For more information, look at the [`export::vcd`][crate::export::vcd] documentation.
+    pub fn write_vcd(
+        &self,
+        writer: &mut impl io::Write,
+        options: &VcdExportOptions,
+    ) -> io::Result<()> {
+        let (timescale, tick_scale) = vcd_timescale(options.timescale);
+        let timestep = u64::from(options.timestep) * tick_scale;
+
+        let mut num_signals = 0;
+        let declarations = collect_declarations(self.sections(), timestep, &mut num_signals);
+
+        writeln!(writer, "$version")?;
+        writeln!(
+            writer,
+            "    {} {}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION")
+        )?;
+        writeln!(writer, "$end")?;
+        writeln!(writer, "$timescale {timescale} $end")?;
+
+        let mut variables = Vec::new();
+        writeln!(
+            writer,
+            "$scope module {} $end",
+            identifier(&options.scope, "top")
+        )?;
+        write_declarations(writer, &declarations, &mut variables)?;
+        writeln!(writer, "$upscope $end")?;
+        writeln!(writer, "$enddefinitions $end")?;
+
+        // The values at the first timestamp are the initial values of the dump
+        writeln!(writer, "#0")?;
+        writeln!(writer, "$dumpvars")?;
+        for (idx, variable) in variables.iter().enumerate() {
+            let initial = match variable.changes.first() {
+                Some((0, value)) => value.as_str(),
+                _ => "x",
+            };
+            write_value(writer, variable, initial, idx)?;
+        }
+        writeln!(writer, "$end")?;
+
+        let mut changes: Vec<(u64, usize, &str)> = variables
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, variable)| {
+                variable
+                    .changes
+                    .iter()
+                    .filter(|(time, _)| *time != 0)
+                    .map(move |(time, value)| (*time, idx, value.as_str()))
+            })
+            .collect();
+        changes.sort_by_key(|(time, idx, _)| (*time, *idx));
+
+        let mut current_time = 0;
+        for (time, idx, value) in changes {
+            if time != current_time {
+                writeln!(writer, "#{time}")?;
+                current_time = time;
+            }
+            write_value(writer, variables[idx], value, idx)?;
+        }
+
+        // Close the dump at the end of the longest signal
+        let end_time = variables
+            .iter()
+            .map(|variable| variable.end)
+            .max()
+            .unwrap_or_default();
+        if end_time > current_time {
+            writeln!(writer, "#{end_time}")?;
+        }
+
+        Ok(())
+    }
+}
+
+fn collect_declarations(
+    sections: &[SignalFigureSection],
+    timestep: u64,
+    num_signals: &mut usize,
+) -> Vec<Declaration> {
+    let mut declarations = Vec::new();
+
+    for section in sections {
+        match section {
+            SignalFigureSection::Signal(signal) => {
+                *num_signals += 1;
+                let fallback = format!("signal{num_signals}");
+                let name = identifier(&signal.get_name().to_plain_string(), &fallback);
+
+                declarations.push(Declaration::Variable(variable(signal, name, timestep)));
+            }
+            SignalFigureSection::Group(group) => {
+                let items = collect_declarations(group.items(), timestep, num_signals);
+
+                // Groups without a label are flattened into their parent scope
+                match group.label() {
+                    Some(label) if !label.is_empty() => {
+                        let name = identifier(&label.to_plain_string(), "group");
+                        declarations.push(Declaration::Scope(name, items));
+                    }
+                    _ => declarations.extend(items),
+                }
+            }
+        }
+    }
+
+    declarations
+}
+
+fn write_declarations<'a>(
+    writer: &mut impl io::Write,
+    declarations: &'a [Declaration],
+    variables: &mut Vec<&'a Variable>,
+) -> io::Result<()> {
+    for declaration in declarations {
+        match declaration {
+            Declaration::Scope(name, items) => {
+                writeln!(writer, "$scope module {name} $end")?;
+                write_declarations(writer, items, variables)?;
+                writeln!(writer, "$upscope $end")?;
+            }
+            Declaration::Variable(variable) => {
+                let id_code = id_code(variables.len());
+                let Variable { name, width, .. } = variable;
+
+                if variable.is_vector {
+                    let msb = width - 1;
+                    writeln!(writer, "$var wire {width} {id_code} {name} [{msb}:0] $end")?;
+                } else {
+                    writeln!(writer, "$var wire 1 {id_code} {name} $end")?;
+                }
+
+                variables.push(variable);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn write_value(
+    writer: &mut impl io::Write,
+    variable: &Variable,
+    value: &str,
+    idx: usize,
+) -> io::Result<()> {
+    let id_code = id_code(idx);
+
+    if variable.is_vector {
+        writeln!(writer, "b{value} {id_code}")
+    } else {
+        writeln!(writer, "{value}{id_code}")
+    }
+}
+
+/// Collect the value changes of a [`Signal`].
+fn variable(signal: &Signal, name: String, timestep: u64) -> Variable {
+    use CycleState::*;
+
+    let cycles = signal.cycles();
+
+    let numbers: Vec<Option<u64>> = match signal.get_bus() {
+        Some(bus) => bus.values().iter().copied().map(Some).collect(),
+        None => signal
+            .get_data_fields()
+            .iter()
+            .map(|field| parse_number(&field.to_plain_string()))
+            .collect(),
+    };
+
+    let is_vector = cycles.iter().any(|state| state.is_data());
+    let width = match signal.get_bus() {
+        Some(bus) => bus.width(),
+        None => numbers
+            .iter()
+            .flatten()
+            .map(|number| 64 - number.leading_zeros())
+            .max()
+            .unwrap_or_default()
+            .max(1),
+    };
+
+    // The start of every state and the end of the last state in ticks
+    let path = SignalPath::new(cycles, &[], signal.get_period(), signal.get_phase());
+    let offsets: Vec<u64> = path
+        .state_offsets()
+        .take(cycles.len() + 1)
+        .map(|offset| offset.ticks(timestep))
+        .collect();
+
+    let mut changes = Vec::new();
+    let mut push = |time: u64, value: String| {
+        if let Some((last_time, last_value)) = changes.last_mut() {
+            // A state without length is replaced by the next state
+            if *last_time == time {
+                *last_value = value;
+                return;
+            }
+
+            if *last_value == value {
+                return;
+            }
+        }
+
+        changes.push((time, value));
+    };
+
+    let mut numbers = numbers.into_iter();
+    let mut prev = None;
+
+    for (idx, state) in cycles.iter().enumerate() {
+        let (start, end) = (offsets[idx], offsets[idx + 1]);
+
+        let state = match state {
+            Continue | Gap => match prev {
+                // A continued clock keeps on toggling
+                Some(
+                    clock @ (PosedgeClockUnmarked | PosedgeClockMarked | NegedgeClockUnmarked
+                    | NegedgeClockMarked),
+                ) => clock,
+                _ => continue,
+            },
+            state => *state,
+        };
+        prev = Some(state);
+
+        let (first, second) = match state {
+            Bottom | LowUnmarked | LowMarked | Down => ("0", None),
+            Top | HighUnmarked | HighMarked | Up => ("1", None),
+            Middle => ("z", None),
+            X => ("x", None),
+            PosedgeClockUnmarked | PosedgeClockMarked => ("1", Some("0")),
+            NegedgeClockUnmarked | NegedgeClockMarked => ("0", Some("1")),
+            Box2 | Box3 | Box4 | Box5 | Box6 | Box7 | Box8 | Box9 | Data => {
+                let value = match numbers.next().flatten() {
+                    Some(number) => format!("{number:b}"),
+                    None => "x".to_string(),
+                };
+                push(start, value);
+                continue;
+            }
+            Continue | Gap => unreachable!(),
+        };
+
+        push(start, first.to_string());
+        if let Some(second) = second {
+            push(start + (end - start) / 2, second.to_string());
+        }
+    }
+
+    Variable {
+        name,
+        width,
+        is_vector,
+        changes,
+        end: offsets.last().copied().unwrap_or_default(),
+    }
+}
+
+/// Parse a decimal number or a number with a `0x` or `0b` prefix. Underscores are ignored.
+fn parse_number(text: &str) -> Option<u64> {
+    let text = text.trim().replace('_', "");
+
+    if let Some(hex) = text.strip_prefix("0x").or_else(|| text.strip_prefix("0X")) {
+        u64::from_str_radix(hex, 16).ok()
+    } else if let Some(bin) = text.strip_prefix("0b").or_else(|| text.strip_prefix("0B")) {
+        u64::from_str_radix(bin, 2).ok()
+    } else {
+        text.parse().ok()
+    }
+}
+
+/// Returns the largest timescale that VCD allows and that divides `timescale` together with the
+/// number of its ticks in one tick of `timescale`.
+fn vcd_timescale(timescale: VcdTime) -> (VcdTime, u64) {
+    let femtoseconds = timescale.as_femtoseconds().max(1);
+
+    let mut best = 1;
+    let mut unit = 1u64;
+    while let Some(next_unit) = unit.checked_mul(1000) {
+        for magnitude in [1, 10, 100] {
+            let candidate = unit * magnitude;
+            if femtoseconds % candidate == 0 {
+                best = candidate;
+            }
+        }
+
+        // Seconds are the largest unit
+        if unit == 1_000_000_000_000_000 {
+            break;
+        }
+        unit = next_unit;
+    }
+
+    (VcdTime::from_femtoseconds(best), femtoseconds / best)
+}
+
+/// Turn a name into a VCD identifier by replacing whitespace.
+fn identifier(name: &str, fallback: &str) -> String {
+    let name = name.trim();
+
+    if name.is_empty() {
+        return fallback.to_string();
+    }
+
+    name.chars()
+        .map(|c| if c.is_whitespace() { '_' } else { c })
+        .collect()
+}
+
+/// Returns the short identifier code for the `idx`-th variable using the printable ASCII
+/// characters.
+fn id_code(mut idx: usize) -> String {
+    const FIRST: u8 = b'!';
+    const NUM_CODES: usize = (b'~' - b'!' + 1) as usize;
+
+    let mut code = String::new();
+    loop {
+        code.push(char::from(FIRST + (idx % NUM_CODES) as u8));
+        idx /= NUM_CODES;
+
+        if idx == 0 {
+            return code;
+        }
+        idx -= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::import::vcd::{Vcd, VcdImportOptions};
+
+    #[test]
+    fn round_trip() {
+        let figure = SignalFigure::new().add_signals([
+            Signal::with_cycle_str("p...").name("clk"),
+            Signal::with_cycle_str("01zx").name("level"),
+            Signal::with_cycle_str("x=.3")
+                .name("data")
+                .add_data_fields(["0x1F", "seven"]),
+        ]);
+
+        let mut vcd = Vec::new();
+        figure
+            .write_vcd(&mut vcd, &VcdExportOptions::new().timestep(2))
+            .unwrap();
+        let vcd = String::from_utf8(vcd).unwrap();
+
+        assert!(vcd.contains("$timescale 1ns $end"));
+        assert!(vcd.contains("$var wire 5 # data [4:0] $end"));
+        assert!(vcd.contains("b11111 #"));
+
+        let imported = Vcd::parse(&vcd)
+            .unwrap()
+            .to_figure(
+                &VcdImportOptions::new()
+                    .clock("clk")
+                    .to("8ns".parse().unwrap()),
+            )
+            .unwrap();
+
+        let [SignalFigureSection::Group(top)] = imported.sections() else {
+            panic!("expected a single top scope");
+        };
+        let [_, SignalFigureSection::Signal(level), SignalFigureSection::Signal(data)] =
+            top.items()
+        else {
+            panic!("expected three signals");
+        };
+
+        let wave = |signal: &Signal| {
+            signal
+                .cycles()
+                .iter()
+                .map(|c| c.to_char())
+                .collect::<String>()
+        };
+        assert_eq!(wave(level), "01zx");
+        assert_eq!(wave(data), "x=.x");
+    }
+
+    #[test]
+    fn timescales() {
+        let time = VcdTime::from_femtoseconds;
+        assert_eq!(vcd_timescale(time(1_000_000)), (time(1_000_000), 1));
+        assert_eq!(vcd_timescale(time(100_000_000)), (time(100_000_000), 1));
+        assert_eq!(vcd_timescale(time(250_000)), (time(10_000), 25));
+        assert_eq!(vcd_timescale(time(0)), (time(1), 1));
+    }
+
+    #[test]
+    fn id_codes() {
+        assert_eq!(id_code(0), "!");
+        assert_eq!(id_code(93), "~");
+        assert_eq!(id_code(94), "!!");
+    }
+}
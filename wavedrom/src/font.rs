@@ -1,7 +1,14 @@
 use std::marker::PhantomData;
 
+use crate::text::RichText;
+
+/// The TTF file of the Helvetica font that is embedded into the binary.
+#[cfg(feature = "embed_font")]
+pub(crate) static EMBEDDED_FONT_DATA: &[u8] = include_bytes!("../helvetica.ttf");
+
 #[cfg(feature = "embed_font")]
-static EMBEDDED_HELVETICA: std::sync::OnceLock<ttf_parser::Face<'static>> = std::sync::OnceLock::new(); 
+static EMBEDDED_HELVETICA: std::sync::OnceLock<ttf_parser::Face<'static>> =
+    std::sync::OnceLock::new();
 
 /// The font that is used by the svg assembler to calculate text widths.
 ///
@@ -31,6 +38,24 @@ impl Font {
 
         width.ceil() as u32
     }
+
+    /// Get an upperbound on the text width for a given [`RichText`] and `font_size`.
+    ///
+    /// Spans that set their own font size are measured with that font size instead of
+    /// `font_size`.
+    pub fn get_rich_text_width(&self, text: &RichText, font_size: u32) -> u32 {
+        text.spans()
+            .iter()
+            .map(|span| {
+                self.get_text_width(span.text(), span.style().font_size.unwrap_or(font_size))
+            })
+            .sum()
+    }
+
+    /// Get the width of a string `s` in em units without any rounding.
+    pub(crate) fn get_em_text_width(&self, s: &str) -> f64 {
+        f64::from(self.get_pts_text_width(s)) / f64::from(self.units_per_em())
+    }
 }
 
 #[cfg(not(feature = "embed_font"))]
@@ -73,9 +98,7 @@ impl Font {
 #[cfg(feature = "embed_font")]
 impl Font {
     fn get_face(&self) -> &ttf_parser::Face<'static> {
-        EMBEDDED_HELVETICA.get_or_init(|| {
-            ttf_parser::Face::parse(include_bytes!("../helvetica.ttf"), 0).unwrap()
-        })
+        EMBEDDED_HELVETICA.get_or_init(|| ttf_parser::Face::parse(EMBEDDED_FONT_DATA, 0).unwrap())
     }
 
     #[inline]
@@ -88,8 +111,7 @@ impl Font {
 
         s.chars()
             .map(|c| {
-                face
-                    .glyph_index(c)
+                face.glyph_index(c)
                     .and_then(|g| face.glyph_hor_advance(g))
                     .map(u32::from)
                     .unwrap_or(face.global_bounding_box().width() as u32)
@@ -146,4 +168,4 @@ fn generate_lookup_table() {
     println!("Rest Advance: {:?}", face.global_bounding_box().width());
 
     assert!(false);
-}
\ No newline at end of file
+}
@@ -0,0 +1,3 @@
+//! Importers that turn the output of other tools into figures.
+
+pub mod vcd;
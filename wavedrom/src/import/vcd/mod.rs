@@ -0,0 +1,837 @@
+//! Import of Value Change Dump (VCD) files as written by most HDL simulators.
+//!
+//! A VCD file is parsed with [`Vcd::parse`] and turned into a [`SignalFigure`] with
+//! [`Vcd::to_figure`]. The [`VcdImportOptions`] select the signals by their hierarchical name, the
+//! time window and the way the signals are sampled into cycles. Every cycle is one period of a
+//! reference clock or of a fixed sample period. The signals are sampled at the end of each cycle
+//! and put in groups that follow the scopes of the VCD file.
+//!
+//! ```
+//! use wavedrom::import::vcd::{Vcd, VcdImportOptions};
+//!
+//! let vcd = Vcd::parse(r"
+//! $timescale 1ns $end
+//! $scope module top $end
+//! $var wire 1 ! clk $end
+//! $var wire 8 # data [7:0] $end
+//! $upscope $end
+//! $enddefinitions $end
+//! #0 0! b0 #
+//! #5 1!
+//! #10 0! b10101010 #
+//! #15 1!
+//! #20 0!
+//! #25 1!
+//! ").unwrap();
+//!
+//! let options = VcdImportOptions::new()
+//!     .clock("clk")
+//!     .from("5ns".parse().unwrap());
+//! let figure = vcd.to_figure(&options).unwrap();
+//! ```
+
+mod parser;
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use crate::signal::bus::BusFormat;
+use crate::signal::{
+    CycleState, Signal, SignalFigure, SignalFigureSection, SignalFigureSectionGroup,
+};
+
+/// The largest number of cycles that a [`Vcd`] is sampled into with a fixed sample period.
+pub const MAX_CYCLES: u64 = 10_000;
+
+/// A parsed Value Change Dump file.
+#[derive(Debug, Clone)]
+pub struct Vcd {
+    variables: Vec<VcdVariable>,
+    changes: Vec<Vec<(VcdTime, VcdValue)>>,
+    end_time: VcdTime,
+}
+
+/// A variable that is declared in a [`Vcd`] file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VcdVariable {
+    scope: Vec<String>,
+    name: String,
+    kind: String,
+    width: u32,
+    index: usize,
+}
+
+/// A point in simulation time with a resolution of one femtosecond.
+///
+/// A [`VcdTime`] can be parsed from a number with a unit (e.g. `100ns` or `1.5 us`). The known
+/// units are `s`, `ms`, `us`, `ns`, `ps` and `fs`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VcdTime(u64);
+
+/// The options used to turn a [`Vcd`] into a [`SignalFigure`].
+#[derive(Debug, Default, Clone)]
+pub struct VcdImportOptions {
+    signals: Vec<String>,
+    clock: Option<String>,
+    period: Option<VcdTime>,
+    start: Option<VcdTime>,
+    end: Option<VcdTime>,
+    bus_format: BusFormat,
+}
+
+/// An error while importing a [`Vcd`] file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VcdError {
+    /// The VCD file is malformed
+    Syntax {
+        /// The one-based line of the mistake
+        line: usize,
+        /// A description of the mistake
+        message: String,
+    },
+    /// A time that could not be parsed
+    InvalidTime(String),
+    /// The reference clock is not a variable in the VCD file
+    UnknownClock(String),
+    /// Neither a reference clock nor a sample period was given
+    MissingSamplePeriod,
+    /// No variable matches the signal patterns
+    NoSignals,
+    /// The time window contains no cycles
+    NoCycles,
+    /// The sample period splits the time window into more than [`MAX_CYCLES`] cycles
+    TooManyCycles(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum VcdValue {
+    Scalar(char),
+    Vector(String),
+    Real(String),
+}
+
+/// A group or signal in the scope hierarchy of the imported figure.
+enum ScopeItem {
+    Signal(Signal),
+    Scope(String, Vec<ScopeItem>),
+}
+
+impl Vcd {
+    /// Parse the content of a VCD file.
+    #[inline]
+    pub fn parse(source: &str) -> Result<Self, VcdError> {
+        parser::parse(source)
+    }
+
+    /// Returns the variables in the order in which they are declared.
+    #[inline]
+    pub fn variables(&self) -> &[VcdVariable] {
+        &self.variables
+    }
+
+    /// Returns the time of the last value change.
+    #[inline]
+    pub fn end_time(&self) -> VcdTime {
+        self.end_time
+    }
+
+    /// Sample the selected variables into a [`SignalFigure`].
+    pub fn to_figure(&self, options: &VcdImportOptions) -> Result<SignalFigure, VcdError> {
+        let clock = options
+            .clock
+            .as_ref()
+            .map(|clock| {
+                self.variables
+                    .iter()
+                    .find(|variable| variable.matches_name(clock))
+                    .ok_or_else(|| VcdError::UnknownClock(clock.clone()))
+            })
+            .transpose()?;
+
+        let cycles = self.cycles(clock, options)?;
+        if cycles.is_empty() {
+            return Err(VcdError::NoCycles);
+        }
+
+        let mut root = Vec::new();
+        for variable in &self.variables {
+            if !options.signals.is_empty()
+                && !options
+                    .signals
+                    .iter()
+                    .any(|pattern| glob_matches(pattern, &variable.hierarchical_name()))
+            {
+                continue;
+            }
+
+            let is_clock = clock.is_some_and(|clock| std::ptr::eq(clock, variable));
+            let signal = if is_clock {
+                Signal::with_cycle_str(format!("p{}", ".".repeat(cycles.len() - 1)))
+            } else {
+                self.sample(variable, &cycles, options.bus_format)
+            };
+
+            insert_in_scope(
+                &mut root,
+                &variable.scope,
+                signal.name(variable.name.as_str()),
+            );
+        }
+
+        if root.is_empty() {
+            return Err(VcdError::NoSignals);
+        }
+
+        Ok(SignalFigure::new().add_sections(root.into_iter().map(ScopeItem::into_section)))
+    }
+
+    /// Sample the selected variables into [`WaveJson`][crate::wavejson::WaveJson].
+    ///
+    /// This allows a trace to be saved with
+    /// [`WaveJson::to_json5`][crate::wavejson::WaveJson::to_json5] and be edited by hand.
+    #[cfg(feature = "serde")]
+    pub fn to_wavejson(
+        &self,
+        options: &VcdImportOptions,
+    ) -> Result<crate::wavejson::WaveJson, VcdError> {
+        let figure = self.to_figure(options)?;
+        Ok(crate::wavejson::WaveJson::Signal(Box::new(
+            (&figure).into(),
+        )))
+    }
+
+    /// Returns the start and end time of every cycle in the time window.
+    fn cycles(
+        &self,
+        clock: Option<&VcdVariable>,
+        options: &VcdImportOptions,
+    ) -> Result<Vec<(VcdTime, VcdTime)>, VcdError> {
+        let start = options.start.unwrap_or_default();
+
+        if let Some(clock) = clock {
+            let end = options.end.unwrap_or(VcdTime::MAX);
+
+            let mut prev = None;
+            let edges: Vec<VcdTime> = self.changes[clock.index]
+                .iter()
+                .filter_map(|(time, value)| {
+                    let is_high = value.bit() == Some(true);
+                    let is_edge = is_high && prev != Some(true);
+                    prev = value.bit();
+                    is_edge.then_some(*time)
+                })
+                .filter(|time| (start..end).contains(time))
+                .collect();
+
+            let mut cycles: Vec<_> = edges.windows(2).map(|edge| (edge[0], edge[1])).collect();
+
+            // The last edge only starts a cycle when the window explicitly ends after it
+            if let (Some(&last), Some(end)) = (edges.last(), options.end) {
+                let period = cycles
+                    .last()
+                    .map_or(u64::MAX, |(start, end)| end.0 - start.0);
+                let cycle_end = VcdTime(u64::min(end.0, last.0.saturating_add(period)));
+                cycles.push((last, cycle_end));
+            }
+
+            return Ok(cycles);
+        }
+
+        let Some(period) = options.period.filter(|period| period.0 > 0) else {
+            return Err(VcdError::MissingSamplePeriod);
+        };
+
+        // Without a window end, the last value change still gets a full cycle
+        let end = options
+            .end
+            .unwrap_or(VcdTime(self.end_time.0.saturating_add(period.0)));
+
+        let num_cycles = match end.0.checked_sub(start.0) {
+            Some(0) | None => 0,
+            Some(window) => (window - 1) / period.0 + 1,
+        };
+        if num_cycles > MAX_CYCLES {
+            return Err(VcdError::TooManyCycles(num_cycles));
+        }
+
+        let mut cycles = Vec::new();
+        let mut time = start;
+        while time < end {
+            let cycle_end = VcdTime(u64::min(time.0.saturating_add(period.0), end.0));
+            cycles.push((time, cycle_end));
+            time = cycle_end;
+        }
+
+        Ok(cycles)
+    }
+
+    /// Sample a variable at the end of every cycle.
+    fn sample(
+        &self,
+        variable: &VcdVariable,
+        cycles: &[(VcdTime, VcdTime)],
+        bus_format: BusFormat,
+    ) -> Signal {
+        let changes = &self.changes[variable.index];
+        let samples = cycles.iter().map(|(_, end)| {
+            let idx = changes.partition_point(|(time, _)| time.0 < end.0);
+            idx.checked_sub(1).map(|idx| &changes[idx].1)
+        });
+
+        if variable.kind.starts_with("real") {
+            let mut cycles = Vec::new();
+            let mut data = Vec::new();
+            let mut prev = None;
+
+            for sample in samples {
+                let value = match sample {
+                    Some(VcdValue::Real(value)) => Some(value.as_str()),
+                    _ => None,
+                };
+
+                cycles.push(match value {
+                    _ if prev == Some(value) => CycleState::Continue,
+                    Some(value) => {
+                        data.push(value.to_string());
+                        CycleState::Data
+                    }
+                    None => CycleState::X,
+                });
+                prev = Some(value);
+            }
+
+            Signal::new().add_cycles(cycles).add_data_fields(data)
+        } else if variable.width == 1 {
+            let mut prev = None;
+            let cycles = samples.map(|sample| {
+                let state = match sample.and_then(VcdValue::bit_char) {
+                    Some('0') => CycleState::Bottom,
+                    Some('1') => CycleState::Top,
+                    Some('z') => CycleState::Middle,
+                    _ => CycleState::X,
+                };
+
+                if prev == Some(state) {
+                    return CycleState::Continue;
+                }
+                prev = Some(state);
+                state
+            });
+
+            Signal::new().add_cycles(cycles.collect::<Vec<_>>())
+        } else {
+            Signal::bus(
+                variable.width,
+                samples.map(|sample| sample.and_then(VcdValue::number)),
+            )
+            .bus_format(bus_format)
+        }
+    }
+}
+
+impl VcdVariable {
+    /// Returns the names of the scopes that the variable is declared in from outer to inner.
+    #[inline]
+    pub fn scope(&self) -> &[String] {
+        &self.scope
+    }
+
+    /// Returns the name of the variable within its scope.
+    #[inline]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the type of the variable (e.g. `wire`, `reg` or `real`).
+    #[inline]
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    /// Returns the width of the variable in bits.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the name of the variable prefixed with its scopes (e.g. `top.cpu.clk`).
+    pub fn hierarchical_name(&self) -> String {
+        let mut name = self.scope.join(".");
+        if !name.is_empty() {
+            name.push('.');
+        }
+        name.push_str(&self.name);
+        name
+    }
+
+    /// Returns whether `name` is the hierarchical name or the name within the scope.
+    fn matches_name(&self, name: &str) -> bool {
+        self.name == name || self.hierarchical_name() == name
+    }
+}
+
+impl VcdTime {
+    /// The largest possible [`VcdTime`]
+    pub const MAX: Self = Self(u64::MAX);
+
+    /// Create a new [`VcdTime`] from a number of femtoseconds.
+    #[inline]
+    pub fn from_femtoseconds(femtoseconds: u64) -> Self {
+        Self(femtoseconds)
+    }
+
+    /// Returns the number of femtoseconds since the start of the simulation.
+    #[inline]
+    pub fn as_femtoseconds(self) -> u64 {
+        self.0
+    }
+}
+
+impl Display for VcdTime {
+    /// Display the time in the largest unit that represents it exactly (e.g. `1500ps`).
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        const UNITS: [(u64, &str); 5] = [
+            (1_000_000_000_000_000, "s"),
+            (1_000_000_000_000, "ms"),
+            (1_000_000_000, "us"),
+            (1_000_000, "ns"),
+            (1_000, "ps"),
+        ];
+
+        let (femtoseconds_per_unit, unit) = UNITS
+            .into_iter()
+            .find(|(femtoseconds_per_unit, _)| self.0 % femtoseconds_per_unit == 0 && self.0 != 0)
+            .unwrap_or((1, "fs"));
+
+        write!(f, "{}{unit}", self.0 / femtoseconds_per_unit)
+    }
+}
+
+impl FromStr for VcdTime {
+    type Err = VcdError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || VcdError::InvalidTime(s.to_string());
+
+        let s = s.trim();
+        let unit_start = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .ok_or_else(invalid)?;
+        let (number, unit) = s.split_at(unit_start);
+
+        let femtoseconds_per_unit: u128 = match unit.trim() {
+            "s" => 1_000_000_000_000_000,
+            "ms" => 1_000_000_000_000,
+            "us" => 1_000_000_000,
+            "ns" => 1_000_000,
+            "ps" => 1_000,
+            "fs" => 1,
+            _ => return Err(invalid()),
+        };
+
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if integer.is_empty() && fraction.is_empty() {
+            return Err(invalid());
+        }
+
+        let parse = |digits: &str| -> Result<u128, VcdError> {
+            if digits.is_empty() {
+                return Ok(0);
+            }
+            digits.parse().map_err(|_| invalid())
+        };
+
+        // Digits beyond the femtosecond resolution are ignored
+        let fraction = &fraction[..usize::min(fraction.len(), 18)];
+        let fraction_scale = 10u128.pow(fraction.len() as u32);
+
+        let femtoseconds = parse(integer)?
+            .checked_mul(femtoseconds_per_unit)
+            .and_then(|fs| {
+                fs.checked_add(parse(fraction).ok()? * femtoseconds_per_unit / fraction_scale)
+            })
+            .ok_or_else(invalid)?;
+
+        u64::try_from(femtoseconds).map(Self).map_err(|_| invalid())
+    }
+}
+
+impl VcdImportOptions {
+    /// Create a new [`VcdImportOptions`] that selects all variables.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Select the variables with a hierarchical name (e.g. `top.cpu.clk`) that matches
+    /// `pattern`.
+    ///
+    /// A `*` in the pattern matches any sequence of characters and a `?` matches any single
+    /// character. All variables are selected when no pattern is given.
+    #[inline]
+    pub fn signal(mut self, pattern: impl Into<String>) -> Self {
+        self.signals.push(pattern.into());
+        self
+    }
+
+    /// Select the variables with a hierarchical name that matches any of the `patterns`.
+    ///
+    /// For more information, look at [`VcdImportOptions::signal`].
+    #[inline]
+    pub fn signals(mut self, patterns: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        self.signals.extend(patterns.into_iter().map(Into::into));
+        self
+    }
+
+    /// Set the reference clock by its hierarchical name or its name within its scope.
+    ///
+    /// Every rising edge of the clock starts a new cycle. This takes precedence over
+    /// [`VcdImportOptions::period`].
+    #[inline]
+    pub fn clock(mut self, clock: impl Into<String>) -> Self {
+        self.clock = Some(clock.into());
+        self
+    }
+
+    /// Set a fixed period for the cycles, which is used when there is no reference clock.
+    #[inline]
+    pub fn period(mut self, period: VcdTime) -> Self {
+        self.period = Some(period);
+        self
+    }
+
+    /// Set the start of the time window.
+    #[inline]
+    pub fn from(mut self, start: VcdTime) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    /// Set the end of the time window.
+    #[inline]
+    pub fn to(mut self, end: VcdTime) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    /// Set the [`BusFormat`] for the multi-bit variables.
+    #[inline]
+    pub fn bus_format(mut self, format: BusFormat) -> Self {
+        self.bus_format = format;
+        self
+    }
+
+    /// Get the patterns that select the variables.
+    #[inline]
+    pub fn get_signals(&self) -> &[String] {
+        &self.signals
+    }
+
+    /// Get the name of the reference clock.
+    #[inline]
+    pub fn get_clock(&self) -> Option<&str> {
+        self.clock.as_deref()
+    }
+
+    /// Get the fixed period of the cycles.
+    #[inline]
+    pub fn get_period(&self) -> Option<VcdTime> {
+        self.period
+    }
+
+    /// Get the start of the time window.
+    #[inline]
+    pub fn get_from(&self) -> Option<VcdTime> {
+        self.start
+    }
+
+    /// Get the end of the time window.
+    #[inline]
+    pub fn get_to(&self) -> Option<VcdTime> {
+        self.end
+    }
+
+    /// Get the [`BusFormat`] for the multi-bit variables.
+    #[inline]
+    pub fn get_bus_format(&self) -> BusFormat {
+        self.bus_format
+    }
+}
+
+impl VcdValue {
+    /// Returns the value of a single bit, where `x` and `z` are `None`.
+    fn bit(&self) -> Option<bool> {
+        match self.bit_char()? {
+            '0' => Some(false),
+            '1' => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Returns the character of a single bit value.
+    fn bit_char(&self) -> Option<char> {
+        match self {
+            Self::Scalar(c) => Some(*c),
+            // Vectors are extended to the left, so the last character is the least significant bit
+            Self::Vector(bits) => bits.chars().last(),
+            Self::Real(_) => None,
+        }
+    }
+
+    /// Returns the number of a vector, where a value with `x` or `z` bits is `None`.
+    fn number(&self) -> Option<u64> {
+        let bits = match self {
+            Self::Scalar(c) => c.encode_utf8(&mut [0; 4]).to_string(),
+            Self::Vector(bits) => bits.clone(),
+            Self::Real(_) => return None,
+        };
+
+        // Only the lower 64 bits are kept
+        let bits = &bits[bits.len().saturating_sub(64)..];
+        if bits.is_empty() {
+            return None;
+        }
+        u64::from_str_radix(bits, 2).ok()
+    }
+}
+
+impl ScopeItem {
+    fn into_section(self) -> SignalFigureSection {
+        match self {
+            Self::Signal(signal) => SignalFigureSection::Signal(signal),
+            Self::Scope(name, items) => SignalFigureSection::Group(SignalFigureSectionGroup::new(
+                Some(name.into()),
+                items.into_iter().map(Self::into_section).collect(),
+            )),
+        }
+    }
+}
+
+impl Display for VcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, message } => write!(f, "{message} at line {line}"),
+            Self::InvalidTime(time) => {
+                write!(
+                    f,
+                    "invalid time `{time}`, expected a number with a unit (e.g. `100ns`)"
+                )
+            }
+            Self::UnknownClock(clock) => write!(f, "clock `{clock}` is not a variable in the VCD"),
+            Self::MissingSamplePeriod => write!(f, "a reference clock or sample period is needed"),
+            Self::NoSignals => write!(f, "no variable in the VCD matches the signal patterns"),
+            Self::NoCycles => write!(f, "the time window contains no cycles"),
+            Self::TooManyCycles(num_cycles) => write!(
+                f,
+                "the sample period gives {num_cycles} cycles, but at most {MAX_CYCLES} are allowed"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for VcdError {}
+
+/// Put `signal` in the group of its scope and create the groups that do not exist yet.
+///
+/// Only the last item on every level is reused, so that the order of the VCD file is kept.
+fn insert_in_scope(items: &mut Vec<ScopeItem>, scope: &[String], signal: Signal) {
+    let Some((name, inner_scope)) = scope.split_first() else {
+        items.push(ScopeItem::Signal(signal));
+        return;
+    };
+
+    match items.last_mut() {
+        Some(ScopeItem::Scope(last_name, inner_items)) if last_name == name => {
+            insert_in_scope(inner_items, inner_scope, signal);
+        }
+        _ => {
+            let mut inner_items = Vec::new();
+            insert_in_scope(&mut inner_items, inner_scope, signal);
+            items.push(ScopeItem::Scope(name.clone(), inner_items));
+        }
+    }
+}
+
+/// Returns whether `text` matches a `pattern` with `*` and `?` wildcards.
+fn glob_matches(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // The position of the last `*` and the text position it currently matches up to
+    let mut backtrack = None;
+
+    while t < text.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, t));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star, star_t)) => {
+                    p = star + 1;
+                    t = star_t + 1;
+                    backtrack = Some((star, star_t + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|c| *c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TRACE: &str = r"
+$timescale 1 ns $end
+$scope module top $end
+$var wire 1 ! clk $end
+$scope module cpu $end
+$var wire 1 % valid $end
+$var reg 4 # state [3:0] $end
+$upscope $end
+$upscope $end
+$enddefinitions $end
+$dumpvars
+0!
+0%
+bx #
+$end
+#5
+1!
+1%
+b11 #
+#10
+0!
+#15
+1!
+b1010 #
+#20
+0!
+0%
+#25
+1!
+";
+
+    #[test]
+    fn parse_time() {
+        assert_eq!("100ns".parse(), Ok(VcdTime(100_000_000)));
+        assert_eq!("1.5 us".parse(), Ok(VcdTime(1_500_000_000)));
+        assert_eq!(".25ps".parse(), Ok(VcdTime(250)));
+        assert!("100".parse::<VcdTime>().is_err());
+        assert!("10 years".parse::<VcdTime>().is_err());
+        assert_eq!(VcdTime(1_500_000).to_string(), "1500ps");
+        assert_eq!(VcdTime(2_000_000_000).to_string(), "2us");
+        assert_eq!(VcdTime(0).to_string(), "0fs");
+    }
+
+    #[test]
+    fn glob() {
+        assert!(glob_matches("top.cpu.*", "top.cpu.valid"));
+        assert!(glob_matches("*.valid", "top.cpu.valid"));
+        assert!(glob_matches("top.?pu.*d", "top.cpu.valid"));
+        assert!(!glob_matches("top.cpu.*", "top.clk"));
+        assert!(!glob_matches("top", "top.clk"));
+    }
+
+    #[test]
+    fn sample_on_clock() {
+        let vcd = Vcd::parse(TRACE).unwrap();
+        let figure = vcd
+            .to_figure(
+                &VcdImportOptions::new()
+                    .clock("clk")
+                    .to("30ns".parse().unwrap()),
+            )
+            .unwrap();
+
+        let [SignalFigureSection::Group(top)] = figure.sections() else {
+            panic!("expected a single top group");
+        };
+        assert_eq!(top.label().unwrap().to_plain_string(), "top");
+
+        let [SignalFigureSection::Signal(clk), SignalFigureSection::Group(cpu)] = top.items()
+        else {
+            panic!("expected the clock and the cpu group");
+        };
+        assert_eq!(clk.cycles().len(), 3);
+
+        let [SignalFigureSection::Signal(valid), SignalFigureSection::Signal(state)] = cpu.items()
+        else {
+            panic!("expected two signals in the cpu group");
+        };
+
+        let cycles = |signal: &Signal| {
+            signal
+                .cycles()
+                .iter()
+                .map(|c| c.to_char())
+                .collect::<String>()
+        };
+        assert_eq!(cycles(valid), "10.");
+        assert_eq!(cycles(state), "==.");
+        assert_eq!(state.get_bus().unwrap().values(), [0x3, 0xA]);
+    }
+
+    #[test]
+    fn select_and_window() {
+        let vcd = Vcd::parse(TRACE).unwrap();
+
+        let figure = vcd
+            .to_figure(
+                &VcdImportOptions::new()
+                    .signal("top.cpu.s*")
+                    .period("5ns".parse().unwrap())
+                    .from("10ns".parse().unwrap()),
+            )
+            .unwrap();
+        let [SignalFigureSection::Group(top)] = figure.sections() else {
+            panic!("expected a single top group");
+        };
+        let [SignalFigureSection::Group(cpu)] = top.items() else {
+            panic!("expected a single cpu group");
+        };
+        let [SignalFigureSection::Signal(state)] = cpu.items() else {
+            panic!("expected a single signal");
+        };
+        assert_eq!(state.cycles().len(), 4);
+
+        assert_eq!(
+            vcd.to_figure(&VcdImportOptions::new().clock("nope"))
+                .unwrap_err(),
+            VcdError::UnknownClock("nope".to_string())
+        );
+        assert_eq!(
+            vcd.to_figure(&VcdImportOptions::new()).unwrap_err(),
+            VcdError::MissingSamplePeriod
+        );
+        assert_eq!(
+            vcd.to_figure(&VcdImportOptions::new().period("1fs".parse().unwrap()))
+                .unwrap_err(),
+            VcdError::TooManyCycles(25_000_001)
+        );
+    }
+
+    #[test]
+    fn invalid_vector() {
+        let source = format!(
+            "$var wire 8 # data $end\n$enddefinitions $end\n#0\nb{}1 #\n",
+            "é".repeat(40)
+        );
+        assert!(matches!(
+            Vcd::parse(&source),
+            Err(VcdError::Syntax { line: 4, .. })
+        ));
+        assert!(matches!(
+            Vcd::parse("$var wire 2 # d $end\n#0\nb12 #\n"),
+            Err(VcdError::Syntax { line: 3, .. })
+        ));
+    }
+}
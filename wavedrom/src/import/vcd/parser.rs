@@ -0,0 +1,164 @@
+use std::collections::HashMap;
+use std::str::{Lines, SplitWhitespace};
+
+use super::{Vcd, VcdError, VcdTime, VcdValue, VcdVariable};
+
+/// Splits a VCD file into whitespace separated tokens and remembers the line of the last token.
+struct Tokenizer<'a> {
+    lines: Lines<'a>,
+    words: Option<SplitWhitespace<'a>>,
+    line: usize,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(source: &'a str) -> Self {
+        Self {
+            lines: source.lines(),
+            words: None,
+            line: 0,
+        }
+    }
+
+    fn error(&self, message: impl Into<String>) -> VcdError {
+        VcdError::Syntax {
+            line: self.line,
+            message: message.into(),
+        }
+    }
+
+    /// Returns the tokens up to the next `$end` of the section that started with `keyword`.
+    fn section(&mut self, keyword: &str) -> Result<Vec<&'a str>, VcdError> {
+        let mut content = Vec::new();
+
+        loop {
+            match self.next() {
+                Some("$end") => return Ok(content),
+                Some(token) => content.push(token),
+                None => return Err(self.error(format!("`{keyword}` is missing its `$end`"))),
+            }
+        }
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(word) = self.words.as_mut().and_then(Iterator::next) {
+                return Some(word);
+            }
+
+            self.words = Some(self.lines.next()?.split_whitespace());
+            self.line += 1;
+        }
+    }
+}
+
+pub(super) fn parse(source: &str) -> Result<Vcd, VcdError> {
+    let mut tokens = Tokenizer::new(source);
+
+    let mut timescale = VcdTime::from_femtoseconds(1_000_000);
+    let mut scope = Vec::new();
+    let mut variables = Vec::new();
+    let mut changes: Vec<Vec<(VcdTime, VcdValue)>> = Vec::new();
+    let mut id_codes = HashMap::new();
+
+    let mut time = VcdTime::default();
+    let mut end_time = VcdTime::default();
+
+    while let Some(token) = tokens.next() {
+        match token {
+            "$timescale" => {
+                let content = tokens.section(token)?.concat();
+                timescale = content
+                    .parse()
+                    .map_err(|_| tokens.error(format!("invalid timescale `{content}`")))?;
+            }
+            "$scope" => {
+                let content = tokens.section(token)?;
+                let Some(name) = content.get(1) else {
+                    return Err(tokens.error("`$scope` is missing a name"));
+                };
+                scope.push(name.to_string());
+            }
+            "$upscope" => {
+                tokens.section(token)?;
+                scope.pop();
+            }
+            "$var" => {
+                let content = tokens.section(token)?;
+                let [kind, width, id_code, name, ..] = content[..] else {
+                    return Err(tokens.error("`$var` needs a type, size, identifier and name"));
+                };
+                let width = width
+                    .parse()
+                    .map_err(|_| tokens.error(format!("invalid variable size `{width}`")))?;
+
+                // Variables with the same identifier code share their value changes
+                let index = *id_codes.entry(id_code.to_string()).or_insert_with(|| {
+                    changes.push(Vec::new());
+                    changes.len() - 1
+                });
+
+                variables.push(VcdVariable {
+                    scope: scope.clone(),
+                    name: name.to_string(),
+                    kind: kind.to_string(),
+                    width,
+                    index,
+                });
+            }
+            // The value changes within these sections are handled like all other value changes
+            "$dumpvars" | "$dumpall" | "$dumpon" | "$dumpoff" | "$end" => {}
+            keyword if keyword.starts_with('$') => {
+                tokens.section(keyword)?;
+            }
+            timestamp if timestamp.starts_with('#') => {
+                time = timestamp[1..]
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|ticks| ticks.checked_mul(timescale.as_femtoseconds()))
+                    .map(VcdTime::from_femtoseconds)
+                    .ok_or_else(|| tokens.error(format!("invalid timestamp `{timestamp}`")))?;
+                end_time = VcdTime::max(end_time, time);
+            }
+            change => {
+                let (value, id_code) = match change.chars().next() {
+                    Some('b' | 'B') => {
+                        let id_code = tokens.next().ok_or_else(|| {
+                            tokens.error("vector value is missing its identifier")
+                        })?;
+                        let bits = change[1..].to_ascii_lowercase();
+                        if let Some(c) = bits.chars().find(|c| !"01xz".contains(*c)) {
+                            return Err(tokens.error(format!("invalid bit `{c}` in `{change}`")));
+                        }
+                        (VcdValue::Vector(bits), id_code)
+                    }
+                    Some('r' | 'R') => {
+                        let id_code = tokens
+                            .next()
+                            .ok_or_else(|| tokens.error("real value is missing its identifier"))?;
+                        (VcdValue::Real(change[1..].to_string()), id_code)
+                    }
+                    Some(c @ ('0' | '1' | 'x' | 'X' | 'z' | 'Z')) => {
+                        (VcdValue::Scalar(c.to_ascii_lowercase()), &change[1..])
+                    }
+                    _ => return Err(tokens.error(format!("unexpected `{change}`"))),
+                };
+
+                let Some(index) = id_codes.get(id_code) else {
+                    return Err(tokens.error(format!("unknown identifier code `{id_code}`")));
+                };
+
+                changes[*index].push((time, value));
+            }
+        }
+    }
+
+    Ok(Vcd {
+        variables,
+        changes,
+        end_time,
+    })
+}
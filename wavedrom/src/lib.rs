@@ -90,6 +90,8 @@ wavedrom::render_json5(r##"
 //! * `skins`. Enabled by default. Adds the [`skin`] module, which defines the serialize and
 //! deserialize formats for WaveDrom skins. Also adds logic to merge a skin into an existing set of
 //! options.
+//! * `png`. Disabled by default. Adds the `write_png` methods, which rasterize a figure to a PNG
+//! image using the embedded [Helvetica][helvetica]. This enables the `embed_font` feature.
 //!
 //! # Rendering Process
 //!
@@ -116,6 +118,16 @@ wavedrom::render_json5(r##"
 //! expensive, it is recommended to wrap the [`io::Write`][std::io::Write] in a
 //! [`std::io::BufWriter`].
 //!
+//! The [`AssembledFigure::write_pdf`][signal::AssembledFigure::write_pdf] and
+//! [`AssembledFigure::write_eps`][signal::AssembledFigure::write_eps] methods write a PDF
+//! document or an Encapsulated PostScript file instead. The
+//! [`AssembledFigure::write_tikz`][signal::AssembledFigure::write_tikz] method writes a TikZ
+//! picture for LaTeX documents and the
+//! [`AssembledFigure::write_text`][signal::AssembledFigure::write_text] method writes the signals
+//! as plain text. Other formats can be supported by implementing a
+//! [`Backend`][backend::Backend] and passing it to
+//! [`AssembledFigure::render`][signal::AssembledFigure::render].
+//!
 //! [helvetica]: https://en.wikipedia.org/wiki/Helvetica
 //! [dtd]: https://en.wikipedia.org/wiki/Digital_timing_diagram
 //! [editor]: https://gburghoorn.com/wavedrom
@@ -143,21 +155,36 @@ pub use serde_json;
 #[cfg(feature = "skins")]
 pub mod skin;
 
+#[cfg(any(feature = "json5", feature = "serde_json"))]
+pub mod error;
+
+pub mod backend;
+pub mod text;
+
 mod color;
 mod font;
 mod shortcuts;
 mod escape;
+#[cfg(feature = "png")]
+mod png;
 
 #[macro_use]
 mod options;
 
 pub mod signal;
 pub mod reg;
+pub mod assign;
+pub mod export;
+pub mod import;
  
 pub use font::Font;
 pub use color::Color;
 pub use shortcuts::*;
 
+#[cfg(any(feature = "json5", feature = "serde_json"))]
+pub use error::Error;
+
+use self::assign::AssignFigure;
 use self::reg::RegisterFigure;
 use self::signal::SignalFigure;
 
@@ -180,6 +207,8 @@ define_options! {
         signal: signal::options::SignalOptions[signal::options::PartialSignalOptions],
         /// The options specific to register figures.
         reg: reg::options::RegisterOptions[reg::options::PartialRegisterOptions],
+        /// The options specific to assign figures.
+        assign: assign::options::AssignOptions[assign::options::PartialAssignOptions],
 
         /// The background colors for the Box2 to Box9 states
         backgrounds: [Color; 8] => [
@@ -195,6 +224,34 @@ define_options! {
 
         /// The background color of the undefined background pattern
         undefined_background: Option<Color> => None,
+
+        /// The prefix of the ids in SVG output
+        ///
+        /// Figures that are inlined into the same HTML page need different prefixes. Otherwise, a
+        /// figure can use the definitions of another figure.
+        id_prefix: String,
+    }
+}
+
+impl Options {
+    /// Returns an [`Options::id_prefix`] that is derived from the `source` of a figure and the
+    /// `options` it is rendered with.
+    ///
+    /// Figures with a different source or different options (e.g. another skin) get a different
+    /// prefix, and the same source with the same options always gives the same prefix. A page
+    /// that contains the exact same figure more than once still needs to tell them apart.
+    pub fn id_prefix_for(source: &str, options: &Options) -> String {
+        // 64-bit FNV-1a, which unlike the standard library hasher is stable between builds
+        let options = format!("{options:?}");
+        let hash = source
+            .bytes()
+            .chain([0])
+            .chain(options.bytes())
+            .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+                (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+            });
+
+        format!("wd{hash:016x}-")
     }
 }
 
@@ -289,4 +346,20 @@ pub enum Figure {
     Signal(SignalFigure),
 
     Register(RegisterFigure),
+
+    /// A figure containing a logic-circuit schematic
+    Assign(AssignFigure),
+}
+
+impl Figure {
+    /// Returns the name of the skin that the figure asks to be rendered with.
+    ///
+    /// For a WaveJson file, this is the `config.skin` value. The name can be resolved with a
+    /// [`SkinRegistry`][crate::skin::SkinRegistry].
+    pub fn skin(&self) -> Option<&str> {
+        match self {
+            Figure::Signal(figure) => figure.get_skin(),
+            Figure::Register(_) | Figure::Assign(_) => None,
+        }
+    }
 }
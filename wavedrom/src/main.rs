@@ -1,16 +1,80 @@
 use std::fmt::Display;
 use std::fs::File;
 use std::io::{self, stdin, stdout, BufWriter, Read, StdoutLock};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-use wavedrom::skin::Skin;
+use wavedrom::backend::{Backend, EpsBackend, PdfBackend, SvgBackend, TikzBackend};
+use wavedrom::import::vcd::{Vcd, VcdImportOptions, VcdTime};
+use wavedrom::signal::lint::LintLevel;
+use wavedrom::signal::TextStyle;
+use wavedrom::skin::{builtin, Skin, SkinRegistry};
+use wavedrom::wavejson::WaveJson;
 use wavedrom::Figure;
 
 #[derive(Default)]
 struct Flags {
     input: Option<PathBuf>,
     output: Option<PathBuf>,
+    format: Option<OutputFormat>,
+    scale: Option<f32>,
+    width: Option<u32>,
     skin: Option<PathBuf>,
+    lint: bool,
+    strict: bool,
+    from_vcd: Option<PathBuf>,
+    signals: Vec<String>,
+    clock: Option<String>,
+    period: Option<String>,
+    from: Option<String>,
+    to: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Svg,
+    Png,
+    Pdf,
+    Eps,
+    Tikz,
+    Text(TextStyle),
+}
+
+impl OutputFormat {
+    fn from_name(name: &str) -> Option<Self> {
+        match &name.to_ascii_lowercase()[..] {
+            "svg" => Some(Self::Svg),
+            "png" => Some(Self::Png),
+            "pdf" => Some(Self::Pdf),
+            "eps" => Some(Self::Eps),
+            "tikz" | "tex" => Some(Self::Tikz),
+            "txt" | "text" => Some(Self::Text(TextStyle::Unicode)),
+            "ascii" => Some(Self::Text(TextStyle::Ascii)),
+            _ => None,
+        }
+    }
+
+    /// Infer the format from the extension of the output file and default to SVG.
+    fn from_output(output: Option<&Path>) -> Self {
+        output
+            .and_then(Path::extension)
+            .and_then(|extension| extension.to_str())
+            .and_then(Self::from_name)
+            .unwrap_or(Self::Svg)
+    }
+}
+
+impl Display for OutputFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Svg => write!(f, "svg"),
+            Self::Png => write!(f, "png"),
+            Self::Pdf => write!(f, "pdf"),
+            Self::Eps => write!(f, "eps"),
+            Self::Tikz => write!(f, "tikz"),
+            Self::Text(TextStyle::Unicode) => write!(f, "txt"),
+            Self::Text(TextStyle::Ascii) => write!(f, "ascii"),
+        }
+    }
 }
 
 enum OutputWriter<'a> {
@@ -38,6 +102,7 @@ enum ParsingError {
     MissingArgument(String),
     UnexpectedArgument(String),
     InvalidFlag(String),
+    InvalidValue(String, String),
 }
 
 impl Display for ParsingError {
@@ -50,6 +115,9 @@ impl Display for ParsingError {
                 write!(f, "The argument '{arg}' is unexpected")
             }
             ParsingError::InvalidFlag(arg) => write!(f, "Flag '{arg}' is not valid"),
+            ParsingError::InvalidValue(arg, value) => {
+                write!(f, "The value '{value}' is not valid for flag '{arg}'")
+            }
         }
     }
 }
@@ -83,8 +151,23 @@ Takes a wavejson file from the STDIN and outputs a SVG to the STDOUT.
 
 Flags:
 -i/--input <path/to/input.json>: specify a path to a input wavejson file
--o/--output <path/to/output.svg>: specify a path to a output svg file
--s/--skin <path/to/skin.json>: specify a path to a skin file
+-o/--output <path/to/output.svg>: specify a path to a output svg, png, pdf, eps, tex or txt file
+-f/--format <svg|png|pdf|eps|tikz|txt|ascii>: specify the output format (default: inferred from
+    the output extension, png requires the png feature)
+--scale <number>: the number of pixels per svg unit for png output (default: 1)
+--width <number>: the maximum number of characters per line for txt and ascii output
+-s/--skin <name|path/to/skin.json>: specify a built-in skin (default, dark, narrow, lowkey,
+    print) or a path to a skin file
+--lint: report mistakes in the wavejson and fail on errors
+--strict: report mistakes in the wavejson and fail on errors and warnings
+
+VCD Import Flags:
+--from-vcd <path/to/trace.vcd>: read a VCD file instead of a wavejson file
+--signals <pattern,...>: select the signals by hierarchical name (e.g. top.cpu.*)
+--clock <name>: the reference clock that starts a new cycle on every rising edge
+--period <time>: the cycle period when there is no reference clock (e.g. 10ns)
+--from <time>: the start of the time window (e.g. 100ns)
+--to <time>: the end of the time window (e.g. 400ns)
         "
         .trim()
     }
@@ -113,6 +196,39 @@ Flags:
                             .into(),
                     );
                 }
+                "-f" | "--format" => {
+                    let format = args
+                        .next()
+                        .ok_or(ParsingError::MissingArgument(arg.clone()))?;
+                    flags.format = Some(
+                        OutputFormat::from_name(&format)
+                            .ok_or(ParsingError::InvalidValue(arg, format))?,
+                    );
+                }
+                "--scale" => {
+                    let scale = args
+                        .next()
+                        .ok_or(ParsingError::MissingArgument(arg.clone()))?;
+                    flags.scale = Some(
+                        scale
+                            .parse()
+                            .ok()
+                            .filter(|scale: &f32| scale.is_finite() && *scale > 0.0)
+                            .ok_or(ParsingError::InvalidValue(arg, scale))?,
+                    );
+                }
+                "--width" => {
+                    let width = args
+                        .next()
+                        .ok_or(ParsingError::MissingArgument(arg.clone()))?;
+                    flags.width = Some(
+                        width
+                            .parse()
+                            .ok()
+                            .filter(|width: &u32| *width > 0)
+                            .ok_or(ParsingError::InvalidValue(arg, width))?,
+                    );
+                }
                 "-s" | "--skin" => {
                     flags.skin = Some(
                         args.next()
@@ -120,6 +236,38 @@ Flags:
                             .into(),
                     );
                 }
+                "--from-vcd" => {
+                    flags.from_vcd = Some(
+                        args.next()
+                            .ok_or(ParsingError::MissingArgument(arg))?
+                            .into(),
+                    );
+                }
+                "--signals" => {
+                    let patterns = args.next().ok_or(ParsingError::MissingArgument(arg))?;
+                    flags.signals.extend(
+                        patterns
+                            .split(',')
+                            .map(|pattern| pattern.trim().to_string()),
+                    );
+                }
+                "--clock" => {
+                    flags.clock = Some(args.next().ok_or(ParsingError::MissingArgument(arg))?);
+                }
+                "--period" => {
+                    flags.period = Some(args.next().ok_or(ParsingError::MissingArgument(arg))?);
+                }
+                "--from" => {
+                    flags.from = Some(args.next().ok_or(ParsingError::MissingArgument(arg))?);
+                }
+                "--to" => {
+                    flags.to = Some(args.next().ok_or(ParsingError::MissingArgument(arg))?);
+                }
+                "--lint" => flags.lint = true,
+                "--strict" => {
+                    flags.lint = true;
+                    flags.strict = true;
+                }
                 "-h" | "--help" => {
                     Self::print_metadata();
                     println!();
@@ -146,38 +294,16 @@ fn main() {
         std::process::exit(1);
     });
 
-    let content = match flags.input {
-        None => {
-            let mut buffer = Vec::new();
-            let mut stdin = stdin().lock();
-            match stdin.read_to_end(&mut buffer) {
-                Ok(_) => {}
-                Err(err) => {
-                    eprintln!("[ERROR]: Failed to read stdin until end. Reason: {err}");
-                    std::process::exit(1);
-                }
-            }
-
-            match String::from_utf8(buffer) {
-                Ok(s) => s,
-                Err(err) => {
-                    eprintln!("[ERROR]: Stdin does not contain valid UTF-8. Reason: {err}");
-                    std::process::exit(1);
-                }
-            }
-        }
-        Some(input_path) => match std::fs::read_to_string(input_path) {
-            Ok(content) => content,
-            Err(err) => {
-                eprintln!("[ERROR]: Failed to read content from file. Reason: {err}");
-                std::process::exit(1);
-            }
-        },
-    };
+    let builtin_skin = flags
+        .skin
+        .as_ref()
+        .and_then(|skin| skin.to_str())
+        .and_then(builtin::get);
 
-    let options = match flags.skin {
-        None => wavedrom::Options::default(),
-        Some(ref skin_path) => {
+    let options = match (builtin_skin, &flags.skin) {
+        (Some(skin), _) => skin.options(),
+        (None, None) => wavedrom::Options::default(),
+        (None, Some(skin_path)) => {
             let skin = match std::fs::read_to_string(skin_path) {
                 Ok(content) => content,
                 Err(err) => {
@@ -196,14 +322,20 @@ fn main() {
         }
     };
 
-    let figure = match Figure::from_json5(&content) {
-        Ok(figure) => figure,
-        Err(err) => {
-            eprintln!("[ERROR]: Failed to parse content of file. Reason:");
-            eprintln!("{err}");
-            std::process::exit(1);
-        }
+    let figure = match &flags.from_vcd {
+        Some(vcd_path) => vcd_figure(vcd_path, &flags),
+        None => wavejson_figure(&flags),
     };
+    let options = SkinRegistry::default().figure_options(&figure, &options);
+
+    let format = flags
+        .format
+        .unwrap_or_else(|| OutputFormat::from_output(flags.output.as_deref()));
+
+    if format == OutputFormat::Png && cfg!(not(feature = "png")) {
+        eprintln!("[ERROR]: PNG output requires wavedrom to be built with the `png` feature");
+        std::process::exit(1);
+    }
 
     let mut writer = BufWriter::new(match flags.output {
         None => OutputWriter::Stdio(stdout().lock()),
@@ -225,16 +357,200 @@ fn main() {
         }
     });
 
-    let result = match figure {
-        Figure::Signal(figure) => {
-            let assembled = figure.assemble_with_options(&options);
-            assembled.write_svg_with_options(&mut writer, &options)
-        }
-        Figure::Register(register) => register.write_svg(&mut writer),
+    let result = match format {
+        OutputFormat::Svg => render(
+            &figure,
+            &mut SvgBackend::new(&mut writer).id_prefix(&options.id_prefix),
+            &options,
+        ),
+        OutputFormat::Pdf => render(&figure, &mut PdfBackend::new(&mut writer), &options),
+        OutputFormat::Eps => render(&figure, &mut EpsBackend::new(&mut writer), &options),
+        OutputFormat::Tikz => render(&figure, &mut TikzBackend::new(&mut writer), &options),
+        OutputFormat::Png => write_png(&figure, &mut writer, &options, flags.scale.unwrap_or(1.0)),
+        OutputFormat::Text(style) => write_text(
+            &figure,
+            &mut writer,
+            &options,
+            style,
+            flags.width.unwrap_or(u32::MAX),
+        ),
     };
 
     if let Err(err) = result {
-        eprintln!("[ERROR]: Failed to write out svg. Reason: {err}");
+        eprintln!("[ERROR]: Failed to write out {format}. Reason: {err}");
         std::process::exit(1);
     }
 }
+
+fn render(
+    figure: &Figure,
+    backend: &mut impl Backend,
+    options: &wavedrom::Options,
+) -> io::Result<()> {
+    match figure {
+        Figure::Signal(figure) => {
+            let assembled = figure.assemble_with_options(options);
+            assembled.render_with_options(backend, options)
+        }
+        Figure::Register(register) => register.render_with_options(backend, options),
+        Figure::Assign(assign) => assign.render_with_options(backend, options),
+    }
+}
+
+fn write_text(
+    figure: &Figure,
+    writer: &mut impl io::Write,
+    options: &wavedrom::Options,
+    style: TextStyle,
+    width: u32,
+) -> io::Result<()> {
+    match figure {
+        Figure::Signal(figure) => {
+            let assembled = figure.assemble_with_options(options);
+            assembled.write_text_with_width(writer, style, width)
+        }
+        Figure::Register(_) | Figure::Assign(_) => Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "text output is only supported for signal figures",
+        )),
+    }
+}
+
+#[cfg(feature = "png")]
+fn write_png(
+    figure: &Figure,
+    writer: &mut impl io::Write,
+    options: &wavedrom::Options,
+    scale: f32,
+) -> io::Result<()> {
+    match figure {
+        Figure::Signal(figure) => {
+            let assembled = figure.assemble_with_options(options);
+            assembled.write_png_with_options(writer, options, scale)
+        }
+        Figure::Register(register) => register.write_png_with_options(writer, options, scale),
+        Figure::Assign(assign) => assign.write_png_with_options(writer, options, scale),
+    }
+}
+
+#[cfg(not(feature = "png"))]
+fn write_png(
+    _figure: &Figure,
+    _writer: &mut impl io::Write,
+    _options: &wavedrom::Options,
+    _scale: f32,
+) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "wavedrom is built without the `png` feature",
+    ))
+}
+
+fn wavejson_figure(flags: &Flags) -> Figure {
+    let content = match &flags.input {
+        None => {
+            let mut buffer = Vec::new();
+            let mut stdin = stdin().lock();
+            match stdin.read_to_end(&mut buffer) {
+                Ok(_) => {}
+                Err(err) => {
+                    eprintln!("[ERROR]: Failed to read stdin until end. Reason: {err}");
+                    std::process::exit(1);
+                }
+            }
+
+            match String::from_utf8(buffer) {
+                Ok(s) => s,
+                Err(err) => {
+                    eprintln!("[ERROR]: Stdin does not contain valid UTF-8. Reason: {err}");
+                    std::process::exit(1);
+                }
+            }
+        }
+        Some(input_path) => match std::fs::read_to_string(input_path) {
+            Ok(content) => content,
+            Err(err) => {
+                eprintln!("[ERROR]: Failed to read content from file. Reason: {err}");
+                std::process::exit(1);
+            }
+        },
+    };
+
+    let wavejson = match WaveJson::from_json5(&content) {
+        Ok(wavejson) => wavejson,
+        Err(err) => {
+            eprintln!("[ERROR]: Failed to parse content of file. Reason:");
+            eprintln!("{}", err.snippet());
+            std::process::exit(1);
+        }
+    };
+
+    if flags.lint {
+        let lints = wavejson.validate();
+
+        for lint in &lints {
+            eprintln!("{}", lint.snippet(&content));
+            eprintln!();
+        }
+
+        let fail_level = if flags.strict {
+            LintLevel::Warning
+        } else {
+            LintLevel::Error
+        };
+
+        // Without `--strict`, warnings are shown but do not count as mistakes
+        let mistakes = lints
+            .iter()
+            .filter(|lint| lint.level() >= fail_level)
+            .count();
+        if mistakes > 0 {
+            eprintln!("[ERROR]: Found {mistakes} mistake(s) in the wavejson");
+            std::process::exit(1);
+        }
+    }
+
+    Figure::from(wavejson)
+}
+
+fn vcd_figure(vcd_path: &Path, flags: &Flags) -> Figure {
+    let content = match std::fs::read_to_string(vcd_path) {
+        Ok(content) => content,
+        Err(err) => {
+            eprintln!("[ERROR]: Failed to read content from VCD file. Reason: {err}");
+            std::process::exit(1);
+        }
+    };
+
+    let parse_time = |time: &Option<String>| {
+        time.as_ref().map(|time| {
+            time.parse::<VcdTime>().unwrap_or_else(|err| {
+                eprintln!("[ERROR]: {err}");
+                std::process::exit(1);
+            })
+        })
+    };
+
+    let mut options = VcdImportOptions::new().signals(&flags.signals);
+    if let Some(clock) = &flags.clock {
+        options = options.clock(clock);
+    }
+    if let Some(period) = parse_time(&flags.period) {
+        options = options.period(period);
+    }
+    if let Some(from) = parse_time(&flags.from) {
+        options = options.from(from);
+    }
+    if let Some(to) = parse_time(&flags.to) {
+        options = options.to(to);
+    }
+
+    let figure = Vcd::parse(&content).and_then(|vcd| vcd.to_figure(&options));
+    match figure {
+        Ok(figure) => Figure::Signal(figure),
+        Err(err) => {
+            eprintln!("[ERROR]: Failed to import VCD file. Reason: {err}");
+            std::process::exit(1);
+        }
+    }
+}
@@ -65,7 +65,7 @@ macro_rules! define_options {
         }
 
         #[cfg(feature = "skins")]
-        #[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+        #[derive(Debug, Clone, Default, serde::Deserialize, serde::Serialize)]
         $(
         #[$opt_struct_doc]
         )*
@@ -0,0 +1,58 @@
+//! The rasterization of the generated SVG into a PNG image.
+
+use std::io;
+use std::sync::Arc;
+
+use resvg::{tiny_skia, usvg};
+
+use crate::font::EMBEDDED_FONT_DATA;
+use crate::Font;
+
+/// Rasterize an `svg` with `scale` pixels per SVG unit and write it as a PNG image to `writer`.
+///
+/// The text is drawn with the embedded Helvetica font, so the result does not depend on the fonts
+/// that are installed on the system.
+pub(crate) fn write_png(svg: &[u8], writer: &mut impl io::Write, scale: f32) -> io::Result<()> {
+    if !scale.is_finite() || scale <= 0.0 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "the PNG scale should be a positive number",
+        ));
+    }
+
+    let mut fontdb = usvg::fontdb::Database::new();
+    fontdb.load_font_data(EMBEDDED_FONT_DATA.to_vec());
+
+    let options = usvg::Options {
+        font_family: Font::default()
+            .get_font_family_name()
+            .unwrap_or_else(|| String::from("Helvetica")),
+        fontdb: Arc::new(fontdb),
+        ..usvg::Options::default()
+    };
+
+    let tree = usvg::Tree::from_data(svg, &options)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+    let size = tree.size();
+    let width = (size.width() * scale).ceil() as u32;
+    let height = (size.height() * scale).ceil() as u32;
+
+    let Some(mut pixmap) = tiny_skia::Pixmap::new(width, height) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("a PNG image of {width}x{height} pixels cannot be created"),
+        ));
+    };
+
+    resvg::render(
+        &tree,
+        tiny_skia::Transform::from_scale(scale, scale),
+        &mut pixmap.as_mut(),
+    );
+
+    let png = pixmap
+        .encode_png()
+        .map_err(|err| io::Error::new(io::ErrorKind::Other, err))?;
+    writer.write_all(&png)
+}
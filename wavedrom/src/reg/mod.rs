@@ -5,6 +5,12 @@ pub struct RegisterFigure {
     lanes: Vec<Lane>,
     vspace: Option<u32>,
     hspace: Option<u32>,
+    fontsize: Option<u32>,
+    font_weight: Option<String>,
+    font_family: Option<String>,
+    compact: bool,
+    hflip: bool,
+    bit_offset: u32,
 }
 
 pub enum FieldString {
@@ -27,17 +33,23 @@ pub struct LaneBitRange {
 
 impl FromIterator<Lane> for RegisterFigure {
     fn from_iter<T: IntoIterator<Item = Lane>>(iter: T) -> Self {
-        Self {
-            lanes: <Vec<Lane>>::from_iter(iter),
-            vspace: None,
-            hspace: None,
-        }
+        Self::with(<Vec<Lane>>::from_iter(iter))
     }
 }
 
 impl RegisterFigure {
     pub fn with(lanes: Vec<Lane>) -> Self {
-        Self { lanes, vspace: None, hspace: None }
+        Self {
+            lanes,
+            vspace: None,
+            hspace: None,
+            fontsize: None,
+            font_weight: None,
+            font_family: None,
+            compact: false,
+            hflip: false,
+            bit_offset: 0,
+        }
     }
 
     pub fn vspace(mut self, vspace: u32) -> Self {
@@ -49,6 +61,88 @@ impl RegisterFigure {
         self.hspace = Some(hspace);
         self
     }
+
+    /// Set the font size of the field names and attributes.
+    pub fn fontsize(mut self, fontsize: u32) -> Self {
+        self.fontsize = Some(fontsize);
+        self
+    }
+
+    /// Set the font weight of all text (e.g. `bold` or `700`).
+    pub fn font_weight(mut self, font_weight: impl Into<String>) -> Self {
+        self.font_weight = Some(font_weight.into());
+        self
+    }
+
+    /// Set the font family of all text.
+    pub fn font_family(mut self, font_family: impl Into<String>) -> Self {
+        self.font_family = Some(font_family.into());
+        self
+    }
+
+    /// Merge the bit numbers of all lanes into one header above the first lane and draw the lanes
+    /// without spacing in between.
+    pub fn compact(mut self, compact: bool) -> Self {
+        self.compact = compact;
+        self
+    }
+
+    /// Draw the least significant bit on the left instead of on the right.
+    pub fn hflip(mut self, hflip: bool) -> Self {
+        self.hflip = hflip;
+        self
+    }
+
+    /// Add an offset to all the displayed bit numbers.
+    pub fn bit_offset(mut self, bit_offset: u32) -> Self {
+        self.bit_offset = bit_offset;
+        self
+    }
+
+    /// Returns the lanes of the figure from top to bottom.
+    pub fn lanes(&self) -> &[Lane] {
+        &self.lanes
+    }
+
+    /// Returns the height of the lanes, if it was set.
+    pub fn get_vspace(&self) -> Option<u32> {
+        self.vspace
+    }
+
+    /// Returns the width of the lanes, if it was set.
+    pub fn get_hspace(&self) -> Option<u32> {
+        self.hspace
+    }
+
+    /// Returns the font size of the field names and attributes, if it was set.
+    pub fn get_fontsize(&self) -> Option<u32> {
+        self.fontsize
+    }
+
+    /// Returns the font weight of all text, if it was set.
+    pub fn get_font_weight(&self) -> Option<&str> {
+        self.font_weight.as_deref()
+    }
+
+    /// Returns the font family of all text, if it was set.
+    pub fn get_font_family(&self) -> Option<&str> {
+        self.font_family.as_deref()
+    }
+
+    /// Returns whether the lanes are drawn without spacing in between.
+    pub fn get_compact(&self) -> bool {
+        self.compact
+    }
+
+    /// Returns whether the least significant bit is drawn on the left.
+    pub fn get_hflip(&self) -> bool {
+        self.hflip
+    }
+
+    /// Returns the offset that is added to all the displayed bit numbers.
+    pub fn get_bit_offset(&self) -> u32 {
+        self.bit_offset
+    }
 }
 
 impl LaneBitRange {
@@ -79,6 +173,26 @@ impl LaneBitRange {
         }
     }
 
+    /// Returns the name of the field.
+    pub fn get_name(&self) -> Option<&FieldString> {
+        self.name.as_ref()
+    }
+
+    /// Returns the attributes that are displayed below the field.
+    pub fn attributes(&self) -> &[FieldString] {
+        &self.attributes
+    }
+
+    /// Returns the number of bits of the field.
+    pub fn length(&self) -> u32 {
+        self.length
+    }
+
+    /// Returns the type of the field that determines its background color.
+    pub fn variant(&self) -> u32 {
+        self.variant
+    }
+
     // pub fn name(mut self, name: impl Into<String>) -> Self {
     //     self.name = Some(name.into());
     //     self
@@ -129,4 +243,19 @@ impl Lane {
         self.bit_ranges.push(bit_range);
         self
     }
+
+    /// Returns the fields of the lane. The bits after the last field are padding.
+    pub fn bit_ranges(&self) -> &[LaneBitRange] {
+        &self.bit_ranges
+    }
+
+    /// Returns the number of the first bit of the lane.
+    pub fn get_start_bit(&self) -> u32 {
+        self.start_bit
+    }
+
+    /// Returns the number of bits in the lane.
+    pub fn width(&self) -> u32 {
+        self.width
+    }
 }
@@ -1,3 +1,5 @@
+use crate::Color;
+
 define_options! {
     /// The options used while rendering a register figure
     RegisterOptions,
@@ -16,6 +18,25 @@ define_options! {
         name_fontsize: u32 => 16,
         bit_marker_fontsize: u32 => 12,
         attribute_fontsize: u32 => 16,
+
+        /// The font family of all text. This uses the font family of the backend by default.
+        font_family: Option<String> => None,
+        /// The color of the field names, attributes and bit numbers
+        text_color: Color => Color::BLACK,
+        /// The color of the lane outlines, field separators and bit hint markers
+        line_color: Color => Color::BLACK,
+        /// The fill color of fields without a type
+        field_background: Option<Color> => None,
+        /// The fill colors of fields with the types `1` to `7`. Types above `7` use the last color.
+        type_fills: [Color; 7] => [
+            Color { red: 0xBB, green: 0x55, blue: 0x55 },
+            Color { red: 0xCC, green: 0xCC, blue: 0xCC },
+            Color { red: 0x55, green: 0xBB, blue: 0x55 },
+            Color { red: 0x55, green: 0xBB, blue: 0x55 },
+            Color { red: 0x55, green: 0xBB, blue: 0x55 },
+            Color { red: 0x55, green: 0xBB, blue: 0x55 },
+            Color { red: 0x55, green: 0xBB, blue: 0x55 },
+        ],
     }
 }
 
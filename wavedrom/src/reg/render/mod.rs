@@ -1,116 +1,306 @@
 use std::io;
 
-use crate::escape::escape_str;
-use crate::{Font, Options};
+use crate::backend::{
+    to_display_num, Backend, EpsBackend, Path, PathStyle, PdfBackend, SvgBackend, TextAnchor,
+    TextBaseline, TextLayout,
+};
+use crate::text::RichText;
+use crate::{Color, Options};
 
 use super::{FieldString, Lane, LaneBitRange, RegisterFigure};
 
-const DISPLAY_PRECISION: u8 = 3;
-const DISPLAY_PRECISION_ROUNDING: f64 = {
-    let mut n = 10.;
-    let mut i = 0;
-    loop {
-        if i >= DISPLAY_PRECISION {
-            break n;
-        }
-
-        n *= 10.;
-        i += 1;
-    }
-};
-
-fn to_display_num(n: f64) -> f64 {
-    (n * DISPLAY_PRECISION_ROUNDING).round() / DISPLAY_PRECISION_ROUNDING
+/// The state that is shared by all lanes while rendering
+struct RenderContext<'a> {
+    options: &'a Options,
+    font_family: Option<&'a str>,
+    font_weight: Option<&'a str>,
+    /// The amount of bits that span the full width of a bar
+    bar_bits: u32,
+    hflip: bool,
+    bit_offset: u32,
 }
 
 impl RegisterFigure {
+    /// Render a [`RegisterFigure`] into a `writer`.
+    #[inline]
     pub fn write_svg(&self, writer: &mut impl io::Write) -> io::Result<()> {
         self.write_svg_with_options(writer, &Options::default())
     }
 
+    /// Render a [`RegisterFigure`] into a `writer` with a set of options.
     pub fn write_svg_with_options(
         &self,
         writer: &mut impl io::Write,
         options: &Options,
     ) -> io::Result<()> {
-        let mut height = f64::from(options.reg.padding.top + options.reg.padding.bottom);
+        self.render_with_options(
+            &mut SvgBackend::new(writer).id_prefix(&options.id_prefix),
+            options,
+        )
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as a PDF document.
+    #[inline]
+    pub fn write_pdf(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_pdf_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as a PDF document with a set of options.
+    pub fn write_pdf_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut PdfBackend::new(writer), options)
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as an Encapsulated PostScript file.
+    #[inline]
+    pub fn write_eps(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_eps_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as an Encapsulated PostScript file with a set
+    /// of options.
+    pub fn write_eps_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut EpsBackend::new(writer), options)
+    }
+
+    /// Render a [`RegisterFigure`] with a [`Backend`].
+    #[inline]
+    pub fn render(&self, backend: &mut impl Backend) -> io::Result<()> {
+        self.render_with_options(backend, &Options::default())
+    }
+
+    /// Render a [`RegisterFigure`] with a [`Backend`] and a set of options.
+    pub fn render_with_options(
+        &self,
+        backend: &mut impl Backend,
+        options: &Options,
+    ) -> io::Result<()> {
+        let options = &self.figure_options(options);
+        let ctx = self.render_context(options);
+
+        let lane_spacing = if self.compact {
+            0
+        } else {
+            options.reg.spacing.lane_spacing
+        };
+
+        let height = self.display_height(options);
+
+        backend.begin(
+            f64::from(options.reg.padding.left + options.reg.padding.right + options.reg.bar_width),
+            to_display_num(height),
+            options.background,
+        )?;
+
+        let mut y = f64::from(options.reg.padding.top);
         let mut displayed_lanes = 0;
+
         for lane in &self.lanes {
             if lane.is_empty() {
-                height += f64::from(options.reg.spacing.lane_spacing);
+                y += f64::from(lane_spacing);
 
                 continue;
             }
 
+            let has_bit_markers = !self.compact || displayed_lanes == 0;
             displayed_lanes += 1;
-            height += lane.display_height(options, self.vspace)
-                + f64::from(options.reg.spacing.lane_spacing);
-        }
 
-        let height = if displayed_lanes == 0 {
-            0.
-        } else {
-            height - f64::from(options.reg.spacing.lane_spacing)
-        };
+            backend.begin_group((f64::from(options.reg.padding.left), to_display_num(y)))?;
 
-        write!(
-            writer,
-            r#"<svg version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewport="0 0 {figure_width} {figure_height}" overflow="hidden" width="{figure_width}" height="{figure_height}">"#,
-            figure_width =
-                options.reg.padding.left + options.reg.padding.right + options.reg.bar_width,
-            figure_height = to_display_num(height),
-        )?;
+            if self.compact && has_bit_markers {
+                ctx.write_compact_bit_markers(backend)?;
+            }
 
-        if self.lanes.is_empty() {
-            write!(writer, "</svg>")?;
-            return Ok(());
+            lane.render(backend, &ctx, has_bit_markers, !self.compact)?;
+
+            let lane_height = lane.display_height(options, has_bit_markers);
+
+            y += lane_height + f64::from(lane_spacing);
+
+            backend.end_group()?;
         }
 
-        write!(
-            writer,
-            r##"<defs><g id="bm"><path d="M0,0v{hint_indent}m0,{jump}v{hint_indent}" stroke="#000" fill="none"/></g></defs>"##,
-            jump = options.reg.bar_height - 2 * options.reg.hint_indent,
-            hint_indent = options.reg.hint_indent,
-        )?;
+        backend.finish()
+    }
 
-        let mut y = f64::from(options.reg.padding.top);
+    /// Returns the state that is shared by all lanes while rendering with the figure `options`.
+    fn render_context<'a>(&'a self, options: &'a Options) -> RenderContext<'a> {
+        RenderContext {
+            options,
+            font_family: options.reg.font_family.as_deref(),
+            font_weight: self.font_weight.as_deref(),
+            bar_bits: self
+                .lanes
+                .iter()
+                .map(|lane| lane.width)
+                .max()
+                .unwrap_or_default(),
+            hflip: self.hflip,
+            bit_offset: self.bit_offset,
+        }
+    }
+
+    /// Returns the height of the figure with the figure `options`.
+    fn display_height(&self, options: &Options) -> f64 {
+        let lane_spacing = if self.compact {
+            0
+        } else {
+            options.reg.spacing.lane_spacing
+        };
 
+        let mut height = f64::from(options.reg.padding.top + options.reg.padding.bottom);
+        let mut displayed_lanes = 0;
         for lane in &self.lanes {
             if lane.is_empty() {
-                y += f64::from(options.reg.spacing.lane_spacing);
+                height += f64::from(lane_spacing);
 
                 continue;
             }
 
-            write!(
-                writer,
-                r##"<g transform="translate({x},{y})">"##,
-                x = options.reg.padding.left,
-            )?;
+            let has_bit_markers = !self.compact || displayed_lanes == 0;
+
+            displayed_lanes += 1;
+            height += lane.display_height(options, has_bit_markers) + f64::from(lane_spacing);
+        }
+
+        if displayed_lanes == 0 {
+            0.
+        } else {
+            height - f64::from(lane_spacing)
+        }
+    }
+
+    /// Apply the configuration of the figure to the `options`.
+    fn figure_options(&self, options: &Options) -> Options {
+        let mut options = options.clone();
+
+        if let Some(vspace) = self.vspace {
+            options.reg.bar_height = vspace;
+        }
+        if let Some(hspace) = self.hspace {
+            options.reg.bar_width = hspace;
+        }
+        if let Some(fontsize) = self.fontsize {
+            options.reg.name_fontsize = fontsize;
+            options.reg.attribute_fontsize = fontsize;
+        }
+        if let Some(font_family) = &self.font_family {
+            options.reg.font_family = Some(font_family.clone());
+        }
+
+        options
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as a PNG image.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    #[inline]
+    pub fn write_png(&self, writer: &mut impl io::Write, scale: f32) -> io::Result<()> {
+        self.write_png_with_options(writer, &Options::default(), scale)
+    }
+
+    /// Render a [`RegisterFigure`] into a `writer` as a PNG image with a set of options.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    pub fn write_png_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+        scale: f32,
+    ) -> io::Result<()> {
+        let mut svg = Vec::new();
+        self.write_svg_with_options(&mut svg, options)?;
+        crate::png::write_png(&svg, writer, scale)
+    }
+}
+
+impl<'a> RenderContext<'a> {
+    /// Returns the horizontal position of a bit boundary within a lane. Bit `0` is the boundary at
+    /// the least significant side of the lane.
+    ///
+    /// The bits of all lanes line up. Therefore, a lane with less bits than the widest lane leaves
+    /// the space of its missing most significant bits empty.
+    fn bit_x(&self, bit: f64) -> f64 {
+        let bar_width = f64::from(self.options.reg.bar_width);
+        let bit_width = bar_width / f64::from(self.bar_bits);
+
+        if self.hflip {
+            bit * bit_width
+        } else {
+            bar_width - bit * bit_width
+        }
+    }
+
+    /// Returns the layout for a text with the color and font of the figure.
+    fn text_layout(&self, font_size: u32, anchor: TextAnchor) -> TextLayout<'a> {
+        let mut layout = TextLayout::new(font_size, self.options.reg.text_color).anchor(anchor);
+
+        if let Some(font_family) = self.font_family {
+            layout = layout.font_family(font_family);
+        }
+        if let Some(font_weight) = self.font_weight {
+            layout = layout.font_weight(font_weight);
+        }
 
-            lane.write_svg(writer, options)?;
+        layout
+    }
 
-            let lane_height = lane.display_height(options, self.vspace);
+    /// Draw a bit number at the top of a lane.
+    fn write_bit_number(
+        &self,
+        backend: &mut impl Backend,
+        x: f64,
+        bit: u32,
+        anchor: TextAnchor,
+    ) -> io::Result<()> {
+        let fontsize = self.options.reg.bit_marker_fontsize;
 
-            y += lane_height + f64::from(options.reg.spacing.lane_spacing);
+        backend.draw_text(
+            (to_display_num(x), f64::from(fontsize)),
+            &RichText::from(bit.to_string()),
+            &self.text_layout(fontsize, anchor),
+        )
+    }
 
-            write!(writer, "</g>")?;
+    /// Draw the bit numbers of all the bits in a bar above the first lane.
+    fn write_compact_bit_markers(&self, backend: &mut impl Backend) -> io::Result<()> {
+        for i in 0..self.bar_bits {
+            self.write_bit_number(
+                backend,
+                self.bit_x(f64::from(i) + 0.5),
+                i.saturating_add(self.bit_offset),
+                TextAnchor::Middle,
+            )?;
         }
 
-        write!(writer, "</svg>")?;
         Ok(())
     }
 }
 
 impl Lane {
-    fn write_svg(&self, writer: &mut impl io::Write, options: &Options) -> io::Result<()> {
+    fn render(
+        &self,
+        backend: &mut impl Backend,
+        ctx: &RenderContext,
+        has_bit_markers: bool,
+        has_field_bit_markers: bool,
+    ) -> io::Result<()> {
         if self.width == 0 {
             return Ok(());
         }
 
-        let bar_width = options.reg.bar_width;
+        let options = ctx.options;
         let bar_height = options.reg.bar_height;
-        let bar_y = options.reg.bit_marker_fontsize + options.reg.offset.bit_marker_y;
+        let bar_y = self.bar_y(options, has_bit_markers);
 
         let mut offset = 0;
         for bit_range in &self.bit_ranges {
@@ -120,7 +310,7 @@ impl Lane {
 
             let offset_end = offset + bit_range.length;
 
-            bit_range.write_svg(writer, offset, self.width, self.start_bit, options)?;
+            bit_range.render(backend, ctx, self, offset, bar_y, has_field_bit_markers)?;
 
             offset = offset_end;
         }
@@ -132,12 +322,13 @@ impl Lane {
             .sum();
 
         if amount_of_field_bits != self.width {
-            LaneBitRange::new_padding(self.width - amount_of_field_bits).write_svg(
-                writer,
+            LaneBitRange::new_padding(self.width - amount_of_field_bits).render(
+                backend,
+                ctx,
+                self,
                 amount_of_field_bits,
-                self.width,
-                self.start_bit,
-                options,
+                bar_y,
+                has_field_bit_markers,
             )?;
         }
 
@@ -154,26 +345,40 @@ impl Lane {
             }
 
             // Draw field separation markers
-            write!(
-                writer,
-                r##"<path d="M{x},{bar_y}v{bar_height}" stroke="#000" stroke-width="2"/>"##,
-                x = to_display_num(
-                    f64::from(bar_width) - (f64::from(offset * bar_width)) / f64::from(self.width)
-                ),
+            backend.draw_path(
+                &Path::new()
+                    .move_to(to_display_num(ctx.bit_x(f64::from(offset))), bar_y)
+                    .vertical_by(bar_height),
+                &PathStyle::new().stroke(options.reg.line_color, 2),
             )?;
         }
 
-        write!(
-            writer,
-            r##"<path d="M0,{bar_y}h{bar_width}v{bar_height}H0V{bar_y}z" stroke="#000" stroke-width="2" fill="none"/>"##
+        let start_x = ctx.bit_x(f64::from(self.width));
+        let end_x = ctx.bit_x(0.);
+        backend.draw_path(
+            &Path::rect(
+                to_display_num(f64::min(start_x, end_x)),
+                bar_y,
+                to_display_num((end_x - start_x).abs()),
+                bar_height,
+            ),
+            &PathStyle::new().stroke(options.reg.line_color, 2),
         )?;
 
         Ok(())
     }
 
-    pub fn display_height(&self, options: &Options, vspace: Option<u32>) -> f64 {
-        let bit_marker_height = options.reg.bit_marker_fontsize + options.reg.offset.bit_marker_y;
-        let bar_height = vspace.unwrap_or(options.reg.bar_height);
+    fn bar_y(&self, options: &Options, has_bit_markers: bool) -> u32 {
+        if has_bit_markers {
+            options.reg.bit_marker_fontsize + options.reg.offset.bit_marker_y
+        } else {
+            0
+        }
+    }
+
+    pub fn display_height(&self, options: &Options, has_bit_markers: bool) -> f64 {
+        let bit_marker_height = self.bar_y(options, has_bit_markers);
+        let bar_height = options.reg.bar_height;
         let max_attributes = self
             .bit_ranges
             .iter()
@@ -193,212 +398,321 @@ impl Lane {
 }
 
 impl LaneBitRange {
-    fn write_svg(
+    fn render(
         &self,
-        writer: &mut impl io::Write,
+        backend: &mut impl Backend,
+        ctx: &RenderContext,
+        lane: &Lane,
         offset: u32,
-        bit_width: u32,
-        start_bit: u32,
-        options: &Options,
+        bar_y: u32,
+        has_bit_markers: bool,
     ) -> io::Result<()> {
         if self.length == 0 {
             return Ok(());
         }
 
-        let font = Font::default();
+        let options = ctx.options;
+        let bit_x = |bit: f64| to_display_num(ctx.bit_x(bit));
 
-        let font_family = font
-            .get_font_family_name()
-            .unwrap_or_else(|| String::from("Helvetica"));
-
-        let bar_width = options.reg.bar_width;
         let bar_height = options.reg.bar_height;
-        let bar_y = options.reg.bit_marker_fontsize + options.reg.offset.bit_marker_y;
-
         let bar_middle = f64::from(bar_y + bar_y + bar_height) / 2.;
 
         let offset_start = offset;
         let offset_end = offset + self.length;
 
         // Draw background
-        if self.variant != 0 {
-            let background = match self.variant {
-                1 => "#B55",
-                2 => "#CCC",
-                _ => "#5B5",
-            };
-
-            // TODO: Can this be deleted
-            let width = to_display_num(if offset_end == bit_width {
-                f64::from(bar_width) - f64::from(offset_start * bar_width) / f64::from(bit_width)
-            } else {
-                f64::from(self.length * bar_width) / f64::from(bit_width)
-            });
-
-            write!(
-                writer,
-                r##"<path d="M{x},{bar_y}h{width}v{bar_height}h-{width}v-{bar_height}z" stroke="none" fill="{background}"/>"##,
-                x = to_display_num(
-                    f64::from(bar_width) - f64::from(offset_end * bar_width) / f64::from(bit_width)
+        if let Some(background) = self.background(options) {
+            let start_x = bit_x(f64::from(offset_start));
+            let end_x = bit_x(f64::from(offset_end));
+
+            backend.draw_path(
+                &Path::rect(
+                    f64::min(start_x, end_x),
+                    bar_y,
+                    to_display_num((start_x - end_x).abs()),
+                    bar_height,
                 ),
+                &PathStyle::new().fill(background),
             )?;
         }
 
         // Draw bit hint markers
-        for i in offset_start + 1..offset_end {
-            let i = f64::from(i);
-
-            write!(
-                writer,
-                r##"<use x="{x}" y="{bar_y}" xlink:href="#bm"/>"##,
-                x = to_display_num(
-                    f64::from(bar_width) - i * f64::from(bar_width) / f64::from(bit_width)
-                ),
-            )?;
-        }
+        let hint_indent = options.reg.hint_indent;
+        let jump = bar_height - 2 * hint_indent;
+        let hint_markers = (offset_start + 1..offset_end).fold(Path::new(), |path, i| {
+            path.move_to(bit_x(f64::from(i)), bar_y)
+                .vertical_by(hint_indent)
+                .move_by(0, jump)
+                .vertical_by(hint_indent)
+        });
+        backend.draw_path(
+            &hint_markers,
+            &PathStyle::new().stroke(options.reg.line_color, 1),
+        )?;
 
         let offset_center = f64::from(offset_start + offset_end) / 2.;
 
         // Draw the field name
         if let Some(name) = &self.name {
-            match name {
-                FieldString::Text(name) => {
-                    let name = escape_str(name);
-                    write!(
-                        writer,
-                        r##"<text x="{x}" y="{bar_middle}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0"><tspan>{name}</tspan></text>"##,
-                        x = to_display_num(
-                            f64::from(bar_width)
-                                - offset_center * f64::from(bar_width) / f64::from(bit_width)
-                        ),
-                        fontsize = options.reg.name_fontsize,
-                    )?;
-                }
-                FieldString::Binary(mut binary) => {
-                    write!(
-                        writer,
-                        r##"<text y="{bar_middle}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0">"##,
-                        fontsize = options.reg.name_fontsize,
-                    )?;
-                    for i in 0..self.length {
-                        write!(
-                            writer,
-                            r#"<tspan x="{x}">{bit}</tspan>"#,
-                            x = to_display_num(
-                                f64::from(bar_width)
-                                    - (f64::from(offset_end - i - 1) + 0.5) * f64::from(bar_width)
-                                        / f64::from(bit_width)
-                            ),
-                            bit = binary & 1,
-                        )?;
-
-                        binary &= !1;
-                        binary >>= 1;
-                    }
-                    write!(writer, "</text>")?;
-                }
-            }
+            let layout = ctx
+                .text_layout(options.reg.name_fontsize, TextAnchor::Middle)
+                .baseline(TextBaseline::Middle);
+
+            self.write_field_string(backend, ctx, name, offset_end, bar_middle, &layout)?;
         }
 
         // Draw the start and end markers
-        if self.length == 1 {
-            write!(
-                writer,
-                r##"<text x="{x}" y="{y}" text-anchor="middle" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0"><tspan>{bit_idx}</tspan></text>"##,
-                x = to_display_num(
-                    f64::from(bar_width)
-                        - (offset_center * f64::from(bar_width)) / f64::from(bit_width)
-                ),
-                y = options.reg.bit_marker_fontsize,
-                fontsize = options.reg.bit_marker_fontsize,
-                bit_idx = start_bit + offset_start,
-            )?;
+        // The bit offset can be anything, so the numbers saturate instead of overflowing
+        let start_bit = lane.start_bit.saturating_add(ctx.bit_offset);
+        let first_bit = start_bit.saturating_add(offset_start);
+        let last_bit = start_bit.saturating_add(offset_end - 1);
+        let bit_marker_x = f64::from(options.reg.offset.bit_marker_x);
+        let (start_anchor, end_anchor, bit_marker_x) = if ctx.hflip {
+            (TextAnchor::Start, TextAnchor::End, -bit_marker_x)
         } else {
-            write!(
-                writer,
-                r##"<text x="{x}" y="{y}" text-anchor="end" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0"><tspan>{text}</tspan></text>"##,
-                x = to_display_num(
-                    f64::from(bar_width)
-                        - f64::from(offset_start) * f64::from(bar_width) / f64::from(bit_width)
-                        - f64::from(options.reg.offset.bit_marker_x)
-                ),
-                y = options.reg.bit_marker_fontsize,
-                fontsize = options.reg.bit_marker_fontsize,
-                text = start_bit + offset_start,
-            )?;
-            write!(
-                writer,
-                r##"<text x="{x}" y="{y}" text-anchor="start" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0"><tspan>{text}</tspan></text>"##,
-                x = to_display_num(
-                    f64::from(bar_width)
-                        - f64::from(offset_end) * f64::from(bar_width) / f64::from(bit_width)
-                        + f64::from(options.reg.offset.bit_marker_x)
-                ),
-                y = options.reg.bit_marker_fontsize,
-                fontsize = options.reg.bit_marker_fontsize,
-                text = start_bit + offset_end - 1,
-            )?;
+            (TextAnchor::End, TextAnchor::Start, bit_marker_x)
+        };
+
+        if has_bit_markers {
+            if self.length == 1 {
+                ctx.write_bit_number(backend, bit_x(offset_center), first_bit, TextAnchor::Middle)?;
+            } else {
+                ctx.write_bit_number(
+                    backend,
+                    bit_x(f64::from(offset_start)) - bit_marker_x,
+                    first_bit,
+                    start_anchor,
+                )?;
+                ctx.write_bit_number(
+                    backend,
+                    bit_x(f64::from(offset_end)) + bit_marker_x,
+                    last_bit,
+                    end_anchor,
+                )?;
+            }
         }
 
+        let layout = ctx
+            .text_layout(options.reg.attribute_fontsize, TextAnchor::Middle)
+            .baseline(TextBaseline::Hanging);
+
         for (i, attribute) in self.attributes.iter().enumerate() {
+            if matches!(attribute, FieldString::Text(attribute) if attribute.is_empty()) {
+                continue;
+            }
+
             let i = i as u32;
 
-            match attribute {
-                FieldString::Text(attribute) => {
-                    if attribute.is_empty() {
-                        continue;
-                    }
-
-                    let attribute = escape_str(attribute);
-
-                    write!(
-                        writer,
-                        r##"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="hanging" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0"><tspan>{attribute}</tspan></text>"##,
-                        x = to_display_num(
-                            f64::from(bar_width)
-                                - (offset_center * f64::from(bar_width)) / f64::from(bit_width)
-                        ),
-                        y = bar_y
-                            + bar_height
-                            + options.reg.offset.attribute_y
-                            + (options.reg.attribute_fontsize
-                                + options.reg.spacing.attribute_spacing)
-                                * i,
-                        fontsize = options.reg.attribute_fontsize,
-                    )?;
-                }
-                FieldString::Binary(mut binary) => {
-                    write!(
-                        writer,
-                        r##"<text y="{y}" text-anchor="middle" dominant-baseline="hanging" font-family="{font_family}" font-size="{fontsize}" fill="#000" letter-spacing="0">"##,
-                        y = bar_y
-                            + bar_height
-                            + options.reg.offset.attribute_y
-                            + (options.reg.attribute_fontsize
-                                + options.reg.spacing.attribute_spacing)
-                                * i,
-                        fontsize = options.reg.attribute_fontsize,
+            let y = bar_y
+                + bar_height
+                + options.reg.offset.attribute_y
+                + (options.reg.attribute_fontsize + options.reg.spacing.attribute_spacing) * i;
+
+            self.write_field_string(backend, ctx, attribute, offset_end, f64::from(y), &layout)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the fill color of the field, which depends on its type.
+    fn background(&self, options: &Options) -> Option<Color> {
+        match self.variant {
+            0 => options.reg.field_background,
+            variant => {
+                let type_fills = &options.reg.type_fills;
+                Some(type_fills[(variant as usize - 1).min(type_fills.len() - 1)])
+            }
+        }
+    }
+
+    /// Draw a field name or attribute. Binary values are drawn with every bit centered above its
+    /// own bit.
+    fn write_field_string(
+        &self,
+        backend: &mut impl Backend,
+        ctx: &RenderContext,
+        field_string: &FieldString,
+        offset_end: u32,
+        y: f64,
+        layout: &TextLayout,
+    ) -> io::Result<()> {
+        let bit_x = |bit: f64| to_display_num(ctx.bit_x(bit));
+
+        match field_string {
+            FieldString::Text(text) => {
+                let offset_center = f64::from(offset_end + offset_end - self.length) / 2.;
+                backend.draw_text(
+                    (bit_x(offset_center), y),
+                    &RichText::from(text.as_str()),
+                    layout,
+                )
+            }
+            FieldString::Binary(mut binary) => {
+                for i in 0..self.length {
+                    backend.draw_text(
+                        (bit_x(f64::from(offset_end - i - 1) + 0.5), y),
+                        &RichText::from((binary & 1).to_string()),
+                        layout,
                     )?;
-                    for j in 0..self.length {
-                        write!(
-                            writer,
-                            r#"<tspan x="{x}">{bit}</tspan>"#,
-                            x = to_display_num(
-                                f64::from(bar_width)
-                                    - (f64::from(offset_end - j - 1) + 0.5) * f64::from(bar_width)
-                                        / f64::from(bit_width)
-                            ),
-                            bit = binary & 1,
-                        )?;
-
-                        binary &= !1;
-                        binary >>= 1;
-                    }
-                    write!(writer, "</text>")?;
+
+                    binary &= !1;
+                    binary >>= 1;
                 }
+
+                Ok(())
             }
         }
+    }
+}
 
-        Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reg::options::RegisterOptions;
+
+    /// A [`Backend`] that records the bit numbers with their anchor.
+    #[derive(Default)]
+    struct BitMarkers(Vec<(String, TextAnchor)>);
+
+    impl Backend for BitMarkers {
+        fn begin(&mut self, _: f64, _: f64, _: Option<Color>) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn begin_group(&mut self, _: (f64, f64)) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn end_group(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn draw_path(&mut self, _: &Path, _: &PathStyle) -> io::Result<()> {
+            Ok(())
+        }
+
+        fn draw_text(
+            &mut self,
+            _: (f64, f64),
+            text: &RichText,
+            layout: &TextLayout,
+        ) -> io::Result<()> {
+            self.0.push((text.to_plain_string(), layout.anchor));
+            Ok(())
+        }
+
+        fn finish(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn bit_markers(figure: &RegisterFigure) -> Vec<(String, TextAnchor)> {
+        let mut backend = BitMarkers::default();
+        figure.render(&mut backend).unwrap();
+        backend.0
+    }
+
+    fn figure(hflip: bool) -> RegisterFigure {
+        RegisterFigure::with(vec![Lane::new()
+            .add(LaneBitRange::new(1))
+            .add(LaneBitRange::new(3))])
+        .hflip(hflip)
+    }
+
+    #[test]
+    fn single_and_multi_bit_markers() {
+        let marker = |bit: &str, anchor| (bit.to_string(), anchor);
+
+        // A single bit gets one centered number and a longer field gets its first and last bit
+        assert_eq!(
+            bit_markers(&figure(false)),
+            [
+                marker("0", TextAnchor::Middle),
+                marker("1", TextAnchor::End),
+                marker("3", TextAnchor::Start),
+            ]
+        );
+        assert_eq!(
+            bit_markers(&figure(true)),
+            [
+                marker("0", TextAnchor::Middle),
+                marker("1", TextAnchor::Start),
+                marker("3", TextAnchor::End),
+            ]
+        );
+
+        // Compact figures only number the bits above the first lane
+        assert_eq!(
+            bit_markers(&figure(false).compact(true))
+                .into_iter()
+                .map(|(bit, _)| bit)
+                .collect::<Vec<_>>(),
+            ["0", "1", "2", "3"]
+        );
+
+        let mut svg = Vec::new();
+        figure(false).write_svg(&mut svg).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        assert_eq!(svg.matches(r#"text-anchor="middle""#).count(), 1);
+        assert_eq!(svg.matches(r#"text-anchor="end""#).count(), 1);
+        // Text that starts at its point has no `text-anchor`
+        assert_eq!(svg.matches("<text").count(), 3);
+    }
+
+    #[test]
+    fn large_bit_offset() {
+        let bits = |figure: &RegisterFigure| {
+            bit_markers(figure)
+                .into_iter()
+                .map(|(bit, _)| bit)
+                .collect::<Vec<_>>()
+        };
+
+        let figure = figure(false).bit_offset(u32::MAX - 1);
+        assert_eq!(bits(&figure), ["4294967294", "4294967295", "4294967295"]);
+        assert_eq!(
+            bits(&figure.compact(true)),
+            ["4294967294", "4294967295", "4294967295", "4294967295"]
+        );
+    }
+
+    #[test]
+    fn skin_colors() {
+        let color = |hex: &str| hex.parse::<Color>().unwrap();
+
+        let mut type_fills = RegisterOptions::default().type_fills;
+        type_fills[1] = color("#778899");
+
+        let options = Options {
+            background: Some(color("#123456")),
+            reg: RegisterOptions {
+                text_color: color("#ABCDEF"),
+                line_color: color("#112233"),
+                field_background: Some(color("#445566")),
+                type_fills,
+                ..RegisterOptions::default()
+            },
+            ..Options::default()
+        };
+
+        let figure = RegisterFigure::with(vec![Lane::new()
+            .add(LaneBitRange::with(
+                Some(FieldString::Text("a".to_string())),
+                Vec::new(),
+                2,
+                0,
+            ))
+            .add(LaneBitRange::with(None, Vec::new(), 2, 2))]);
+
+        let mut svg = Vec::new();
+        figure.write_svg_with_options(&mut svg, &options).unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+
+        assert!(svg.contains(r##"<rect width="100%" height="100%" fill="#123456"/>"##));
+        assert!(svg.contains(r##"fill="#ABCDEF" letter-spacing="0"><tspan>a</tspan>"##));
+        assert!(svg.contains(r##"stroke="#123" stroke-width="2""##));
+        assert!(svg.contains(r##"fill="#456" stroke="none"/>"##));
+        assert!(svg.contains(r##"fill="#789" stroke="none"/>"##));
+        assert!(!svg.contains("#000"));
     }
 }
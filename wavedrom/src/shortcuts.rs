@@ -17,7 +17,7 @@ mod json5 {
     #[derive(Debug)]
     pub enum RenderJson5Error {
         /// An error parsing the JSON
-        Json(json5::Error),
+        Json(crate::Error),
         /// An error with the IO
         Io(io::Error),
     }
@@ -29,6 +29,8 @@ mod json5 {
     }
 
     /// Render the contents of a json5 file to a `writer` with a specific set of options.
+    ///
+    /// If the file asks for one of the built-in skins, it is merged into the `options`.
     pub fn render_json5_with_options(
         json: &str,
         writer: &mut impl io::Write,
@@ -36,22 +38,28 @@ mod json5 {
     ) -> Result<(), RenderJson5Error> {
         let figure = Figure::from_json5(json)?;
 
+        #[cfg(feature = "skins")]
+        let options = &crate::skin::SkinRegistry::builtin().figure_options(&figure, options);
+
         match figure {
             Figure::Signal(figure) => {
                 let assembled = figure.assemble_with_options(options);
                 assembled.write_svg_with_options(writer, options)?;
             }
             Figure::Register(register) => {
-                register.write_svg(writer)?;
+                register.write_svg_with_options(writer, options)?;
+            }
+            Figure::Assign(assign) => {
+                assign.write_svg_with_options(writer, options)?;
             }
         }
 
         Ok(())
     }
 
-    impl From<json5::Error> for RenderJson5Error {
+    impl From<crate::Error> for RenderJson5Error {
         #[inline]
-        fn from(error: json5::Error) -> Self {
+        fn from(error: crate::Error) -> Self {
             Self::Json(error)
         }
     }
@@ -89,7 +97,7 @@ mod serde_json {
     #[derive(Debug)]
     pub enum RenderJsonError {
         /// An error parsing the JSON
-        Json(serde_json::error::Error),
+        Json(crate::Error),
         /// An error with the IO
         Io(io::Error),
     }
@@ -101,6 +109,8 @@ mod serde_json {
     }
 
     /// Render the contents of a json file to a `writer` with a specific set of options.
+    ///
+    /// If the file asks for one of the built-in skins, it is merged into the `options`.
     pub fn render_json_with_options(
         json: &str,
         writer: &mut impl io::Write,
@@ -108,22 +118,28 @@ mod serde_json {
     ) -> Result<(), RenderJsonError> {
         let figure = Figure::from_json(json)?;
 
+        #[cfg(feature = "skins")]
+        let options = &crate::skin::SkinRegistry::builtin().figure_options(&figure, options);
+
         match figure {
             Figure::Signal(figure) => {
                 let assembled = figure.assemble_with_options(options);
                 assembled.write_svg_with_options(writer, options)?;
             }
             Figure::Register(register) => {
-                register.write_svg(writer)?;
+                register.write_svg_with_options(writer, options)?;
+            }
+            Figure::Assign(assign) => {
+                assign.write_svg_with_options(writer, options)?;
             }
         }
 
         Ok(())
     }
 
-    impl From<serde_json::error::Error> for RenderJsonError {
+    impl From<crate::Error> for RenderJsonError {
         #[inline]
-        fn from(error: serde_json::error::Error) -> Self {
+        fn from(error: crate::Error) -> Self {
             Self::Json(error)
         }
     }
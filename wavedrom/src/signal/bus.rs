@@ -0,0 +1,266 @@
+//! Multi-bit buses with numeric values.
+//!
+//! A bus is created with [`Signal::bus`][super::Signal::bus] from one value per cycle. Consecutive
+//! equal values are merged into one box and the values are only turned into text when the figure
+//! is assembled. This means that the [`BusFormat`] can be changed without rebuilding the signal.
+//!
+//! ```
+//! use wavedrom::signal::bus::{BusFormat, Radix};
+//! use wavedrom::signal::Signal;
+//!
+//! let signal = Signal::bus(8, [0x12, 0x12, 0xAB, 0xFF])
+//!     .name("data")
+//!     .bus_format(BusFormat::new(Radix::Hexadecimal).prefix(true));
+//! ```
+
+use crate::text::RichText;
+
+use super::CycleState;
+
+/// The numeric base that is used to display the values of a [`Bus`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Radix {
+    /// Base 16 (e.g. `0x1F`)
+    #[default]
+    Hexadecimal,
+    /// Base 2 (e.g. `0b11111`)
+    Binary,
+    /// Base 10 where the value is seen as unsigned
+    Decimal,
+    /// Base 10 where the value is seen as a two's complement number of the bus width
+    SignedDecimal,
+}
+
+/// The way the values of a [`Bus`] are turned into text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BusFormat {
+    radix: Radix,
+    prefix: bool,
+    pad: bool,
+}
+
+/// The numeric values of a multi-bit [`Signal`][super::Signal].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bus {
+    width: u32,
+    values: Vec<u64>,
+    format: BusFormat,
+}
+
+impl BusFormat {
+    /// Create a new [`BusFormat`] for a [`Radix`] without prefix or padding.
+    #[inline]
+    pub fn new(radix: Radix) -> Self {
+        Self {
+            radix,
+            prefix: false,
+            pad: false,
+        }
+    }
+
+    /// Set whether the value is prefixed with `0x` or `0b` for the [`Radix::Hexadecimal`] and
+    /// [`Radix::Binary`] radices. Decimal values never get a prefix.
+    #[inline]
+    pub fn prefix(mut self, prefix: bool) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Set whether the value is padded with zeros to the number of digits of the largest value
+    /// that fits in the bus width.
+    #[inline]
+    pub fn pad(mut self, pad: bool) -> Self {
+        self.pad = pad;
+        self
+    }
+
+    /// Get the [`Radix`] of the [`BusFormat`].
+    #[inline]
+    pub fn get_radix(&self) -> Radix {
+        self.radix
+    }
+
+    /// Returns whether the values get a radix prefix.
+    #[inline]
+    pub fn has_prefix(&self) -> bool {
+        self.prefix
+    }
+
+    /// Returns whether the values are padded with zeros.
+    #[inline]
+    pub fn is_padded(&self) -> bool {
+        self.pad
+    }
+
+    /// Format a `value` of a bus that is `width` bits wide.
+    ///
+    /// Bits of `value` above `width` are ignored and `width` is clamped to `1..=64`.
+    ///
+    /// ```
+    /// use wavedrom::signal::bus::{BusFormat, Radix};
+    ///
+    /// let format = BusFormat::new(Radix::Hexadecimal).prefix(true).pad(true);
+    /// assert_eq!(format.format(0xA, 12), "0x00A");
+    ///
+    /// let format = BusFormat::new(Radix::SignedDecimal);
+    /// assert_eq!(format.format(0xFE, 8), "-2");
+    /// ```
+    pub fn format(&self, value: u64, width: u32) -> String {
+        let width = clamp_width(width);
+        let value = value & mask(width);
+
+        match self.radix {
+            Radix::Hexadecimal => {
+                let digits = if self.pad {
+                    (width as usize + 3) / 4
+                } else {
+                    0
+                };
+                let prefix = if self.prefix { "0x" } else { "" };
+                format!("{prefix}{value:0digits$X}")
+            }
+            Radix::Binary => {
+                let digits = if self.pad { width as usize } else { 0 };
+                let prefix = if self.prefix { "0b" } else { "" };
+                format!("{prefix}{value:0digits$b}")
+            }
+            Radix::Decimal => {
+                let digits = if self.pad {
+                    mask(width).to_string().len()
+                } else {
+                    0
+                };
+                format!("{value:0digits$}")
+            }
+            Radix::SignedDecimal => {
+                let sign_bit = 1 << (width - 1);
+                let (sign, magnitude) = if value & sign_bit == 0 {
+                    ("", value)
+                } else {
+                    // The two's complement of the value within the bus width
+                    ("-", (!value).wrapping_add(1) & mask(width))
+                };
+                let digits = if self.pad {
+                    sign_bit.to_string().len()
+                } else {
+                    0
+                };
+                format!("{sign}{magnitude:0digits$}")
+            }
+        }
+    }
+}
+
+impl Bus {
+    /// Merge consecutive equal values into boxes and return the [`CycleState`]s of the boxes.
+    pub(crate) fn from_cycle_values(
+        width: u32,
+        values: impl IntoIterator<Item = Option<u64>>,
+    ) -> (Self, Vec<CycleState>) {
+        let width = clamp_width(width);
+
+        let mut cycles = Vec::new();
+        let mut box_values = Vec::new();
+        let mut prev = None;
+
+        for value in values {
+            let value = value.map(|value| value & mask(width));
+
+            if prev == Some(value) {
+                cycles.push(CycleState::Continue);
+                continue;
+            }
+
+            match value {
+                Some(value) => {
+                    cycles.push(CycleState::Data);
+                    box_values.push(value);
+                }
+                None => cycles.push(CycleState::X),
+            }
+
+            prev = Some(value);
+        }
+
+        let bus = Self {
+            width,
+            values: box_values,
+            format: BusFormat::default(),
+        };
+
+        (bus, cycles)
+    }
+
+    pub(crate) fn set_format(&mut self, format: BusFormat) {
+        self.format = format;
+    }
+
+    /// Returns the width of the [`Bus`] in bits.
+    #[inline]
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// Returns the values of the boxes of the [`Bus`] in order.
+    ///
+    /// Consecutive equal values have been merged into one box, so there is one value for every
+    /// [`CycleState::Data`][super::CycleState::Data] in the signal.
+    #[inline]
+    pub fn values(&self) -> &[u64] {
+        &self.values
+    }
+
+    /// Returns the [`BusFormat`] that is used to display the values.
+    #[inline]
+    pub fn format(&self) -> BusFormat {
+        self.format
+    }
+
+    /// Returns the values of the [`Bus`] formatted with its [`BusFormat`].
+    pub fn data_fields(&self) -> Vec<RichText> {
+        self.values
+            .iter()
+            .map(|value| RichText::from(self.format.format(*value, self.width)))
+            .collect()
+    }
+}
+
+#[inline]
+fn clamp_width(width: u32) -> u32 {
+    width.clamp(1, 64)
+}
+
+#[inline]
+fn mask(width: u32) -> u64 {
+    u64::MAX >> (64 - width)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn radices() {
+        let hex = BusFormat::new(Radix::Hexadecimal);
+        assert_eq!(hex.format(0x1F, 8), "1F");
+        assert_eq!(hex.prefix(true).pad(true).format(0x1F, 10), "0x01F");
+        assert_eq!(hex.format(0x1FF, 8), "FF");
+
+        let bin = BusFormat::new(Radix::Binary);
+        assert_eq!(bin.format(5, 4), "101");
+        assert_eq!(bin.prefix(true).pad(true).format(5, 4), "0b0101");
+
+        let dec = BusFormat::new(Radix::Decimal);
+        assert_eq!(dec.format(255, 8), "255");
+        assert_eq!(dec.prefix(true).pad(true).format(7, 8), "007");
+        assert_eq!(dec.format(u64::MAX, 64), u64::MAX.to_string());
+
+        let signed = BusFormat::new(Radix::SignedDecimal);
+        assert_eq!(signed.format(0x7F, 8), "127");
+        assert_eq!(signed.format(0x80, 8), "-128");
+        assert_eq!(signed.pad(true).format(0xFF, 8), "-001");
+        assert_eq!(signed.format(1, 1), "-1");
+        assert_eq!(signed.format(u64::MAX, 64), "-1");
+        assert_eq!(signed.format(1 << 63, 64), i64::MIN.to_string());
+    }
+}
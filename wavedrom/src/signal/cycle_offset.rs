@@ -1,110 +1,81 @@
-use std::cmp::Ordering;
 use std::ops::{Add, AddAssign};
 
-/// The cycle offset within a single-clock cycle.
+/// The number of units that a single cycle is divided into.
 ///
-/// At the moment a cycle in divided into 4 quarters and you 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub enum InCycleOffset {
-    /// 0/4
-    #[default]
-    Begin,
-    /// 1/4
-    Quarter,
-    /// 2/4
-    Half,
-    /// 3/4
-    ThreeQuarter,
-}
+/// This is divisible by all numbers up to 10 and by 16 and 32. Therefore, common fractions of a
+/// cycle such as tenths, thirds and sixteenths are represented exactly.
+const UNITS_PER_CYCLE: u64 = 10080;
+
+/// The largest number of cycles that [`CycleOffset::from_capped`] gives.
+const MAX_CAPPED_CYCLES: u16 = u16::MAX;
 
-/// The cycle offset within multiple clock cycles. This is precise up until a quarter of a cycle.
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// The cycle offset within multiple clock cycles.
+///
+/// This is a fixed-point number of cycles that is precise up until 1/10080th of a cycle. This
+/// allows for fractional periods and phases, such as setup and hold times of a tenth of a cycle.
+///
+/// # Examples
+///
+/// ```
+/// use wavedrom::signal::CycleOffset;
+///
+/// let skew = CycleOffset::from_fraction(1, 10);
+/// assert_eq!(CycleOffset::from(0.1), skew);
+/// assert_eq!(skew.width_offset(48), 5);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct CycleOffset {
-    index: u32,
-    in_offset: InCycleOffset,
+    units: u64,
 }
 
-impl PartialOrd for CycleOffset {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(match self.index.cmp(&other.index) {
-            Ordering::Equal => self.in_offset.cmp(&other.in_offset),
-            ord => ord,
-        })
+impl From<f32> for CycleOffset {
+    #[inline]
+    fn from(value: f32) -> Self {
+        Self::from(f64::from(value))
     }
 }
 
-impl Ord for CycleOffset {
-    fn cmp(&self, other: &Self) -> Ordering {
-        match self.index.cmp(&other.index) {
-            Ordering::Equal => self.in_offset.cmp(&other.in_offset),
-            ord => ord,
+impl From<f64> for CycleOffset {
+    /// Negative numbers and `NaN` become `0` and the value is rounded to the nearest unit.
+    fn from(value: f64) -> Self {
+        let units = (value * UNITS_PER_CYCLE as f64).round();
+
+        // The `as` cast saturates and turns `NaN` into `0`
+        Self {
+            units: units as u64,
         }
     }
 }
 
-impl From<f32> for CycleOffset {
-    fn from(value: f32) -> Self {
-        if value.is_subnormal() {
-            return Self::default();
-        }
-
-        let in_offset = match (value.fract() * 4.).round() as u32 {
-            4 | 0 => InCycleOffset::Begin,
-            1 => InCycleOffset::Quarter,
-            2 => InCycleOffset::Half,
-            _ => InCycleOffset::ThreeQuarter,
-        };
-        let index = if value.fract() > 0.75 && in_offset == InCycleOffset::Begin {
-            value.ceil()
-        } else {
-            value.floor()
-        } as u32;
-
-        Self { index, in_offset }
+impl From<u32> for CycleOffset {
+    #[inline]
+    fn from(value: u32) -> Self {
+        Self::new_rounded(value)
     }
 }
 
-impl From<f64> for CycleOffset {
-    fn from(value: f64) -> Self {
-        if value.is_subnormal() {
-            return Self::default();
-        }
-
-        let in_offset = match ((value * 4.).round() % 4.) as u32 {
-            0 => InCycleOffset::Begin,
-            1 => InCycleOffset::Quarter,
-            2 => InCycleOffset::Half,
-            _ => InCycleOffset::ThreeQuarter,
-        };
-        let index = if value.fract() > 0.75 && in_offset == InCycleOffset::Begin {
-            value.ceil()
-        } else {
-            value.floor()
-        } as u32;
+impl From<CycleOffset> for f64 {
+    #[inline]
+    fn from(value: CycleOffset) -> Self {
+        value.units as f64 / UNITS_PER_CYCLE as f64
+    }
+}
 
-        Self { index, in_offset }
+impl From<CycleOffset> for f32 {
+    #[inline]
+    fn from(value: CycleOffset) -> Self {
+        f64::from(value) as f32
     }
 }
 
 impl Add for CycleOffset {
     type Output = Self;
 
+    #[inline]
     fn add(self, rhs: Self) -> Self::Output {
-        use InCycleOffset::*;
-
-        let (in_offset, carry) = match (self.in_offset, rhs.in_offset) {
-            (x, Begin) | (Begin, x) => (x, 0),
-            (Quarter, Quarter) => (Half, 0),
-            (Quarter, Half) | (Half, Quarter) => (ThreeQuarter, 0),
-            (Half, Half)
-            | (Quarter, ThreeQuarter)
-            | (ThreeQuarter, Quarter) => (Begin, 1),
-            (Half, ThreeQuarter) | (ThreeQuarter, Half) => (Quarter, 1),
-            (ThreeQuarter, ThreeQuarter) => (Half, 1),
-        };
-        let index = self.index + rhs.index + carry;
-
-        Self { index, in_offset }
+        Self {
+            units: self.units.saturating_add(rhs.units),
+        }
     }
 }
 
@@ -116,77 +87,130 @@ impl AddAssign for CycleOffset {
 }
 
 impl CycleOffset {
-    /// Create a new [`CycleOffset`] with a specific cycle cycle `index` and an in cycle cycle
-    /// offset `in_offset`
-    #[inline]
-    pub fn new(index: u32, in_offset: InCycleOffset) -> Self {
-        Self { index, in_offset }
-    }
-
     /// Create a new [`CycleOffset`] that is rounded to an specific cycle cycle `index`
     #[inline]
     pub fn new_rounded(index: u32) -> Self {
         Self {
-            index,
-            in_offset: InCycleOffset::Begin,
+            units: u64::from(index) * UNITS_PER_CYCLE,
+        }
+    }
+
+    /// Create a new [`CycleOffset`] of `numerator / denominator` cycles.
+    ///
+    /// The result is rounded to the nearest unit. A `denominator` of `0` gives a zero offset.
+    pub fn from_fraction(numerator: u32, denominator: u32) -> Self {
+        if denominator == 0 {
+            return Self::default();
         }
+
+        let denominator = u64::from(denominator);
+        let units = (u64::from(numerator) * UNITS_PER_CYCLE + denominator / 2) / denominator;
+
+        Self { units }
+    }
+
+    /// Create a new [`CycleOffset`] from a user given number of cycles.
+    ///
+    /// This works like the conversion from a [`f64`], but caps the value to keep the figure a
+    /// reasonable size. Infinity and `NaN` become `0`.
+    pub(crate) fn from_capped(value: f64) -> Self {
+        if !value.is_finite() {
+            return Self::default();
+        }
+
+        Self::from(value.min(f64::from(MAX_CAPPED_CYCLES)))
+    }
+
+    /// Returns whether the [`CycleOffset`] is zero.
+    #[inline]
+    pub fn is_zero(self) -> bool {
+        self.units == 0
     }
 
     /// Get the a ceiled value of the number of cycles that the [`CycleOffset`] incorperates.
     pub fn ceil_num_cycles(self) -> u32 {
-        self.index
-            + match self.in_offset {
-                InCycleOffset::Begin => 0,
-                _ => 1,
-            }
+        let cycles = self.units / UNITS_PER_CYCLE + u64::from(!self.is_whole());
+        u32::try_from(cycles).unwrap_or(u32::MAX)
     }
 
-    /// Get the `index` of the [`CycleOffset`]
+    /// Get the index of the cycle that the [`CycleOffset`] falls in.
     #[inline]
     pub fn cycle_index(self) -> u32 {
-        self.index
+        u32::try_from(self.units / UNITS_PER_CYCLE).unwrap_or(u32::MAX)
     }
 
-    /// Get the in cycle offset of the [`CycleOffset`]
+    /// Returns whether the [`CycleOffset`] falls exactly on the start of a cycle.
     #[inline]
-    pub fn in_cycle_offset(self) -> InCycleOffset {
-        self.in_offset
+    pub fn is_whole(self) -> bool {
+        self.units % UNITS_PER_CYCLE == 0
     }
 
     /// Get the width knowning that a cycle cycle is `width` units wide.
+    ///
+    /// The width is rounded to the nearest whole unit.
     #[inline]
     pub fn width_offset(self, width: u32) -> u32 {
-        width * self.index + self.in_offset.width_offset(width)
+        let width = u128::from(self.units) * u128::from(width);
+        let width = (width + u128::from(UNITS_PER_CYCLE / 2)) / u128::from(UNITS_PER_CYCLE);
+
+        u32::try_from(width).unwrap_or(u32::MAX)
+    }
+
+    /// Get the number of ticks knowing that a cycle is `ticks_per_cycle` ticks long.
+    ///
+    /// The number of ticks is rounded to the nearest whole tick.
+    #[inline]
+    pub(crate) fn ticks(self, ticks_per_cycle: u64) -> u64 {
+        let ticks = u128::from(self.units) * u128::from(ticks_per_cycle);
+        let ticks = (ticks + u128::from(UNITS_PER_CYCLE / 2)) / u128::from(UNITS_PER_CYCLE);
+
+        u64::try_from(ticks).unwrap_or(u64::MAX)
     }
 
     /// Half the [`CycleOffset`]
+    #[inline]
     pub fn half(&self) -> CycleOffset {
-        use InCycleOffset::*;
-
-        if self.index % 2 == 0 {
-            Self::new(self.index / 2, match self.in_offset {
-                Begin | Quarter => Begin,
-                Half | ThreeQuarter => Quarter,
-            })
-        } else {
-            Self::new(self.index / 2, match self.in_offset {
-                Begin | Quarter => Half,
-                Half | ThreeQuarter => ThreeQuarter,
-            })
+        Self {
+            units: self.units / 2,
         }
     }
 }
 
-impl InCycleOffset {
-    /// Get the width knowning that a cycle cycle is `width` units wide.
-    pub fn width_offset(self, width: u32) -> u32 {
-        let w = f64::from(width);
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fractions() {
+        assert_eq!(CycleOffset::from(0.25), CycleOffset::from_fraction(1, 4));
+        assert_eq!(CycleOffset::from(1.5), CycleOffset::from_fraction(3, 2));
+        assert_eq!(CycleOffset::from(2.0), CycleOffset::new_rounded(2));
+        assert_eq!(CycleOffset::from(-1.0), CycleOffset::default());
+        assert_eq!(CycleOffset::from(f64::NAN), CycleOffset::default());
+
+        let third = CycleOffset::from_fraction(1, 3);
+        assert_eq!(third + third + third, CycleOffset::new_rounded(1));
+        assert_eq!(f64::from(CycleOffset::from(0.1)), 0.1);
+
+        assert_eq!(CycleOffset::from(1.1).ceil_num_cycles(), 2);
+        assert_eq!(CycleOffset::from(1.1).cycle_index(), 1);
+        assert_eq!(CycleOffset::from(0.5).width_offset(48), 24);
+        assert_eq!(CycleOffset::from(0.1).width_offset(48), 5);
+    }
 
-        match self {
-            Self::Begin => 0,
-            Self::Quarter => (w * 0.25).round() as u32,
-            Self::Half => (w * 0.5).round() as u32,
-            Self::ThreeQuarter => (w * 0.75).round() as u32,
-        }
+    #[test]
+    fn capped() {
+        let max = CycleOffset::new_rounded(u32::from(MAX_CAPPED_CYCLES));
+
+        assert_eq!(CycleOffset::from_capped(1.5), CycleOffset::from(1.5));
+        assert_eq!(CycleOffset::from_capped(1e12), max);
+        assert_eq!(
+            CycleOffset::from_capped(f64::INFINITY),
+            CycleOffset::default()
+        );
+        assert_eq!(CycleOffset::from_capped(f64::NAN), CycleOffset::default());
+
+        // A capped phase still fits a figure
+        assert!(CycleOffset::from_capped(1e12).width_offset(48) < u32::MAX / 2);
     }
 }
@@ -15,16 +15,20 @@
 //!
 //! [book]: https://coastalwhite.github.io/wavedrom-rs
 
-use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
+use std::fmt::{self, Display};
 use std::str::FromStr;
 
-use super::{CycleOffset, Signal};
+use crate::text::RichText;
 
-/// A set of edge markers. Both the edge lines and the text_nodes.
+use super::timing::{TimingAnnotation, TimingMarker};
+use super::{CycleOffset, Signal, SignalPath};
+
+/// A set of edge markers. The edge lines, the timing annotations and the text_nodes.
 #[derive(Debug, Clone)]
 pub struct LineEdgeMarkers<'a> {
     lines: Vec<LineEdge<'a>>,
+    timing: Vec<TimingMarker<'a>>,
     text_nodes: Vec<LineEdgeText>,
 }
 
@@ -35,7 +39,7 @@ pub struct LineEdge<'a> {
     from_marker: Option<char>,
     to: InSignalPosition,
     to_marker: Option<char>,
-    text: Option<Cow<'a, str>>,
+    text: Option<&'a RichText>,
     variant: EdgeVariant,
 }
 
@@ -61,7 +65,7 @@ pub struct EdgeDefinition {
     variant: EdgeVariant,
     from: char,
     to: char,
-    label: Option<String>,
+    label: Option<RichText>,
 }
 
 /// A variant of an edge
@@ -288,15 +292,20 @@ impl LineEdgeMarkersBuilder {
     pub fn add_signal(&mut self, signal: &Signal) {
         let line_number = self.line_number;
 
-        for (i, c) in signal.get_nodes().chars().enumerate() {
+        // Nodes are placed at the start of their state in the same way as the path is drawn
+        let path = SignalPath::new(
+            signal.cycles(),
+            signal.get_data_fields(),
+            signal.get_period(),
+            signal.get_phase(),
+        );
+
+        for (c, x) in signal.get_nodes().chars().zip(path.state_offsets()) {
             if c == '.' {
                 continue;
             }
 
-            let at = InSignalPosition {
-                x: signal.get_phase() + CycleOffset::new_rounded(i as u32),
-                y: line_number,
-            };
+            let at = InSignalPosition { x, y: line_number };
 
             self.node_positions.insert(c, at.clone());
             self.text_nodes.push(LineEdgeText { at, text: c });
@@ -305,7 +314,11 @@ impl LineEdgeMarkersBuilder {
         self.line_number += 1;
     }
 
-    pub fn build(mut self, edges: &[EdgeDefinition]) -> LineEdgeMarkers {
+    pub fn build<'a>(
+        mut self,
+        edges: &'a [EdgeDefinition],
+        timing: &'a [TimingAnnotation],
+    ) -> LineEdgeMarkers<'a> {
         let mut lines = Vec::new();
         let mut used_text_nodes = HashSet::new();
 
@@ -327,7 +340,7 @@ impl LineEdgeMarkersBuilder {
             let from = from.clone();
             let to = to.clone();
 
-            let text = edge.label.as_ref().map(|text| Cow::Borrowed(&text[..]));
+            let text = edge.label.as_ref();
             let variant = edge.variant;
 
             let from_marker = (!edge.from.is_ascii_uppercase()).then_some(edge.from);
@@ -343,11 +356,25 @@ impl LineEdgeMarkersBuilder {
             });
         }
 
+        let timing = timing
+            .iter()
+            .filter_map(|annotation| {
+                let from = self.node_positions.get(&annotation.from())?;
+                let to = self.node_positions.get(&annotation.to())?;
+
+                used_text_nodes.insert(annotation.from());
+                used_text_nodes.insert(annotation.to());
+
+                Some(TimingMarker::new(from.clone(), to.clone(), annotation))
+            })
+            .collect();
+
         self.text_nodes
             .retain(|n| !used_text_nodes.contains(&n.text()) && !n.text().is_ascii_uppercase());
 
         LineEdgeMarkers {
             lines,
+            timing,
             text_nodes: self.text_nodes,
         }
     }
@@ -359,13 +386,18 @@ impl LineEdgeMarkers<'_> {
         &self.lines
     }
 
+    /// The timing annotations for a [`LineEdgeMarkers`]
+    pub fn timing_markers(&self) -> &[TimingMarker<'_>] {
+        &self.timing
+    }
+
     /// The lone standing text nodes for a [`LineEdgeMarkers`]
     pub fn text_nodes(&self) -> &[LineEdgeText] {
         &self.text_nodes
     }
 }
 
-impl LineEdge<'_> {
+impl<'a> LineEdge<'a> {
     /// The starting position
     #[inline]
     pub fn from(&self) -> &InSignalPosition {
@@ -398,8 +430,8 @@ impl LineEdge<'_> {
 
     /// The label text of the edge line
     #[inline]
-    pub fn label(&self) -> Option<&str> {
-        self.text.as_ref().map(|s| &s[..])
+    pub fn label(&self) -> Option<&'a RichText> {
+        self.text
     }
 }
 
@@ -431,9 +463,72 @@ impl InSignalPosition {
 
 impl EdgeDefinition {
     /// Create a new [`EdgeDefinition`] from a set of parameters
-    pub fn new(variant: EdgeVariant, from: char, to: char, label: Option<String>) -> Self {
+    pub fn new(variant: EdgeVariant, from: char, to: char, label: Option<RichText>) -> Self {
         Self { variant, from, to, label }
     }
+
+    /// Returns the variant of the edge
+    #[inline]
+    pub fn variant(&self) -> EdgeVariant {
+        self.variant
+    }
+
+    /// Returns the name of the start node
+    #[inline]
+    pub fn from(&self) -> char {
+        self.from
+    }
+
+    /// Returns the name of the end node
+    #[inline]
+    pub fn to(&self) -> char {
+        self.to
+    }
+
+    /// Returns the label of the edge
+    #[inline]
+    pub fn label(&self) -> Option<&RichText> {
+        self.label.as_ref()
+    }
+}
+
+impl Display for EdgeVariant {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (identifier, arrow_type) = match self {
+            Self::Spline(SplineEdgeVariant::BothHorizontal(a)) => ("~", a),
+            Self::Spline(SplineEdgeVariant::StartHorizontal(a)) => ("-~", a),
+            Self::Spline(SplineEdgeVariant::EndHorizontal(a)) => ("~-", a),
+            Self::Sharp(SharpEdgeVariant::Straight(a)) => ("-", a),
+            Self::Sharp(SharpEdgeVariant::BothHorizontal(a)) => ("-|-", a),
+            Self::Sharp(SharpEdgeVariant::StartHorizontal(a)) => ("-|", a),
+            Self::Sharp(SharpEdgeVariant::EndHorizontal(a)) => ("|-", a),
+            Self::Sharp(SharpEdgeVariant::Cross) => ("+", &EdgeArrowType::None),
+        };
+
+        if arrow_type.has_start_arrow() {
+            f.write_str("<")?;
+        }
+        f.write_str(identifier)?;
+        if arrow_type.has_end_arrow() {
+            f.write_str(">")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Formats the edge in the WaveJson notation (e.g. `a~>b label`). Only the plain text of the label
+/// is kept.
+impl Display for EdgeDefinition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}{}", self.from, self.variant, self.to)?;
+
+        if let Some(label) = &self.label {
+            write!(f, " {}", label.to_plain_string())?;
+        }
+
+        Ok(())
+    }
 }
 
 impl FromStr for EdgeDefinition {
@@ -458,7 +553,7 @@ impl FromStr for EdgeDefinition {
 
         let (s, to) = take(s).ok_or(start_idx)?;
 
-        let text = (!s.is_empty()).then_some(s.trim_start().to_string());
+        let text = (!s.is_empty()).then(|| RichText::from(s.trim_start()));
 
         Ok(Self {
             variant,
@@ -490,6 +585,7 @@ mod tests {
 
                 assert!(out_str.is_empty());
                 assert_eq!(out, $output);
+                assert_eq!(out.to_string(), $input);
             };
             ($input:literal) => {
                 assert!(EdgeVariant::consume($input).is_none());
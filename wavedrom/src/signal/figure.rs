@@ -1,8 +1,16 @@
-use crate::Options;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use crate::text::RichText;
+use crate::{Color, Options};
 
 use super::edges::{EdgeDefinition, EdgeVariant, LineEdgeMarkersBuilder};
-use super::markers::{CycleEnumerationMarker, GroupMarker};
-use super::{AssembledFigure, AssembledLine, CycleState, DefinitionTracker, Signal, SignalPath};
+use super::lint::{Lint, LintKind, LintLocation};
+use super::markers::{Cursor, CycleEnumerationMarker, GroupMarker, Highlight};
+use super::timing::TimingAnnotation;
+use super::{
+    AssembledFigure, AssembledLine, CycleOffset, CycleState, DefinitionTracker, Signal, SignalPath,
+};
 
 impl Default for SignalFigure {
     fn default() -> Self {
@@ -12,7 +20,11 @@ impl Default for SignalFigure {
             top_cycle_marker: None,
             bottom_cycle_marker: None,
             hscale: 1,
+            skin: None,
             edges: Vec::new(),
+            timing: Vec::new(),
+            highlights: Vec::new(),
+            cursors: Vec::new(),
             sections: Vec::new(),
         }
     }
@@ -58,15 +70,21 @@ impl Default for SignalFigure {
 /// [dtd]: https://en.wikipedia.org/wiki/Digital_timing_diagram
 #[derive(Debug, Clone)]
 pub struct SignalFigure {
-    header_text: Option<String>,
-    footer_text: Option<String>,
+    header_text: Option<RichText>,
+    footer_text: Option<RichText>,
 
     top_cycle_marker: Option<CycleEnumerationMarker>,
     bottom_cycle_marker: Option<CycleEnumerationMarker>,
 
     hscale: u16,
 
+    skin: Option<String>,
+
     edges: Vec<EdgeDefinition>,
+    timing: Vec<TimingAnnotation>,
+
+    highlights: Vec<Highlight>,
+    cursors: Vec<Cursor>,
 
     sections: Vec<SignalFigureSection>,
 }
@@ -82,20 +100,35 @@ pub enum SignalFigureSection {
 
 /// A section of the figure's group
 #[derive(Debug, Clone)]
-pub struct SignalFigureSectionGroup(Option<String>, Vec<SignalFigureSection>);
+pub struct SignalFigureSectionGroup(Option<RichText>, Vec<SignalFigureSection>);
 
 impl SignalFigureSectionGroup {
     /// Create a new [`SignalFigureSectionGroup`]
-    pub fn new(label: Option<String>, items: Vec<SignalFigureSection>) -> SignalFigureSectionGroup {
+    pub fn new(
+        label: Option<RichText>,
+        items: Vec<SignalFigureSection>,
+    ) -> SignalFigureSectionGroup {
         Self(label, items)
     }
+
+    /// Returns the label of the [`SignalFigureSectionGroup`].
+    #[inline]
+    pub fn label(&self) -> Option<&RichText> {
+        self.0.as_ref()
+    }
+
+    /// Returns the sections that the [`SignalFigureSectionGroup`] contains.
+    #[inline]
+    pub fn items(&self) -> &[SignalFigureSection] {
+        &self.1
+    }
 }
 
 impl SignalFigure {
     /// Create a new [`Figure`] with a set of parameters.
     pub fn with(
-        title: Option<String>,
-        footer: Option<String>,
+        title: Option<RichText>,
+        footer: Option<RichText>,
 
         top_cycle_marker: Option<CycleEnumerationMarker>,
         bottom_cycle_marker: Option<CycleEnumerationMarker>,
@@ -113,8 +146,13 @@ impl SignalFigure {
             bottom_cycle_marker,
 
             edges,
+            timing: Vec::new(),
+
+            highlights: Vec::new(),
+            cursors: Vec::new(),
 
             hscale,
+            skin: None,
             sections,
         }
     }
@@ -127,14 +165,14 @@ impl SignalFigure {
 
     /// Give a header text to the [`Figure`].
     #[inline]
-    pub fn header_text(mut self, header_text: impl Into<String>) -> Self {
+    pub fn header_text(mut self, header_text: impl Into<RichText>) -> Self {
         self.header_text = Some(header_text.into());
         self
     }
 
     /// Give a footer text to the [`Figure`].
     #[inline]
-    pub fn footer_text(mut self, footer_text: impl Into<String>) -> Self {
+    pub fn footer_text(mut self, footer_text: impl Into<RichText>) -> Self {
         self.footer_text = Some(footer_text.into());
         self
     }
@@ -155,6 +193,20 @@ impl SignalFigure {
         self
     }
 
+    /// Give a top [`CycleEnumerationMarker`] to the figure.
+    #[inline]
+    pub fn top_cycle_enumeration(mut self, marker: CycleEnumerationMarker) -> Self {
+        self.top_cycle_marker = Some(marker);
+        self
+    }
+
+    /// Give a bottom [`CycleEnumerationMarker`] to the figure.
+    #[inline]
+    pub fn bottom_cycle_enumeration(mut self, marker: CycleEnumerationMarker) -> Self {
+        self.bottom_cycle_marker = Some(marker);
+        self
+    }
+
     /// Set the horizontal scale of the [`Figure`].
     #[inline]
     pub fn horizontal_scale(mut self, hscale: u16) -> Self {
@@ -162,13 +214,88 @@ impl SignalFigure {
         self
     }
 
+    /// Set the name of the skin that the [`Figure`] should be rendered with.
+    ///
+    /// The name is resolved with a [`SkinRegistry`][crate::skin::SkinRegistry].
+    #[inline]
+    pub fn skin(mut self, skin: impl Into<String>) -> Self {
+        self.skin = Some(skin.into());
+        self
+    }
+
+    /// Get the name of the skin that the [`Figure`] should be rendered with.
+    #[inline]
+    pub fn get_skin(&self) -> Option<&str> {
+        self.skin.as_deref()
+    }
+
+    /// Get the header text of the [`Figure`].
+    #[inline]
+    pub fn get_header_text(&self) -> Option<&RichText> {
+        self.header_text.as_ref()
+    }
+
+    /// Get the footer text of the [`Figure`].
+    #[inline]
+    pub fn get_footer_text(&self) -> Option<&RichText> {
+        self.footer_text.as_ref()
+    }
+
+    /// Get the cycle enumeration marker above the signals of the [`Figure`].
+    #[inline]
+    pub fn get_top_cycle_marker(&self) -> Option<&CycleEnumerationMarker> {
+        self.top_cycle_marker.as_ref()
+    }
+
+    /// Get the cycle enumeration marker below the signals of the [`Figure`].
+    #[inline]
+    pub fn get_bottom_cycle_marker(&self) -> Option<&CycleEnumerationMarker> {
+        self.bottom_cycle_marker.as_ref()
+    }
+
+    /// Get the horizontal scale of the [`Figure`].
+    #[inline]
+    pub fn get_horizontal_scale(&self) -> u16 {
+        self.hscale
+    }
+
+    /// Get the arrows / edges of the [`Figure`].
+    #[inline]
+    pub fn edges(&self) -> &[EdgeDefinition] {
+        &self.edges
+    }
+
+    /// Get the [`TimingAnnotation`]s of the [`Figure`].
+    #[inline]
+    pub fn timing_annotations(&self) -> &[TimingAnnotation] {
+        &self.timing
+    }
+
+    /// Get the [`Highlight`]s of the [`Figure`].
+    #[inline]
+    pub fn highlights(&self) -> &[Highlight] {
+        &self.highlights
+    }
+
+    /// Get the [`Cursor`]s of the [`Figure`].
+    #[inline]
+    pub fn cursors(&self) -> &[Cursor] {
+        &self.cursors
+    }
+
+    /// Get the [`SignalFigureSection`]s of the [`Figure`].
+    #[inline]
+    pub fn sections(&self) -> &[SignalFigureSection] {
+        &self.sections
+    }
+
     /// Add a labeled arrow / edge with a set of parameters to the [`Figure`].
     pub fn add_labeled_edge_with(
         mut self,
         variant: EdgeVariant,
         from: char,
         to: char,
-        label: impl Into<String>,
+        label: impl Into<RichText>,
     ) -> Self {
         self.edges
             .push(EdgeDefinition::new(variant, from, to, Some(label.into())));
@@ -194,6 +321,69 @@ impl SignalFigure {
         self
     }
 
+    /// Add a [`TimingAnnotation`] to the [`Figure`].
+    pub fn add_timing_annotation(mut self, annotation: TimingAnnotation) -> Self {
+        self.timing.push(annotation);
+        self
+    }
+
+    /// Add a set of [`TimingAnnotation`]s to the [`Figure`].
+    pub fn add_timing_annotations(
+        mut self,
+        annotations: impl IntoIterator<Item = TimingAnnotation>,
+    ) -> Self {
+        self.timing.extend(annotations);
+        self
+    }
+
+    /// Highlight the cycles in `range` over all the signals with a `color` and a `label`.
+    ///
+    /// An empty `label` is not shown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavedrom::Color;
+    /// use wavedrom::signal::{Signal, SignalFigure, CycleState};
+    ///
+    /// let red = Color { red: 0xFF, green: 0x00, blue: 0x00 };
+    /// let figure = SignalFigure::new()
+    ///                  .add_signal(Signal::repeated(CycleState::Top, 8))
+    ///                  .add_highlight(2.0..3.5, red, "bug happens here")
+    ///                  .add_cursor(5.0, "");
+    /// # let _ = figure;
+    /// ```
+    pub fn add_highlight(
+        mut self,
+        range: Range<impl Into<CycleOffset>>,
+        color: Color,
+        label: impl Into<RichText>,
+    ) -> Self {
+        self.highlights
+            .push(Highlight::new(range).color(color).label(label));
+        self
+    }
+
+    /// Add a set of [`Highlight`]s to the [`Figure`].
+    pub fn add_highlights(mut self, highlights: impl IntoIterator<Item = Highlight>) -> Self {
+        self.highlights.extend(highlights);
+        self
+    }
+
+    /// Add a vertical cursor line over all the signals at cycle offset `at` with a `label`.
+    ///
+    /// An empty `label` is not shown.
+    pub fn add_cursor(mut self, at: impl Into<CycleOffset>, label: impl Into<RichText>) -> Self {
+        self.cursors.push(Cursor::new(at).label(label));
+        self
+    }
+
+    /// Add a set of [`Cursor`]s to the [`Figure`].
+    pub fn add_cursors(mut self, cursors: impl IntoIterator<Item = Cursor>) -> Self {
+        self.cursors.extend(cursors);
+        self
+    }
+
     /// Add a [`Signal`] line to the [`Figure`].
     pub fn add_signal(mut self, signal: Signal) -> Self {
         self.sections.push(SignalFigureSection::Signal(signal));
@@ -246,12 +436,12 @@ impl SignalFigure {
     ///
     /// [dtd]: https://en.wikipedia.org/wiki/Digital_timing_diagram
     pub fn assemble_with_options(&self, options: &Options) -> AssembledFigure {
-        let top_cycle_marker = self.top_cycle_marker;
-        let bottom_cycle_marker = self.bottom_cycle_marker;
+        let top_cycle_marker = self.top_cycle_marker.as_ref();
+        let bottom_cycle_marker = self.bottom_cycle_marker.as_ref();
         let hscale = self.hscale;
 
-        let header_text = self.header_text.as_ref().map(|s| &s[..]);
-        let footer_text = self.footer_text.as_ref().map(|s| &s[..]);
+        let header_text = self.header_text.as_ref();
+        let footer_text = self.footer_text.as_ref();
 
         let mut options = options.signal.path;
         options.cycle_width *= hscale;
@@ -301,12 +491,14 @@ impl SignalFigure {
                         }
                     }
 
+                    let box_contents = signal.box_contents();
                     lines.push(AssembledLine {
                         text: signal.get_name(),
+                        signal,
                         path: SignalPath::new(
                             signal.cycles(),
-                            signal.get_data_fields(),
-                            signal.get_period_internal(),
+                            &box_contents,
+                            signal.get_period(),
                             signal.get_phase(),
                         )
                         .assemble_with_options(options),
@@ -328,14 +520,14 @@ impl SignalFigure {
                     group_markers.push(GroupMarker::new(
                         start_idx,
                         idx,
-                        label.as_ref().map(|s| &s[..]),
+                        label.as_ref(),
                         depth,
                     ));
                 }
             }
         }
 
-        let line_edge_markers = line_edge_markers.build(&self.edges);
+        let line_edge_markers = line_edge_markers.build(&self.edges, &self.timing);
 
         let num_cycles = lines
             .iter()
@@ -365,6 +557,9 @@ impl SignalFigure {
             group_markers,
 
             line_edge_markers,
+
+            highlights: &self.highlights,
+            cursors: &self.cursors,
         }
     }
 
@@ -383,6 +578,101 @@ impl SignalFigure {
     }
 }
 
+impl SignalFigure {
+    /// Find mistakes in the [`SignalFigure`] that are silently accepted during assembly.
+    ///
+    /// This finds edges and timing annotations that refer to nodes which do not exist, nodes that
+    /// are defined more than once and data fields that are never shown. Mistakes that are only visible in the WaveJson,
+    /// such as unknown wave characters, are found by
+    /// [`SignalJson::validate`][crate::wavejson::signal::SignalJson::validate].
+    pub fn validate(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+        let mut nodes = HashSet::new();
+
+        validate_sections(&self.sections, 0, &mut Vec::new(), &mut nodes, &mut lints);
+
+        for (i, edge) in self.edges.iter().enumerate() {
+            for node in [edge.from(), edge.to()] {
+                if !nodes.contains(&node) {
+                    lints.push(Lint::new(
+                        LintKind::MissingEdgeNode(node),
+                        LintLocation::Edge(i),
+                    ));
+                }
+            }
+        }
+
+        for (i, annotation) in self.timing.iter().enumerate() {
+            for node in [annotation.from(), annotation.to()] {
+                if !nodes.contains(&node) {
+                    lints.push(Lint::new(
+                        LintKind::MissingTimingNode(node),
+                        LintLocation::Timing(i),
+                    ));
+                }
+            }
+        }
+
+        lints
+    }
+}
+
+fn validate_sections(
+    sections: &[SignalFigureSection],
+    label_offset: usize,
+    indices: &mut Vec<usize>,
+    nodes: &mut HashSet<char>,
+    lints: &mut Vec<Lint>,
+) {
+    for (i, section) in sections.iter().enumerate() {
+        indices.push(i + label_offset);
+
+        match section {
+            SignalFigureSection::Signal(signal) => validate_signal(signal, indices, nodes, lints),
+            SignalFigureSection::Group(SignalFigureSectionGroup(label, items)) => {
+                // In WaveJson, the label is the first item of the group
+                let label_offset = usize::from(label.is_some());
+                validate_sections(items, label_offset, indices, nodes, lints);
+            }
+        }
+
+        indices.pop();
+    }
+}
+
+fn validate_signal(
+    signal: &Signal,
+    indices: &[usize],
+    nodes: &mut HashSet<char>,
+    lints: &mut Vec<Lint>,
+) {
+    let num_data_states = signal
+        .cycles()
+        .iter()
+        .filter(|state| state.is_data())
+        .count();
+    let num_data_fields = signal.get_data_fields().len();
+
+    if num_data_fields > num_data_states {
+        lints.push(Lint::new(
+            LintKind::ExtraData {
+                expected: num_data_states,
+                given: num_data_fields,
+            },
+            LintLocation::signal(indices, "data"),
+        ));
+    }
+
+    for (i, node) in signal.get_nodes().chars().enumerate() {
+        if node != '.' && !nodes.insert(node) {
+            lints.push(Lint::new(
+                LintKind::DuplicateNode(node),
+                LintLocation::signal_at(indices, "node", i),
+            ));
+        }
+    }
+}
+
 impl From<Signal> for SignalFigureSection {
     fn from(wave: Signal) -> Self {
         Self::Signal(wave)
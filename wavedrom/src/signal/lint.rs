@@ -0,0 +1,275 @@
+//! Lints for mistakes in a signal diagram that do not stop it from being rendered.
+//!
+//! Many mistakes in a [`SignalFigure`][super::SignalFigure] are silently accepted during assembly.
+//! For example, an edge that refers to a node that does not exist is left out of the diagram.
+//! These mistakes can be found with [`SignalFigure::validate`][super::SignalFigure::validate] and
+//! [`SignalJson::validate`][crate::wavejson::signal::SignalJson::validate].
+
+use std::fmt::{self, Display};
+
+/// A mistake found in a signal diagram
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lint {
+    kind: LintKind,
+    location: LintLocation,
+}
+
+/// How severe a [`Lint`] is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LintLevel {
+    /// The input was adjusted, but it is still rendered.
+    Warning,
+    /// The input was replaced or left out of the diagram.
+    Error,
+}
+
+/// The type of mistake that a [`Lint`] describes
+#[derive(Debug, Clone, PartialEq)]
+pub enum LintKind {
+    /// A character in the `wave` that is not a known state. It is rendered as `x`.
+    UnknownWaveCharacter(char),
+    /// An edge refers to a node that does not exist. The edge is left out.
+    MissingEdgeNode(char),
+    /// A timing annotation refers to a node that does not exist. The annotation is left out.
+    MissingTimingNode(char),
+    /// An edge that cannot be parsed. The edge is left out.
+    InvalidEdge(String),
+    /// A node name that is used more than once. Only the last occurrence is used for edges.
+    DuplicateNode(char),
+    /// More data fields than data states. The extra data fields are not shown.
+    ExtraData {
+        /// The amount of data states in the `wave`
+        expected: usize,
+        /// The amount of data fields that were given
+        given: usize,
+    },
+    /// A color that cannot be parsed. The default color is used instead.
+    InvalidColor(String),
+    /// A period that is not a positive number. A period of `1` is used instead.
+    InvalidPeriod(f64),
+    /// A phase that is not a non-negative number. A phase of `0` is used instead.
+    InvalidPhase(f64),
+}
+
+/// The place in the WaveJson where a [`Lint`] was found
+///
+/// The indices follow the WaveJson layout. Therefore, a labeled group has its label at index `0`
+/// and its first signal at index `1`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LintLocation {
+    /// A property of a signal
+    Signal {
+        /// The indices into the `signal` array and the nested groups
+        indices: Vec<usize>,
+        /// The name of the property (e.g. `wave`)
+        property: &'static str,
+        /// The index of the character within the property, if the lint refers to one character
+        position: Option<usize>,
+    },
+    /// An item of the `edge` array
+    Edge(usize),
+    /// An item of the `timing` array
+    Timing(usize),
+    /// A property of an item of the `highlight` array (e.g. `color`)
+    Highlight(usize, &'static str),
+}
+
+impl Lint {
+    pub(crate) fn new(kind: LintKind, location: LintLocation) -> Self {
+        Self { kind, location }
+    }
+
+    /// Returns how severe the [`Lint`] is.
+    pub fn level(&self) -> LintLevel {
+        match self.kind {
+            LintKind::UnknownWaveCharacter(_)
+            | LintKind::MissingEdgeNode(_)
+            | LintKind::MissingTimingNode(_)
+            | LintKind::InvalidEdge(_)
+            | LintKind::InvalidColor(_)
+            | LintKind::InvalidPeriod(_)
+            | LintKind::InvalidPhase(_) => LintLevel::Error,
+            LintKind::DuplicateNode(_) | LintKind::ExtraData { .. } => LintLevel::Warning,
+        }
+    }
+
+    /// Returns the type of mistake.
+    #[inline]
+    pub fn kind(&self) -> &LintKind {
+        &self.kind
+    }
+
+    /// Returns where the mistake was found.
+    #[inline]
+    pub fn location(&self) -> &LintLocation {
+        &self.location
+    }
+}
+
+impl LintLocation {
+    pub(crate) fn signal(indices: &[usize], property: &'static str) -> Self {
+        Self::Signal {
+            indices: indices.to_vec(),
+            property,
+            position: None,
+        }
+    }
+
+    pub(crate) fn signal_at(indices: &[usize], property: &'static str, position: usize) -> Self {
+        Self::Signal {
+            indices: indices.to_vec(),
+            property,
+            position: Some(position),
+        }
+    }
+
+    /// Returns the JSON path to the value of the [`LintLocation`] (e.g. `signal[3].wave`).
+    pub fn path(&self) -> String {
+        match self {
+            Self::Signal {
+                indices, property, ..
+            } => {
+                let mut path = String::from("signal");
+                for index in indices {
+                    path.push_str(&format!("[{index}]"));
+                }
+                path.push('.');
+                path.push_str(property);
+                path
+            }
+            Self::Edge(index) => format!("edge[{index}]"),
+            Self::Timing(index) => format!("timing[{index}]"),
+            Self::Highlight(index, property) => format!("highlight[{index}].{property}"),
+        }
+    }
+
+    /// Returns the index of the character within the value, if the lint refers to one character.
+    pub fn position(&self) -> Option<usize> {
+        match self {
+            Self::Signal { position, .. } => *position,
+            Self::Edge(_) | Self::Timing(_) | Self::Highlight(..) => None,
+        }
+    }
+}
+
+impl Display for LintLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownWaveCharacter(c) => {
+                write!(f, "unknown wave character `{c}` is rendered as `x`")
+            }
+            Self::MissingEdgeNode(c) => write!(f, "edge refers to node `{c}` that does not exist"),
+            Self::MissingTimingNode(c) => {
+                write!(f, "timing annotation refers to node `{c}` that does not exist")
+            }
+            Self::InvalidEdge(edge) => write!(f, "invalid edge `{edge}`"),
+            Self::DuplicateNode(c) => write!(f, "node `{c}` is defined more than once"),
+            Self::ExtraData { expected, given } => write!(
+                f,
+                "{given} data fields are given, but the wave only has {expected} data states"
+            ),
+            Self::InvalidColor(color) => write!(f, "invalid color `{color}`"),
+            Self::InvalidPeriod(period) => {
+                write!(f, "period `{period}` is not a positive number")
+            }
+            Self::InvalidPhase(phase) => {
+                write!(f, "phase `{phase}` is not a non-negative number")
+            }
+        }
+    }
+}
+
+impl Display for LintLocation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}`", self.path())?;
+
+        if let Some(position) = self.position() {
+            write!(f, ", character {}", position + 1)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Lint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {} in {}", self.level(), self.kind, self.location)
+    }
+}
+
+#[cfg(all(test, feature = "json5"))]
+mod tests {
+    use super::*;
+    use crate::wavejson::WaveJson;
+
+    fn lints(src: &str) -> Vec<Lint> {
+        WaveJson::from_json5(src).unwrap().validate()
+    }
+
+    #[test]
+    fn valid_figure() {
+        let src = "{ signal: [ { wave: 'p.=.', data: 'a', node: '.a..' } ], edge: ['a-a'] }";
+        assert_eq!(lints(src), []);
+    }
+
+    #[test]
+    fn group_indices() {
+        let src = "{ signal: [ {}, ['label', { wave: '0k' }] ] }";
+        assert_eq!(
+            lints(src),
+            [Lint::new(
+                LintKind::UnknownWaveCharacter('k'),
+                LintLocation::signal_at(&[1, 1], "wave", 1),
+            )]
+        );
+    }
+
+    #[test]
+    fn edge_indices() {
+        let src = "{ signal: [ { node: 'a' } ], edge: ['?', 'a~>b'] }";
+        assert_eq!(
+            lints(src),
+            [
+                Lint::new(
+                    LintKind::InvalidEdge("?".to_string()),
+                    LintLocation::Edge(0)
+                ),
+                Lint::new(LintKind::MissingEdgeNode('b'), LintLocation::Edge(1)),
+            ]
+        );
+    }
+
+    #[test]
+    fn timing_indices() {
+        let src = "{ signal: [ { node: 'ab' } ], \
+                   timing: [{ from: 'a', to: 'b' }, { from: 'c', to: 'a' }] }";
+        assert_eq!(
+            lints(src),
+            [Lint::new(
+                LintKind::MissingTimingNode('c'),
+                LintLocation::Timing(1)
+            )]
+        );
+    }
+
+    #[test]
+    fn highlight_color() {
+        let src = "{ signal: [], highlight: [{ from: 0, to: 1, color: 'red?' }] }";
+        assert_eq!(
+            lints(src),
+            [Lint::new(
+                LintKind::InvalidColor("red?".to_string()),
+                LintLocation::Highlight(0, "color")
+            )]
+        );
+    }
+}
@@ -1,5 +1,10 @@
 //! A collection of markers that get overlayed onto the signal diagram.
 
+use std::ops::Range;
+
+use crate::text::RichText;
+use crate::Color;
+
 use super::CycleOffset;
 
 /// A marker for a group of [`AssembledLine`][crate::AssembledLine]s.
@@ -11,7 +16,7 @@ use super::CycleOffset;
 pub struct GroupMarker<'a> {
     start: u32,
     end: u32,
-    label: Option<&'a str>,
+    label: Option<&'a RichText>,
     depth: u32,
 }
 
@@ -26,12 +31,62 @@ pub enum ClockEdge {
 
 /// The enumeration above or below signals indicating the number of the current cycle.
 ///
-/// The enumeration starts at `start` with steps of `every`. The marker contains `start,
-/// start+every, start+2*every, ...` until the diagram cycle count is reached.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// By default, the enumeration starts at `start` with steps of `every`. The marker contains
+/// `start, start+every, start+2*every, ...` until the diagram cycle count is reached. The labels
+/// can also be a custom sequence (e.g. `T0, T1, ...` or named phases) and the labels can be placed
+/// at the center of the cycles or at the edges between cycles.
+#[derive(Debug, Clone, PartialEq)]
 pub struct CycleEnumerationMarker {
-    start: u32,
+    labels: CycleLabels,
     every: u32,
+    alignment: CycleMarkerAlignment,
+}
+
+/// The labels of a [`CycleEnumerationMarker`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum CycleLabels {
+    /// The numbers `start, start+step, start+2*step, ...`
+    Numbered {
+        /// The first number
+        start: f64,
+        /// The difference between two consecutive numbers
+        step: f64,
+    },
+    /// A custom label for every position. Positions past the end of the list are left empty.
+    Custom(Vec<String>),
+}
+
+/// Where the labels of a [`CycleEnumerationMarker`] are placed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CycleMarkerAlignment {
+    /// In the middle of every cycle. This is the `tock` in WaveJson.
+    #[default]
+    Center,
+    /// At the edges between the cycles, including the start of the first cycle and end of the last
+    /// cycle. This is the `tick` in WaveJson.
+    Edge,
+}
+
+/// A shaded window over all the signals between two [`CycleOffset`]s.
+///
+/// This is drawn behind the signals and can be used to point at a region of interest. A
+/// highlight without a color uses the color from the
+/// [`HighlightOptions`][super::options::HighlightOptions].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Highlight {
+    start: CycleOffset,
+    end: CycleOffset,
+    color: Option<Color>,
+    label: Option<RichText>,
+}
+
+/// A vertical line over all the signals at a [`CycleOffset`].
+///
+/// This is drawn on top of the signals and can be used to point at a specific moment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cursor {
+    at: CycleOffset,
+    label: Option<RichText>,
 }
 
 /// An arrow illustrating where a clock edge is happening.
@@ -44,7 +99,7 @@ pub struct ClockEdgeMarker {
 impl<'a> GroupMarker<'a> {
     /// Create a new [`GroupMarker`] capturing lines `start..end`.
     #[inline]
-    pub fn new(start: u32, end: u32, label: Option<&'a str>, depth: u32) -> Self {
+    pub fn new(start: u32, end: u32, label: Option<&'a RichText>, depth: u32) -> Self {
         Self {
             start,
             end,
@@ -67,7 +122,7 @@ impl<'a> GroupMarker<'a> {
 
     /// Returns the optional label of a [`GroupMarker`].
     #[inline]
-    pub fn label(&self) -> Option<&str> {
+    pub fn label(&self) -> Option<&'a RichText> {
         self.label
     }
 
@@ -111,20 +166,180 @@ impl CycleEnumerationMarker {
     /// To number all the cycles, utilize a value of `every` equal to `0` or `1`.
     #[inline]
     pub fn new(start: u32, every: u32) -> Self {
-        Self { start, every: every.max(1) }
+        Self::with(
+            CycleLabels::Numbered {
+                start: f64::from(start),
+                step: 1.,
+            },
+            every,
+        )
+    }
+
+    /// Create a new [`CycleEnumerationMarker`] with a set of `labels` where every `every`th
+    /// label is shown.
+    ///
+    /// To show all the labels, utilize a value of `every` equal to `0` or `1`.
+    #[inline]
+    pub fn with(labels: CycleLabels, every: u32) -> Self {
+        Self {
+            labels,
+            every: every.max(1),
+            alignment: CycleMarkerAlignment::default(),
+        }
+    }
+
+    /// Set where the labels of the [`CycleEnumerationMarker`] are placed.
+    #[inline]
+    pub fn alignment(mut self, alignment: CycleMarkerAlignment) -> Self {
+        self.alignment = alignment;
+        self
     }
 
     /// Return the cycle number for the first numbered cycle.
+    ///
+    /// This is `0` for [`CycleLabels::Custom`] labels. A `start` that is not a whole number is
+    /// rounded down and a negative `start` is returned as `0`.
     #[inline]
-    pub fn start(self) -> u32 {
-        self.start
+    pub fn start(&self) -> u32 {
+        match self.labels {
+            CycleLabels::Numbered { start, .. } => start as u32,
+            CycleLabels::Custom(_) => 0,
+        }
+    }
+
+    /// Return the labels of the [`CycleEnumerationMarker`].
+    #[inline]
+    pub fn labels(&self) -> &CycleLabels {
+        &self.labels
     }
 
     /// Return every how manyth cycle is numbered.
     #[inline]
-    pub fn every(self) -> u32 {
+    pub fn every(&self) -> u32 {
         self.every
     }
+
+    /// Return where the labels are placed.
+    #[inline]
+    pub fn get_alignment(&self) -> CycleMarkerAlignment {
+        self.alignment
+    }
+
+    /// Returns the labels that are shown for a diagram with `num_cycles` cycles together with the
+    /// position of the label.
+    ///
+    /// The position is the index of the cycle for [`CycleMarkerAlignment::Center`] and the index
+    /// of the edge before that cycle for [`CycleMarkerAlignment::Edge`].
+    pub fn visible_labels(&self, num_cycles: u32) -> Vec<(u32, String)> {
+        let num_positions = match self.alignment {
+            CycleMarkerAlignment::Center => num_cycles,
+            CycleMarkerAlignment::Edge => num_cycles + 1,
+        };
+
+        (0..num_positions)
+            .step_by(self.every as usize)
+            .filter_map(|i| Some((i, self.labels.get(i)?)))
+            .collect()
+    }
+}
+
+impl CycleLabels {
+    /// Returns the label at position `i`.
+    pub fn get(&self, i: u32) -> Option<String> {
+        match self {
+            Self::Numbered { start, step } => Some(format_number(start + step * f64::from(i))),
+            Self::Custom(labels) => labels.get(i as usize).cloned(),
+        }
+    }
+}
+
+/// Format a number without any floating point artifacts (e.g. `0.30000000000000004`).
+fn format_number(n: f64) -> String {
+    const PRECISION: f64 = 1_000_000.;
+
+    let n = (n * PRECISION).round() / PRECISION;
+
+    // Prevent a `-0`
+    let n = if n == 0. { 0. } else { n };
+
+    n.to_string()
+}
+
+impl Highlight {
+    /// Create a new unlabeled [`Highlight`] over the cycles in `range`.
+    #[inline]
+    pub fn new(range: Range<impl Into<CycleOffset>>) -> Self {
+        Self {
+            start: range.start.into(),
+            end: range.end.into(),
+            color: None,
+            label: None,
+        }
+    }
+
+    /// Set the color of the [`Highlight`].
+    #[inline]
+    pub fn color(mut self, color: Color) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    /// Give a label to the [`Highlight`]. An empty label is not shown.
+    #[inline]
+    pub fn label(mut self, label: impl Into<RichText>) -> Self {
+        let label = label.into();
+        self.label = (!label.is_empty()).then_some(label);
+        self
+    }
+
+    /// Returns the range of cycles that the [`Highlight`] covers.
+    #[inline]
+    pub fn range(&self) -> Range<CycleOffset> {
+        self.start..self.end
+    }
+
+    /// Returns the color of the [`Highlight`].
+    #[inline]
+    pub fn get_color(&self) -> Option<Color> {
+        self.color
+    }
+
+    /// Returns the label of the [`Highlight`].
+    #[inline]
+    pub fn get_label(&self) -> Option<&RichText> {
+        self.label.as_ref()
+    }
+}
+
+impl Cursor {
+    /// Create a new unlabeled [`Cursor`] at the cycle offset `at`.
+    #[inline]
+    pub fn new(at: impl Into<CycleOffset>) -> Self {
+        Self {
+            at: at.into(),
+            label: None,
+        }
+    }
+
+    /// Give a label to the [`Cursor`]. An empty label is not shown.
+    #[inline]
+    pub fn label(mut self, label: impl Into<RichText>) -> Self {
+        let label = label.into();
+        self.label = (!label.is_empty()).then_some(label);
+        self
+    }
+
+    /// Returns where the [`Cursor`] is positioned.
+    #[inline]
+    pub fn at(&self) -> CycleOffset {
+        self.at
+    }
+
+    /// Returns the label of the [`Cursor`].
+    #[inline]
+    pub fn get_label(&self) -> Option<&RichText> {
+        self.label.as_ref()
+    }
 }
 
 impl ClockEdgeMarker {
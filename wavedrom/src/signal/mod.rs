@@ -1,33 +1,41 @@
 //! The logic to render signal diagram
 
+pub mod bus;
 mod cycle_offset;
 pub mod edges;
 mod figure;
+pub mod lint;
 pub mod markers;
 pub mod options;
 mod path;
 mod render;
+pub mod timing;
 
-pub use cycle_offset::{CycleOffset, InCycleOffset};
+pub use cycle_offset::CycleOffset;
 pub use figure::{SignalFigure, SignalFigureSection, SignalFigureSectionGroup};
 pub use path::*;
+pub use render::TextStyle;
 
-use std::num::NonZeroU16;
+use std::borrow::Cow;
 
+use bus::{Bus, BusFormat};
 use edges::LineEdgeMarkers;
-use markers::{CycleEnumerationMarker, GroupMarker};
+use markers::{Cursor, CycleEnumerationMarker, GroupMarker, Highlight};
+
+use crate::text::RichText;
 
 use self::options::PathOptions;
 
 /// A diagram signal line with a set of cycles.
 #[derive(Debug, Clone)]
 pub struct Signal {
-    name: String,
+    name: RichText,
     cycles: Vec<CycleState>,
-    data: Vec<String>,
+    data: Vec<RichText>,
     node: String,
-    period: NonZeroU16,
+    period: CycleOffset,
     phase: CycleOffset,
+    bus: Option<Bus>,
 }
 
 /// A line of the [`AssembledFigure`].
@@ -35,7 +43,8 @@ pub struct Signal {
 /// This contains the shaped signal path, the group nesting depth and the name of the signal line.
 #[derive(Debug, Clone)]
 pub struct AssembledLine<'a> {
-    text: &'a str,
+    text: &'a RichText,
+    signal: &'a Signal,
     path: AssembledSignalPath,
 }
 
@@ -62,11 +71,11 @@ pub struct AssembledFigure<'a> {
     group_label_at_depth: Vec<bool>,
     max_group_depth: u32,
 
-    header_text: Option<&'a str>,
-    footer_text: Option<&'a str>,
+    header_text: Option<&'a RichText>,
+    footer_text: Option<&'a RichText>,
 
-    top_cycle_marker: Option<CycleEnumerationMarker>,
-    bottom_cycle_marker: Option<CycleEnumerationMarker>,
+    top_cycle_marker: Option<&'a CycleEnumerationMarker>,
+    bottom_cycle_marker: Option<&'a CycleEnumerationMarker>,
 
     path_assemble_options: PathOptions,
 
@@ -74,6 +83,9 @@ pub struct AssembledFigure<'a> {
     group_markers: Vec<GroupMarker<'a>>,
 
     line_edge_markers: LineEdgeMarkers<'a>,
+
+    highlights: &'a [Highlight],
+    cursors: &'a [Cursor],
 }
 
 impl<'a> AssembledFigure<'a> {
@@ -155,27 +167,39 @@ impl<'a> AssembledFigure<'a> {
 
     /// Returns a potential header text of the [`AssembledFigure`]
     #[inline]
-    pub fn header_text(&self) -> Option<&'a str> {
+    pub fn header_text(&self) -> Option<&'a RichText> {
         self.header_text
     }
 
     /// Returns a potential footer text of the [`AssembledFigure`]
     #[inline]
-    pub fn footer_text(&self) -> Option<&'a str> {
+    pub fn footer_text(&self) -> Option<&'a RichText> {
         self.footer_text
     }
 
     /// Returns a [`CycleEnumerationMarker`] above the signals of the [`AssembledFigure`]
     #[inline]
-    pub fn top_cycle_marker(&self) -> Option<CycleEnumerationMarker> {
+    pub fn top_cycle_marker(&self) -> Option<&'a CycleEnumerationMarker> {
         self.top_cycle_marker
     }
 
     /// Returns a [`CycleEnumerationMarker`] below the signals of the [`AssembledFigure`]
     #[inline]
-    pub fn bottom_cycle_marker(&self) -> Option<CycleEnumerationMarker> {
+    pub fn bottom_cycle_marker(&self) -> Option<&'a CycleEnumerationMarker> {
         self.bottom_cycle_marker
     }
+
+    /// Returns the [`Highlight`]s that are drawn behind the signals
+    #[inline]
+    pub fn highlights(&self) -> &'a [Highlight] {
+        self.highlights
+    }
+
+    /// Returns the [`Cursor`]s that are drawn on top of the signals
+    #[inline]
+    pub fn cursors(&self) -> &'a [Cursor] {
+        self.cursors
+    }
 }
 
 impl AssembledLine<'_> {
@@ -184,15 +208,25 @@ impl AssembledLine<'_> {
     }
 }
 
+/// A period of zero would collapse the signal, so one cycle is used instead.
+fn non_zero_period(period: CycleOffset) -> CycleOffset {
+    if period.is_zero() {
+        CycleOffset::new_rounded(1)
+    } else {
+        period
+    }
+}
+
 impl Default for Signal {
     fn default() -> Self {
         Self {
-            name: String::new(),
+            name: RichText::new(),
             cycles: Vec::new(),
             data: Vec::new(),
             node: String::new(),
-            period: NonZeroU16::MIN,
+            period: CycleOffset::new_rounded(1),
             phase: CycleOffset::default(),
+            bus: None,
         }
     }
 }
@@ -200,22 +234,48 @@ impl Default for Signal {
 impl Signal {
     /// Create a new [`Signal`] with a set of parameters.
     pub fn with(
-        name: String,
+        name: RichText,
         cycles: Vec<CycleState>,
-        data: Vec<String>,
+        data: Vec<RichText>,
         node: String,
-        period: u16,
+        period: CycleOffset,
         phase: CycleOffset,
     ) -> Self {
-        let period = NonZeroU16::new(period).unwrap_or(NonZeroU16::MIN);
-
         Self {
             name,
             cycles,
             data,
             node,
-            period,
+            period: non_zero_period(period),
             phase,
+            bus: None,
+        }
+    }
+
+    /// Create a new [`Signal`] for a multi-bit bus with one value per cycle.
+    ///
+    /// Consecutive equal values are merged into one [`CycleState::Data`] box and `None` values
+    /// become [`CycleState::X`]. The values are formatted with the [`BusFormat`] that is set with
+    /// [`Signal::bus_format`] when the figure is assembled. The `width` is clamped to `1..=64`
+    /// bits and bits above the `width` are ignored.
+    ///
+    /// ```
+    /// use wavedrom::signal::{CycleState, Signal};
+    ///
+    /// let signal = Signal::bus(4, [Some(1), Some(1), None, Some(0x1A)]);
+    /// assert_eq!(
+    ///     signal.cycles(),
+    ///     [CycleState::Data, CycleState::Continue, CycleState::X, CycleState::Data],
+    /// );
+    /// assert_eq!(signal.get_bus().unwrap().values(), [0x1, 0xA]);
+    /// ```
+    pub fn bus(width: u32, values: impl IntoIterator<Item = impl Into<Option<u64>>>) -> Self {
+        let (bus, cycles) = Bus::from_cycle_values(width, values.into_iter().map(Into::into));
+
+        Self {
+            cycles,
+            bus: Some(bus),
+            ..Self::default()
         }
     }
 
@@ -290,7 +350,10 @@ impl Signal {
     /// This is used for putting markers and arrows / edges on the diagram. For more information
     /// look at the [`edges`][crate::edges] documentation.
     #[inline]
-    pub fn add_data_fields(mut self, fields: impl IntoIterator<Item = impl Into<String>>) -> Self {
+    pub fn add_data_fields(
+        mut self,
+        fields: impl IntoIterator<Item = impl Into<RichText>>,
+    ) -> Self {
         self.data.extend(fields.into_iter().map(Into::into));
         self
     }
@@ -300,7 +363,7 @@ impl Signal {
     /// This is used to fill up a container state, such as the [`CycleState::Data`] or
     /// [`CycleState::Box2`], with text.
     #[inline]
-    pub fn add_data_field(mut self, data: impl Into<String>) -> Self {
+    pub fn add_data_field(mut self, data: impl Into<RichText>) -> Self {
         self.data.push(data.into());
         self
     }
@@ -315,11 +378,12 @@ impl Signal {
     }
 
     /// Set the period for a signal. This is mostly important for clock signals.
+    ///
+    /// The period can be a fraction of a cycle (e.g. `1.5`). A period of zero is replaced by a
+    /// period of one cycle.
     #[inline]
-    pub fn period(mut self, period: u16) -> Self {
-        let period = NonZeroU16::new(period).unwrap_or(NonZeroU16::MIN);
-
-        self.period = period;
+    pub fn period(mut self, period: impl Into<CycleOffset>) -> Self {
+        self.period = non_zero_period(period.into());
         self
     }
 
@@ -330,16 +394,27 @@ impl Signal {
         self
     }
 
+    /// Set the [`BusFormat`] that is used to display the values of a [`Signal::bus`].
+    ///
+    /// This does nothing for a [`Signal`] that is not a bus.
+    #[inline]
+    pub fn bus_format(mut self, format: BusFormat) -> Self {
+        if let Some(bus) = &mut self.bus {
+            bus.set_format(format);
+        }
+        self
+    }
+
     /// Set the name for a [`Signal`]
     #[inline]
-    pub fn name(mut self, name: impl Into<String>) -> Self {
+    pub fn name(mut self, name: impl Into<RichText>) -> Self {
         self.name = name.into();
         self
     }
 
     /// Get the name of the [`Signal`].
     #[inline]
-    pub fn get_name(&self) -> &str {
+    pub fn get_name(&self) -> &RichText {
         &self.name
     }
 
@@ -351,25 +426,35 @@ impl Signal {
 
     /// Get the data of the [`Signal`].
     #[inline]
-    pub fn get_data_fields(&self) -> &[String] {
+    pub fn get_data_fields(&self) -> &[RichText] {
         &self.data
     }
 
-    /// Get the phase of the [`Signal`].
+    /// Get the [`Bus`] values of a [`Signal`] that was created with [`Signal::bus`].
     #[inline]
-    pub fn get_phase(&self) -> CycleOffset {
-        self.phase
+    pub fn get_bus(&self) -> Option<&Bus> {
+        self.bus.as_ref()
     }
 
-    /// Get the period of the [`Signal`].
+    /// Get the text that is put in the boxes of the [`Signal`].
+    ///
+    /// This is the formatted [`Bus`] values for a bus and the data fields otherwise.
+    pub(crate) fn box_contents(&self) -> Cow<'_, [RichText]> {
+        match &self.bus {
+            Some(bus) => Cow::Owned(bus.data_fields()),
+            None => Cow::Borrowed(&self.data),
+        }
+    }
+
+    /// Get the phase of the [`Signal`].
     #[inline]
-    pub fn get_period(&self) -> u16 {
-        self.period.get()
+    pub fn get_phase(&self) -> CycleOffset {
+        self.phase
     }
 
     /// Get the period of the [`Signal`].
     #[inline]
-    pub(crate) fn get_period_internal(&self) -> NonZeroU16 {
+    pub fn get_period(&self) -> CycleOffset {
         self.period
     }
 
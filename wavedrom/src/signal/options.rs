@@ -12,6 +12,10 @@ define_options! {
         group_indicator: GroupIndicatorOptions[PartialGroupIndicatorOptions],
         /// The arrow / edge options
         edge: EdgeOptions[PartialEdgeOptions],
+        /// The timing annotation options
+        timing: TimingOptions[PartialTimingOptions],
+        /// The highlight and cursor options
+        highlight: HighlightOptions[PartialHighlightOptions],
         /// The path options
         path: PathOptions[PartialPathOptions],
 
@@ -92,6 +96,63 @@ define_options! {
     }
 }
 
+define_options! {
+    /// The timing annotation options for the figure
+    TimingOptions,
+
+    /// A subset of the [`TimingOptions`]
+    PartialTimingOptions {
+        /// The height of a row of dimension lines below the signals
+        row_height: u32 => 20,
+        /// The minimal horizontal spacing between dimension lines in the same row
+        spacing: u32 => 8,
+
+        /// The font size for a dimension line label
+        font_size: u32 => 12,
+        /// The text color for a dimension line label
+        text_color: Color => Color::BLACK,
+        /// The background color for a dimension line label
+        text_background_color: Color => Color::WHITE,
+
+        /// The line color for a dimension line
+        line_color: Color => Color::BLACK,
+        /// The line color for the extension lines to the nodes
+        extension_color: Color => Color { red: 0x88, green: 0x88, blue: 0x88 },
+        /// The arrow size for a dimension line
+        arrow_size: u32 => 6,
+
+        /// The color of a shaded window
+        shade_color: Color => Color { red: 0x44, green: 0x88, blue: 0xFF },
+        /// The opacity of a shaded window in percent
+        shade_opacity: u32 => 20,
+    }
+}
+
+define_options! {
+    /// The highlight and cursor options for the figure
+    HighlightOptions,
+
+    /// A subset of the [`HighlightOptions`]
+    PartialHighlightOptions {
+        /// The color of a highlight that does not specify a color
+        color: Color => Color { red: 0xFF, green: 0xCC, blue: 0x00 },
+        /// The opacity of a highlight in percent
+        opacity: u32 => 30,
+
+        /// The line color of a cursor
+        cursor_color: Color => Color { red: 0xE0, green: 0x00, blue: 0x00 },
+        /// The line width of a cursor
+        cursor_width: u32 => 2,
+
+        /// The font size for a highlight or cursor label
+        font_size: u32 => 12,
+        /// The text color for a highlight or cursor label
+        text_color: Color => Color::BLACK,
+        /// The background color for a highlight or cursor label
+        text_background_color: Color => Color::WHITE,
+    }
+}
+
 impl GroupIndicatorOptions {
     /// The label spacing added to the label font size
     pub fn label_height(&self) -> u32 {
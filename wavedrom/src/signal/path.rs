@@ -1,4 +1,4 @@
-use std::num::NonZeroU16;
+use crate::text::RichText;
 
 use super::options::PathOptions;
 use super::markers::{ClockEdge, ClockEdgeMarker};
@@ -8,9 +8,9 @@ use super::CycleOffset;
 #[derive(Debug, Clone)]
 pub struct SignalPath<'a> {
     states: Vec<CycleState>,
-    period: NonZeroU16,
+    period: CycleOffset,
     phase: CycleOffset,
-    data: &'a [String],
+    data: &'a [RichText],
 }
 
 /// A state that a signal can be at any cycle
@@ -70,7 +70,17 @@ pub enum CycleState {
 
 impl From<char> for CycleState {
     fn from(value: char) -> Self {
-        match value {
+        CycleState::from_char(value).unwrap_or(CycleState::X)
+    }
+}
+
+impl CycleState {
+    /// Returns the [`CycleState`] for a WaveJson wave character.
+    ///
+    /// This returns `None` if the character is not a known state. The [`From<char>`]
+    /// implementation turns these into [`CycleState::X`].
+    pub fn from_char(c: char) -> Option<Self> {
+        Some(match c {
             '1' => CycleState::Top,
             '0' => CycleState::Bottom,
             'z' => CycleState::Middle,
@@ -96,9 +106,59 @@ impl From<char> for CycleState {
             'H' => CycleState::HighMarked,
             'l' => CycleState::LowUnmarked,
             'L' => CycleState::LowMarked,
-            _ => CycleState::X,
+            _ => return None,
+        })
+    }
+
+    /// Returns the WaveJson wave character for the [`CycleState`].
+    ///
+    /// This is the inverse of [`CycleState::from_char`].
+    pub fn to_char(self) -> char {
+        match self {
+            CycleState::Top => '1',
+            CycleState::Bottom => '0',
+            CycleState::Middle => 'z',
+            CycleState::X => 'x',
+            CycleState::PosedgeClockUnmarked => 'p',
+            CycleState::PosedgeClockMarked => 'P',
+            CycleState::NegedgeClockUnmarked => 'n',
+            CycleState::NegedgeClockMarked => 'N',
+            CycleState::Box2 => '2',
+            CycleState::Box3 => '3',
+            CycleState::Box4 => '4',
+            CycleState::Box5 => '5',
+            CycleState::Box6 => '6',
+            CycleState::Box7 => '7',
+            CycleState::Box8 => '8',
+            CycleState::Box9 => '9',
+            CycleState::Continue => '.',
+            CycleState::Gap => '|',
+            CycleState::Data => '=',
+            CycleState::Up => 'u',
+            CycleState::Down => 'd',
+            CycleState::HighUnmarked => 'h',
+            CycleState::HighMarked => 'H',
+            CycleState::LowUnmarked => 'l',
+            CycleState::LowMarked => 'L',
         }
     }
+
+    /// Returns whether the state is a box that displays a data field.
+    #[inline]
+    pub fn is_data(self) -> bool {
+        matches!(
+            self,
+            CycleState::Box2
+                | CycleState::Box3
+                | CycleState::Box4
+                | CycleState::Box5
+                | CycleState::Box6
+                | CycleState::Box7
+                | CycleState::Box8
+                | CycleState::Box9
+                | CycleState::Data
+        )
+    }
 }
 
 /// A command for a signal path to take with relative coordinates.
@@ -153,7 +213,7 @@ pub struct SignalPathSegment {
 
     actions: Vec<PathCommand>,
 
-    text: Option<String>,
+    text: Option<RichText>,
     gaps: Vec<CycleOffset>,
     clock_edge_markers: Vec<ClockEdgeMarker>,
 }
@@ -222,8 +282,8 @@ impl SignalPathSegment {
     }
 
     /// Returns the possible marker text that a segment contains.
-    pub fn marker_text(&self) -> Option<&str> {
-        self.text.as_ref().map(|s| &s[..])
+    pub fn marker_text(&self) -> Option<&RichText> {
+        self.text.as_ref()
     }
 
     /// The starting `x` value
@@ -248,7 +308,7 @@ pub struct SignalSegmentIter<'a> {
 
     cycle_offset: CycleOffset,
 
-    period: NonZeroU16,
+    period: CycleOffset,
 
     prev: Option<CycleState>,
 
@@ -256,7 +316,7 @@ pub struct SignalSegmentIter<'a> {
     backward: PathData,
 
     box_index: usize,
-    box_content: &'a [String],
+    box_content: &'a [RichText],
 
     clock_edge_markers: Vec<ClockEdgeMarker>,
     gaps: Vec<CycleOffset>,
@@ -264,6 +324,19 @@ pub struct SignalSegmentIter<'a> {
     options: PathOptions,
 }
 
+/// An iterator over the cycle offsets at which the states of a [`SignalPath`] start
+///
+/// This uses the same time base as the assembled path, so the period and phase of the signal are
+/// taken into account. After the last state, the iterator continues as if the last state was
+/// continued forever.
+#[derive(Debug, Clone)]
+pub struct StateOffsets<'a> {
+    inner: std::slice::Iter<'a, CycleState>,
+    prev: Option<CycleState>,
+    period: CycleOffset,
+    offset: CycleOffset,
+}
+
 /// A item given by the [`SignalSegmentIter`]
 #[derive(Debug)]
 pub struct SignalSegmentItem {
@@ -372,8 +445,6 @@ impl<'a> SignalSegmentIter<'a> {
     fn wave_path(&mut self, mut state: CycleState) {
         let t = i32::from(self.options.transition_offset);
         let h = i32::from(self.options.signal_height);
-        let w = i32::from(self.options.cycle_width);
-        let p = i32::from(self.period.get());
 
         use CycleState::*;
 
@@ -387,6 +458,15 @@ impl<'a> SignalSegmentIter<'a> {
             state = prev;
         }
 
+        // The widths are taken between rounded positions, so that fractional cycle lengths do
+        // not accumulate rounding errors.
+        let cycle_width = u32::from(self.options.cycle_width);
+        let start = self.cycle_offset;
+        let length = cycle_length(state, Some(state), self.period);
+        let x = |offset: CycleOffset| offset.width_offset(cycle_width) as i32;
+        let w = x(start + length) - x(start);
+        let half_w = x(start + length.half()) - x(start);
+
         match state {
             Top | Bottom | Middle => self.forward.horizontal_line(w - t * 2),
             PosedgeClockMarked | PosedgeClockUnmarked => {
@@ -395,9 +475,9 @@ impl<'a> SignalSegmentIter<'a> {
                 }
 
                 self.forward.vertical_line(-h);
-                self.forward.horizontal_line(w * p / 2);
+                self.forward.horizontal_line(half_w);
                 self.forward.vertical_line(h);
-                self.forward.horizontal_line(w * p / 2);
+                self.forward.horizontal_line(w - half_w);
             }
             NegedgeClockMarked | NegedgeClockUnmarked => {
                 if state == NegedgeClockMarked {
@@ -405,9 +485,9 @@ impl<'a> SignalSegmentIter<'a> {
                 }
 
                 self.forward.vertical_line(h);
-                self.forward.horizontal_line(w * p / 2);
+                self.forward.horizontal_line(half_w);
                 self.forward.vertical_line(-h);
-                self.forward.horizontal_line(w * p / 2);
+                self.forward.horizontal_line(w - half_w);
             }
             HighUnmarked | HighMarked | LowUnmarked | LowMarked => {
                 self.forward.horizontal_line(w - t);
@@ -1044,20 +1124,51 @@ impl<'a> SignalSegmentIter<'a> {
         }
     }
 
-    fn cycle_length(&self, mut state: CycleState) -> CycleOffset {
-        use CycleState::*;
+    fn cycle_length(&self, state: CycleState) -> CycleOffset {
+        cycle_length(state, self.prev, self.period)
+    }
+}
 
-        if matches!(state, Continue | Gap) {
-            state = self.prev.unwrap_or(X);
+/// Returns the number of cycles that `state` takes up when the last state that was not continued
+/// is `prev`.
+fn cycle_length(
+    mut state: CycleState,
+    prev: Option<CycleState>,
+    period: CycleOffset,
+) -> CycleOffset {
+    use CycleState::*;
+
+    if matches!(state, Continue | Gap) {
+        state = prev.unwrap_or(X);
+    }
+
+    match state {
+        Top | Bottom | Middle | Box2 | Box3 | Box4 | Box5 | Box6 | Box7 | Box8 | Box9 | Data
+        | X | Down | Up | HighUnmarked | HighMarked | LowUnmarked | LowMarked => {
+            CycleOffset::new_rounded(1)
         }
+        PosedgeClockUnmarked | PosedgeClockMarked | NegedgeClockUnmarked | NegedgeClockMarked => {
+            period
+        }
+        Continue | Gap => unreachable!(),
+    }
+}
 
-        CycleOffset::new_rounded(match state {
-            Top | Bottom | Middle | Box2 | Box3 | Box4 | Box5 | Box6 | Box7 | Box8 | Box9
-            | Data | X | Down | Up | HighUnmarked | HighMarked | LowUnmarked | LowMarked => 1,
-            PosedgeClockUnmarked | PosedgeClockMarked | NegedgeClockUnmarked
-            | NegedgeClockMarked => self.period.get().into(),
-            Continue | Gap => unreachable!(),
-        })
+impl Iterator for StateOffsets<'_> {
+    type Item = CycleOffset;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+
+        // After the last state, the last state is continued
+        let state = self.inner.next().copied().unwrap_or(CycleState::Continue);
+        if !matches!(state, CycleState::Continue | CycleState::Gap) {
+            self.prev = Some(state);
+        }
+
+        self.offset += cycle_length(state, self.prev, self.period);
+
+        Some(offset)
     }
 }
 
@@ -1066,8 +1177,8 @@ impl<'a> SignalPath<'a> {
     #[inline]
     pub fn new(
         states: &[CycleState],
-        data: &'a [String],
-        period: NonZeroU16,
+        data: &'a [RichText],
+        period: CycleOffset,
         phase: CycleOffset,
     ) -> Self {
         let states = states.to_vec();
@@ -1116,6 +1227,18 @@ impl<'a> SignalPath<'a> {
         self.assemble_with_options(PathOptions::default())
     }
 
+    /// Get an iterator over the cycle offsets at which each state starts.
+    ///
+    /// The iterator never ends. For more information, look at [`StateOffsets`].
+    pub fn state_offsets(&self) -> StateOffsets<'_> {
+        StateOffsets {
+            inner: self.states.iter(),
+            prev: None,
+            period: self.period,
+            offset: self.phase,
+        }
+    }
+
     /// Get a iterator over assembled signal segments
     pub fn iter(&'a self, options: PathOptions) -> SignalSegmentIter<'a> {
         let mut iter = SignalSegmentIter {
@@ -1326,32 +1449,50 @@ impl PathSegmentBackground {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::signal::cycle_offset::InCycleOffset;
 
     #[test]
     fn calculate_cycle_length() {
         macro_rules! assert_cycle_length {
-            ([$($item:ident),* $(,)?], $period:literal, ($phase_index:literal, $phase_in_offset:ident) => $result:literal) => {
-                let period = NonZeroU16::new($period).unwrap();
+            ([$($item:ident),* $(,)?], $period:literal, $phase:literal => $result:literal) => {
+                let period = CycleOffset::from($period);
                 let options = PathOptions::default();
                 let num_cycles = SignalPath::new(
                     &[$(CycleState::$item),*],
                     &[],
                     period,
-                    CycleOffset::new($phase_index, InCycleOffset::$phase_in_offset),
+                    CycleOffset::from($phase),
                 ).iter(options).last().map_or(0, |i| i.end_cycle.ceil_num_cycles());
                 assert_eq!(num_cycles, $result);
             };
         }
 
-        assert_cycle_length!([], 1, (0, Begin) => 0);
-        assert_cycle_length!([], 2, (0, Begin) => 0);
-        assert_cycle_length!([Box2], 1, (0, Begin) => 1);
-        assert_cycle_length!([Box2], 2, (0, Begin) => 1);
-        assert_cycle_length!([PosedgeClockMarked], 1, (0, Begin) => 1);
-        assert_cycle_length!([PosedgeClockMarked], 2, (0, Begin) => 2);
-        assert_cycle_length!([Box2, PosedgeClockMarked], 3, (0, Begin) => 4);
-        assert_cycle_length!([PosedgeClockMarked, NegedgeClockMarked], 3, (0, Begin) => 6);
-        assert_cycle_length!([PosedgeClockMarked, Continue, NegedgeClockMarked], 3, (0, Begin) => 9);
+        assert_cycle_length!([], 1.0, 0.0 => 0);
+        assert_cycle_length!([], 2.0, 0.0 => 0);
+        assert_cycle_length!([Box2], 1.0, 0.0 => 1);
+        assert_cycle_length!([Box2], 2.0, 0.0 => 1);
+        assert_cycle_length!([PosedgeClockMarked], 1.0, 0.0 => 1);
+        assert_cycle_length!([PosedgeClockMarked], 2.0, 0.0 => 2);
+        assert_cycle_length!([Box2, PosedgeClockMarked], 3.0, 0.0 => 4);
+        assert_cycle_length!([PosedgeClockMarked, NegedgeClockMarked], 3.0, 0.0 => 6);
+        assert_cycle_length!([PosedgeClockMarked, Continue, NegedgeClockMarked], 3.0, 0.0 => 9);
+        assert_cycle_length!([PosedgeClockMarked, NegedgeClockMarked], 1.5, 0.0 => 3);
+        assert_cycle_length!([PosedgeClockMarked, NegedgeClockMarked], 1.5, 0.1 => 4);
+    }
+
+    #[test]
+    fn state_offsets_follow_path() {
+        use CycleState::*;
+
+        let states = [Box2, PosedgeClockMarked, Continue, NegedgeClockMarked];
+        let period = CycleOffset::new_rounded(3);
+        let phase = CycleOffset::from(0.5);
+        let path = SignalPath::new(&states, &[], period, phase);
+
+        let offsets = path.state_offsets().take(6).collect::<Vec<_>>();
+        let expected = [0.5, 1.5, 4.5, 7.5, 10.5, 13.5].map(CycleOffset::from);
+        assert_eq!(offsets, expected);
+
+        let end_cycle = path.iter(PathOptions::default()).last().unwrap().end_cycle;
+        assert_eq!(offsets[states.len()], end_cycle);
     }
 }
@@ -2,10 +2,15 @@ use crate::signal::options::SignalOptions;
 use crate::signal::AssembledFigure;
 use crate::{Font, Options};
 
+use super::timing::occupied_range;
+
 pub struct SvgDimensions<'a> {
     figure: &'a AssembledFigure<'a>,
     options: &'a Options,
     textbox_width: Option<u32>,
+    timing_rows: Vec<u32>,
+    num_timing_rows: u32,
+    timing_width: u32,
 }
 
 impl<'a> SvgDimensions<'a> {
@@ -15,19 +20,74 @@ impl<'a> SvgDimensions<'a> {
             figure
                 .lines
                 .iter()
-                .map(|line| font.get_text_width(line.text, options.signal.name_font_size))
+                .map(|line| font.get_rich_text_width(line.text, options.signal.name_font_size))
                 .max()
                 .unwrap_or_default()
         });
 
-        Self {
+        let mut dims = Self {
             figure,
             options,
             textbox_width,
+            timing_rows: Vec::new(),
+            num_timing_rows: 0,
+            timing_width: 0,
+        };
+        dims.layout_timing_rows(font);
+        dims
+    }
+
+    /// Put every timing marker in the first row where it and its label do not overlap the
+    /// markers that are already in that row.
+    fn layout_timing_rows(&mut self, font: Font) {
+        let timing = &self.options.signal.timing;
+        let cycle_width = self.cycle_width();
+
+        // The right-most occupied x for every row
+        let mut row_ends: Vec<u32> = Vec::new();
+
+        for marker in self.figure.line_edge_markers.timing_markers() {
+            let from_x = marker.from().x().width_offset(cycle_width);
+            let to_x = marker.to().x().width_offset(cycle_width);
+
+            let label_width = marker
+                .label()
+                .map_or(0, |label| font.get_rich_text_width(label, timing.font_size));
+            let (start, end) = occupied_range(
+                u32::min(from_x, to_x),
+                u32::max(from_x, to_x),
+                label_width,
+                timing.arrow_size,
+            );
+
+            let row = match row_ends
+                .iter()
+                .position(|row_end| row_end + timing.spacing <= start)
+            {
+                Some(row) => {
+                    row_ends[row] = end;
+                    row
+                }
+                None => {
+                    row_ends.push(end);
+                    row_ends.len() - 1
+                }
+            };
+
+            self.timing_rows.push(row as u32);
+            self.timing_width = u32::max(self.timing_width, end);
         }
+
+        self.num_timing_rows = row_ends.len() as u32;
     }
 
     pub fn inner_width(&self) -> u32 {
+        // A label to the right of a dimension line on the last cycles can reach past the schema
+        self.content_width() + self.timing_width.saturating_sub(self.schema_width())
+    }
+
+    /// The width of the groups, names and signals without any timing labels that stick out
+    fn content_width(&self) -> u32 {
         let Options { spacing, .. } = self.options;
 
         let mut width = self.schema_width();
@@ -61,13 +121,14 @@ impl<'a> SvgDimensions<'a> {
         padding.figure_top
             + self.header_height()
             + self.schema_height()
+            + self.timing_height()
             + self.footer_height()
             + padding.figure_bottom
     }
 
     #[inline]
     pub fn header_width(&self) -> u32 {
-        self.inner_width()
+        self.content_width()
     }
 
     #[inline]
@@ -99,7 +160,7 @@ impl<'a> SvgDimensions<'a> {
 
     #[inline]
     pub fn footer_width(&self) -> u32 {
-        self.inner_width()
+        self.content_width()
     }
 
     #[inline]
@@ -126,9 +187,27 @@ impl<'a> SvgDimensions<'a> {
 
     #[inline]
     pub fn footer_y(&self) -> u32 {
+        self.timing_y() + self.timing_height()
+    }
+
+    #[inline]
+    pub fn timing_y(&self) -> u32 {
         self.schema_y() + self.schema_height()
     }
 
+    #[inline]
+    pub fn timing_height(&self) -> u32 {
+        self.num_timing_rows * self.options.signal.timing.row_height
+    }
+
+    /// The `y` of the dimension line of the `idx`-th timing marker
+    pub fn timing_line_y(&self, idx: usize) -> u32 {
+        let row_height = self.options.signal.timing.row_height;
+        let row = self.timing_rows.get(idx).copied().unwrap_or_default();
+
+        self.timing_y() + row * row_height + row_height / 2
+    }
+
     pub fn has_textbox(&self) -> bool {
         self.figure.lines.iter().any(|line| !line.text.is_empty())
     }
@@ -271,3 +350,36 @@ impl<'a> SvgDimensions<'a> {
         self.figure.lines.len() as u32
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::timing::TimingAnnotation;
+    use crate::signal::{Signal, SignalFigure};
+
+    #[test]
+    fn timing_label_on_last_cycle() {
+        let figure = SignalFigure::new().add_signals([
+            Signal::with_cycle_str("p...").add_nodes("...a"),
+            Signal::with_cycle_str("x..=").add_nodes("...b"),
+        ]);
+        let annotated = figure
+            .clone()
+            .add_timing_annotation(TimingAnnotation::new('a', 'b').label("tCLK_TO_OUTPUT"));
+
+        let options = Options::default();
+        let figure = figure.assemble();
+        let annotated = annotated.assemble();
+        let dims = SvgDimensions::new(&figure, Font::default(), &options);
+        let annotated_dims = SvgDimensions::new(&annotated, Font::default(), &options);
+
+        // The label is put to the right of the last cycle
+        assert!(annotated_dims.timing_width > annotated_dims.schema_width());
+        assert!(
+            annotated_dims.schema_x() + annotated_dims.timing_width
+                <= annotated_dims.inner_x() + annotated_dims.inner_width()
+        );
+        assert!(annotated_dims.figure_width() > dims.figure_width());
+        assert_eq!(annotated_dims.header_width(), dims.header_width());
+    }
+}
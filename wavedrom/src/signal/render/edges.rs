@@ -1,24 +1,14 @@
-use std::fmt::Display;
 use std::io;
 
 use super::dimensions::SvgDimensions;
 
-use crate::escape::escape_str;
+use crate::backend::{Backend, Path, PathStyle, TextLayout};
 use crate::signal::edges::{
     EdgeArrowType, EdgeVariant, LineEdge, SharpEdgeVariant, SplineEdgeVariant,
 };
+use crate::text::RichText;
 use crate::{Color, Font, Options};
 
-/// A f64 type that automatically rounds when formatting
-struct SVGF64(pub f64);
-
-impl Display for SVGF64 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let s = format!("{:.3}", self.0);
-        write!(f, "{}", s.trim_end_matches('0').trim_end_matches('.'))
-    }
-}
-
 struct PlacedVec2D {
     origin: (f64, f64),
     dir: (f64, f64),
@@ -41,7 +31,7 @@ struct BBox {
 }
 
 pub fn write_line_edge(
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     edge: LineEdge,
     dims: &SvgDimensions,
     options: &Options,
@@ -53,14 +43,14 @@ pub fn write_line_edge(
     let from = edge.from();
     let to = edge.to();
 
-    let from_x = dims.schema_x() + from.x().width_offset(path_options.cycle_width.into());
+    let from_x = dims.schema_x() + from.x().width_offset(dims.cycle_width());
     let from_y = dims.signal_top(from.y()) + u32::from(path_options.signal_height / 2);
 
     if from == to {
         return Ok((f64::from(from_x), f64::from(from_y)));
     }
 
-    let to_x = dims.schema_x() + to.x().width_offset(path_options.cycle_width.into());
+    let to_x = dims.schema_x() + to.x().width_offset(dims.cycle_width());
     let to_y = dims.signal_top(to.y()) + u32::from(path_options.signal_height / 2);
 
     let from_bbox = edge
@@ -260,36 +250,43 @@ pub fn write_line_edge(
         end.origin
     };
 
-    let (start_x, start_y) = (SVGF64(offset_start.0), SVGF64(offset_start.1));
-    let (end_x, end_y) = (SVGF64(offset_end.0), SVGF64(offset_end.1));
+    let (start_x, start_y) = offset_start;
+    let (end_x, end_y) = offset_end;
 
-    write!(writer, r##"<g><path d="M{start_x},{start_y}"##)?;
+    let path = Path::new().move_to(start_x, start_y);
 
-    let (middle_x, middle_y) = if to_x == from_x {
-        write!(writer, "V{end_y}")?;
+    let (path, (middle_x, middle_y)) = if to_x == from_x {
         (
-            f64::from(from_x),
-            (f64::from(from_y) + f64::from(to_y)) / 2.,
+            path.vertical_to(end_y),
+            (
+                f64::from(from_x),
+                (f64::from(from_y) + f64::from(to_y)) / 2.,
+            ),
         )
     } else if to_y == from_y {
-        write!(writer, "H{end_x}")?;
         (
-            (f64::from(from_x) + f64::from(to_x)) / 2.,
-            f64::from(from_y),
+            path.horizontal_to(end_x),
+            (
+                (f64::from(from_x) + f64::from(to_x)) / 2.,
+                f64::from(from_y),
+            ),
         )
     } else {
         match *edge.variant() {
             EdgeVariant::Spline(spline_edge) => match spline_edge {
                 SplineEdgeVariant::BothHorizontal(_) => {
-                    write!(
-                        writer,
-                        "C{hx},{from_y} {hx},{to_y} {end_x},{end_y}",
-                        hx = (from_x + to_x) / 2,
-                    )?;
+                    let hx = f64::from((from_x + to_x) / 2);
 
                     (
-                        (f64::from(from_x) + f64::from(to_x)) / 2.,
-                        (f64::from(from_y) + f64::from(to_y)) / 2.,
+                        path.curve_to(
+                            (hx, f64::from(from_y)),
+                            (hx, f64::from(to_y)),
+                            (end_x, end_y),
+                        ),
+                        (
+                            (f64::from(from_x) + f64::from(to_x)) / 2.,
+                            (f64::from(from_y) + f64::from(to_y)) / 2.,
+                        ),
                     )
                 }
                 SplineEdgeVariant::StartHorizontal(_) => {
@@ -298,11 +295,12 @@ pub fn write_line_edge(
                     let cx2 = end.origin.0 - end.dir.0;
                     let cy2 = end.origin.1 - end.dir.1;
 
-                    write!(writer, "C{cx1},{cy1} {cx2},{cy2} {end_x},{end_y}",)?;
-
                     (
-                        ((start.origin.0 + end.origin.0) / 2. + cx1 + cx2) / 3.,
-                        ((start.origin.1 + end.origin.1) / 2. + cy1 + cy2) / 3.,
+                        path.curve_to((cx1, cy1), (cx2, cy2), (end_x, end_y)),
+                        (
+                            ((start.origin.0 + end.origin.0) / 2. + cx1 + cx2) / 3.,
+                            ((start.origin.1 + end.origin.1) / 2. + cy1 + cy2) / 3.,
+                        ),
                     )
                 }
                 SplineEdgeVariant::EndHorizontal(_) => {
@@ -311,62 +309,63 @@ pub fn write_line_edge(
                     let cx2 = end.origin.0 - end.dir.0;
                     let cy2 = end.origin.1 - end.dir.1;
 
-                    write!(writer, "C{cx1},{cy1} {cx2},{cy2} {end_x},{end_y}",)?;
-
                     (
-                        ((start.origin.0 + end.origin.0) / 2. + cx1 + cx2) / 3.,
-                        ((start.origin.1 + end.origin.1) / 2. + cy1 + cy2) / 3.,
+                        path.curve_to((cx1, cy1), (cx2, cy2), (end_x, end_y)),
+                        (
+                            ((start.origin.0 + end.origin.0) / 2. + cx1 + cx2) / 3.,
+                            ((start.origin.1 + end.origin.1) / 2. + cy1 + cy2) / 3.,
+                        ),
                     )
                 }
             },
             EdgeVariant::Sharp(sharp_edge) => match sharp_edge {
-                SharpEdgeVariant::Straight(_) | SharpEdgeVariant::Cross => {
-                    write!(writer, "L{end_x},{end_y}")?;
-
+                SharpEdgeVariant::Straight(_) | SharpEdgeVariant::Cross => (
+                    path.line_to(end_x, end_y),
                     (
                         (f64::from(from_x) + f64::from(to_x)) / 2.,
                         (f64::from(from_y) + f64::from(to_y)) / 2.,
-                    )
-                }
-                SharpEdgeVariant::BothHorizontal(_) => {
-                    write!(writer, "H{hx}V{end_y}H{end_x}", hx = (from_x + to_x) / 2)?;
-
+                    ),
+                ),
+                SharpEdgeVariant::BothHorizontal(_) => (
+                    path.horizontal_to((from_x + to_x) / 2)
+                        .vertical_to(end_y)
+                        .horizontal_to(end_x),
                     (
                         (f64::from(from_x) + f64::from(to_x)) / 2.,
                         (f64::from(from_y) + f64::from(to_y)) / 2.,
-                    )
-                }
-                SharpEdgeVariant::StartHorizontal(_) => {
-                    write!(writer, "H{end_x}V{end_y}")?;
-
-                    (f64::from(to_x), f64::from(from_y))
-                }
-                SharpEdgeVariant::EndHorizontal(_) => {
-                    write!(writer, "V{end_y}H{end_x}")?;
-
-                    (f64::from(from_x), f64::from(to_y))
-                }
+                    ),
+                ),
+                SharpEdgeVariant::StartHorizontal(_) => (
+                    path.horizontal_to(end_x).vertical_to(end_y),
+                    (f64::from(to_x), f64::from(from_y)),
+                ),
+                SharpEdgeVariant::EndHorizontal(_) => (
+                    path.vertical_to(end_y).horizontal_to(end_x),
+                    (f64::from(from_x), f64::from(to_y)),
+                ),
             },
         }
     };
 
+    let mut path = path;
+
     if matches!(edge.variant(), EdgeVariant::Sharp(SharpEdgeVariant::Cross)) {
         const MHEIGHT: u32 = 5;
 
         if to_x == from_y {
             let top_x = offset_start.0 - f64::from(MHEIGHT);
-            write!(
-                writer,
-                "M{top_x},{start_y}h{height}M{top_x},{end_y}h{height}",
-                height = 2 * MHEIGHT
-            )?;
+            path = path
+                .move_to(top_x, start_y)
+                .horizontal_by(2 * MHEIGHT)
+                .move_to(top_x, end_y)
+                .horizontal_by(2 * MHEIGHT);
         } else if to_y == from_y {
             let top_y = offset_start.1 - f64::from(MHEIGHT);
-            write!(
-                writer,
-                "M{start_x},{top_y}v{height}M{end_x},{top_y}v{height}",
-                height = 2 * MHEIGHT
-            )?;
+            path = path
+                .move_to(start_x, top_y)
+                .vertical_by(2 * MHEIGHT)
+                .move_to(end_x, top_y)
+                .vertical_by(2 * MHEIGHT);
         } else if let Some((xoffset, yoffset)) = offset_in_dir(
             (0, 0),
             (
@@ -375,34 +374,19 @@ pub fn write_line_edge(
             ),
             MHEIGHT,
         ) {
-            write!(
-                writer,
-                "M{x1},{y1}L{x2},{y2}",
-                x1 = offset_start.0 + xoffset,
-                y1 = offset_start.1 + yoffset,
-                x2 = offset_start.0 - xoffset,
-                y2 = offset_start.1 - yoffset,
-            )?;
-
-            write!(
-                writer,
-                "M{x1},{y1}L{x2},{y2}",
-                x1 = offset_end.0 + xoffset,
-                y1 = offset_end.1 + yoffset,
-                x2 = offset_end.0 - xoffset,
-                y2 = offset_end.1 - yoffset,
-            )?;
+            path = path
+                .move_to(offset_start.0 + xoffset, offset_start.1 + yoffset)
+                .line_to(offset_start.0 - xoffset, offset_start.1 - yoffset)
+                .move_to(offset_end.0 + xoffset, offset_end.1 + yoffset)
+                .line_to(offset_end.0 - xoffset, offset_end.1 - yoffset);
         }
     }
 
-    write!(
-        writer,
-        r##"" fill="none" stroke="{color}" stroke-width="1"/>"##,
-        color = edge_options.edge_color,
-    )?;
+    backend.begin_group((0., 0.))?;
+    backend.draw_path(&path, &PathStyle::new().stroke(edge_options.edge_color, 1))?;
 
     write_edge_arrow_heads(
-        writer,
+        backend,
         arrow_type,
         start,
         end,
@@ -410,13 +394,13 @@ pub fn write_line_edge(
         edge_options.edge_arrow_color,
     )?;
 
-    write!(writer, "</g>")?;
+    backend.end_group()?;
 
     Ok((middle_x, middle_y))
 }
 
 pub fn write_line_edge_markers(
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     edge: LineEdge,
     middle: (f64, f64),
     dims: &SvgDimensions,
@@ -433,10 +417,10 @@ pub fn write_line_edge_markers(
         return Ok(());
     }
 
-    let from_x = dims.schema_x() + from.x().width_offset(path_options.cycle_width.into());
+    let from_x = dims.schema_x() + from.x().width_offset(dims.cycle_width());
     let from_y = dims.signal_top(from.y()) + u32::from(path_options.signal_height / 2);
 
-    let to_x = dims.schema_x() + to.x().width_offset(path_options.cycle_width.into());
+    let to_x = dims.schema_x() + to.x().width_offset(dims.cycle_width());
     let to_y = dims.signal_top(to.y()) + u32::from(path_options.signal_height / 2);
 
     let middle_x = middle.0;
@@ -444,9 +428,9 @@ pub fn write_line_edge_markers(
 
     if let Some(c) = edge.from_marker() {
         write_edge_text(
-            writer,
+            backend,
             (f64::from(from_x), f64::from(from_y)),
-            &c.to_string(),
+            &RichText::from(c.to_string()),
             edge_options.node_font_size,
             edge_options.node_text_color,
             edge_options.node_background_color,
@@ -456,9 +440,9 @@ pub fn write_line_edge_markers(
 
     if let Some(c) = edge.to_marker() {
         write_edge_text(
-            writer,
+            backend,
             (f64::from(to_x), f64::from(to_y)),
-            &c.to_string(),
+            &RichText::from(c.to_string()),
             edge_options.node_font_size,
             edge_options.node_text_color,
             edge_options.node_background_color,
@@ -468,7 +452,7 @@ pub fn write_line_edge_markers(
 
     if let Some(text) = edge.label() {
         write_edge_text(
-            writer,
+            backend,
             (middle_x, middle_y),
             text,
             edge_options.edge_text_font_size,
@@ -584,83 +568,66 @@ fn get_text_bbox(text: &str, middle_x: u32, middle_y: u32, font: &Font, font_siz
 }
 
 pub fn write_edge_text(
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     at: (f64, f64),
-    text: &str,
+    text: &RichText,
     font_size: u32,
     text_color: Color,
     background_color: Color,
     font: &Font,
 ) -> io::Result<()> {
-    let width = font.get_text_width(text, font_size);
-    let font_family = font
-        .get_font_family_name()
-        .unwrap_or_else(|| "Helvetica".to_string());
+    let width = font.get_rich_text_width(text, font_size);
 
     let rect_x = at.0 - f64::from(width) / 2.;
     let rect_y = at.1 - f64::from(font_size) / 2.;
 
-    write!(
-        writer,
-        r##"<g><rect x="{rect_x}" y="{rect_y}" width="{width}" height="{font_size}" stroke="none" fill="{background_color}"/><text x="{text_x}" y="{text_y}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{font_size}" letter-spacing="0" fill="{text_color}"><tspan>{text}</tspan></text></g>"##,
-        text_x = at.0,
-        text_y = at.1,
-        text = escape_str(text),
+    backend.begin_group((0., 0.))?;
+    backend.draw_path(
+        &Path::rect(rect_x, rect_y, width, font_size),
+        &PathStyle::new().fill(background_color),
     )?;
-
-    Ok(())
+    backend.draw_text(at, text, &TextLayout::new(font_size, text_color).centered())?;
+    backend.end_group()
 }
 
-fn write_edge_arrow_head_path(
-    writer: &mut impl io::Write,
-    v2d: PlacedVec2D,
-    size: u32,
-) -> io::Result<()> {
+fn edge_arrow_head_path(path: Path, v2d: PlacedVec2D, size: u32) -> Path {
     let Some(end) = offset_in_dir(v2d.origin, v2d.dir, size) else {
-        return Ok(());
+        return path;
     };
 
     let Some(v1) = offset_in_dir(end, (-v2d.dir.1, v2d.dir.0), f64::from(size / 2)) else {
-        return Ok(());
+        return path;
     };
 
     let Some(v2) = offset_in_dir(end, (-v2d.dir.1, v2d.dir.0), -f64::from(size / 2)) else {
-        return Ok(());
+        return path;
     };
 
-    let at_x = SVGF64(v2d.origin.0);
-    let at_y = SVGF64(v2d.origin.1);
-
-    let v1x = SVGF64(v1.0);
-    let v1y = SVGF64(v1.1);
-
-    let v2x = SVGF64(v2.0);
-    let v2y = SVGF64(v2.1);
-
-    write!(writer, "M{at_x},{at_y}L{v1x},{v1y}L{v2x},{v2y}z")
+    path.move_to(v2d.origin.0, v2d.origin.1)
+        .line_to(v1.0, v1.1)
+        .line_to(v2.0, v2.1)
+        .close()
 }
 
 fn write_edge_arrow_heads(
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     arrow_type: EdgeArrowType,
     begin: PlacedVec2D,
     end: PlacedVec2D,
     arrow_size: u32,
     arrow_color: Color,
 ) -> io::Result<()> {
-    write!(writer, r#"<path d=""#)?;
+    let mut path = Path::new();
 
     if matches!(arrow_type, EdgeArrowType::Start | EdgeArrowType::Both) {
-        write_edge_arrow_head_path(writer, begin, arrow_size)?;
+        path = edge_arrow_head_path(path, begin, arrow_size);
     }
 
     if matches!(arrow_type, EdgeArrowType::End | EdgeArrowType::Both) {
-        write_edge_arrow_head_path(writer, end.invert(), arrow_size)?;
+        path = edge_arrow_head_path(path, end.invert(), arrow_size);
     }
 
-    write!(writer, r##"" fill="{arrow_color}" stroke="none"/>"##)?;
-
-    Ok(())
+    backend.draw_path(&path, &PathStyle::new().fill(arrow_color))
 }
 
 fn offset_in_dir(
@@ -0,0 +1,95 @@
+use std::io;
+
+use super::dimensions::SvgDimensions;
+use super::edges::write_edge_text;
+
+use crate::backend::{Backend, Path, PathStyle};
+use crate::signal::markers::{Cursor, Highlight};
+use crate::signal::CycleOffset;
+use crate::{Font, Options};
+
+/// Returns the `x` of a [`CycleOffset`] where offsets past the end are put at the end of the
+/// schema.
+fn schema_offset_x(at: CycleOffset, dims: &SvgDimensions) -> u32 {
+    dims.schema_x() + u32::min(at.width_offset(dims.cycle_width()), dims.schema_width())
+}
+
+pub fn write_highlight(
+    backend: &mut impl Backend,
+    highlight: &Highlight,
+    dims: &SvgDimensions,
+    options: &Options,
+    font: &Font,
+) -> io::Result<()> {
+    let highlight_options = &options.signal.highlight;
+
+    let range = highlight.range();
+    let start_x = schema_offset_x(range.start, dims);
+    let end_x = schema_offset_x(range.end, dims);
+
+    if start_x >= end_x {
+        return Ok(());
+    }
+
+    backend.draw_path(
+        &Path::rect(
+            start_x,
+            dims.schema_y(),
+            end_x - start_x,
+            dims.schema_height(),
+        ),
+        &PathStyle::new()
+            .fill(highlight.get_color().unwrap_or(highlight_options.color))
+            .fill_opacity(f64::from(highlight_options.opacity.min(100)) / 100.),
+    )?;
+
+    if let Some(label) = highlight.get_label() {
+        write_edge_text(
+            backend,
+            (f64::from(start_x + end_x) / 2., f64::from(dims.schema_y())),
+            label,
+            highlight_options.font_size,
+            highlight_options.text_color,
+            highlight_options.text_background_color,
+            font,
+        )?;
+    }
+
+    Ok(())
+}
+
+pub fn write_cursor(
+    backend: &mut impl Backend,
+    cursor: &Cursor,
+    dims: &SvgDimensions,
+    options: &Options,
+    font: &Font,
+) -> io::Result<()> {
+    let highlight_options = &options.signal.highlight;
+
+    let x = schema_offset_x(cursor.at(), dims);
+
+    backend.draw_path(
+        &Path::new()
+            .move_to(x, dims.schema_y())
+            .vertical_by(dims.schema_height()),
+        &PathStyle::new().stroke(
+            highlight_options.cursor_color,
+            highlight_options.cursor_width,
+        ),
+    )?;
+
+    if let Some(label) = cursor.get_label() {
+        write_edge_text(
+            backend,
+            (f64::from(x), f64::from(dims.schema_y())),
+            label,
+            highlight_options.font_size,
+            highlight_options.text_color,
+            highlight_options.text_background_color,
+            font,
+        )?;
+    }
+
+    Ok(())
+}
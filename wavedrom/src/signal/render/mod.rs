@@ -1,23 +1,34 @@
 use std::io;
 
-use super::markers::ClockEdge;
+use super::markers::{ClockEdge, CycleEnumerationMarker, CycleMarkerAlignment};
 use super::path::{PathCommand, PathSegmentBackground};
-use crate::escape::escape_str;
+use crate::backend::{
+    Backend, EpsBackend, Fill, Path, PathStyle, PdfBackend, SvgBackend, TextBaseline, TextLayout,
+    TikzBackend,
+};
+use crate::text::RichText;
 use crate::{Color, Font, Options};
 
 use self::edges::{write_edge_text, write_line_edge, write_line_edge_markers};
+use self::highlight::{write_cursor, write_highlight};
+use self::timing::{write_timing_marker, write_timing_shade};
 
 use super::path::AssembledSignalPath;
 use super::AssembledFigure;
 
 mod dimensions;
 mod edges;
+mod highlight;
+mod text;
+mod timing;
+
+pub use text::TextStyle;
 
 use super::options::{PathOptions, SignalOptions};
 use dimensions::SvgDimensions;
 
 fn gap(
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     wave_height: u16,
     color: Color,
     background: Color,
@@ -39,66 +50,93 @@ fn gap(
 
     let control_3 = (a / 2.0, -b);
 
-    write!(
-        writer,
-        r##"<path d="M{lp1x},{lp1y}C{lp2x},{lp2y} {lp3x},{lp3y} {lp4x},{lp4y}S{lp5x},{lp5y} {lp6x},{lp6y}H{rp1x}C{rp2x},{rp2y} {rp3x},{rp3y} {rp4x},{rp4y}S{rp5x},{rp5y} {rp6x},{rp6y}H{lp1x}z" fill="{background}" stroke="none"/><path d="M{lp1x},{lp1y}C{lp2x},{lp2y} {lp3x},{lp3y} {lp4x},{lp4y}S{lp5x},{lp5y} {lp6x},{lp6y}" fill="none" stroke="{color}" stroke-width="1"/><path d="M{rp1x},{rp1y}C{rp2x},{rp2y} {rp3x},{rp3y} {rp4x},{rp4y}S{rp5x},{rp5y} {rp6x},{rp6y}" fill="none" stroke="{color}" stroke-width="1"/>"##,
-        lp1x = start.0 - DISTANCE / 2.0,
-        lp1y = start.1,
-        lp2x = control_1.0 - DISTANCE / 2.0,
-        lp2y = control_1.1,
-        lp3x = control_2.0 - DISTANCE / 2.0,
-        lp3y = control_2.1,
-        lp4x = 0.0 - DISTANCE / 2.0,
-        lp4y = 0,
-        lp5x = control_3.0 - DISTANCE / 2.0,
-        lp5y = control_3.1,
-        lp6x = end.0 - DISTANCE / 2.0,
-        lp6y = end.1,
-        rp1x = end.0 + DISTANCE / 2.0,
-        rp1y = end.1,
-        rp2x = control_3.0 + DISTANCE / 2.0,
-        rp2y = control_3.1,
-        rp3x = -control_2.0 + DISTANCE / 2.0,
-        rp3y = -control_2.1,
-        rp4x = 0.0 + DISTANCE / 2.0,
-        rp4y = 0,
-        rp5x = control_1.0 + DISTANCE / 2.0,
-        rp5y = control_1.1,
-        rp6x = start.0 + DISTANCE / 2.0,
-        rp6y = start.1,
-    )
+    let point = |(x, y): (f32, f32), dx: f32| (f64::from(x + dx), f64::from(y));
+    let left = |p| point(p, -DISTANCE / 2.0);
+    let right = |p| point(p, DISTANCE / 2.0);
+
+    // The second curve of each side continues smoothly from the first curve
+    let mirrored = (-control_2.0, -control_2.1);
+    let left_curves = |path: Path| {
+        path.curve_to(left(control_1), left(control_2), left((0.0, 0.0)))
+            .curve_to(left(mirrored), left(control_3), left(end))
+    };
+    let right_curves = |path: Path| {
+        path.curve_to(right(control_3), right(mirrored), right((0.0, 0.0)))
+            .curve_to(right(control_2), right(control_1), right(start))
+    };
+
+    let (left_x, left_y) = left(start);
+    let (right_x, right_y) = right(end);
+
+    let fill =
+        right_curves(left_curves(Path::new().move_to(left_x, left_y)).horizontal_to(right_x))
+            .horizontal_to(left_x)
+            .close();
+    let left_side = left_curves(Path::new().move_to(left_x, left_y));
+    let right_side = right_curves(Path::new().move_to(right_x, right_y));
+
+    backend.draw_path(&fill, &PathStyle::new().fill(background))?;
+    backend.draw_path(&left_side, &PathStyle::new().stroke(color, 1))?;
+    backend.draw_path(&right_side, &PathStyle::new().stroke(color, 1))
+}
+
+fn posedge_arrow(backend: &mut impl Backend, wave_height: u32, color: Color) -> io::Result<()> {
+    let scale = wave_height / 6;
+
+    let path = Path::new()
+        .move_to(-f64::from(scale), scale)
+        .line_to(0, -f64::from(scale))
+        .line_to(scale, scale)
+        .horizontal_by(-f64::from(scale * 2))
+        .close();
+
+    backend.draw_path(&path, &PathStyle::new().fill(color))
 }
 
-fn posedge_arrow(writer: &mut impl io::Write, wave_height: u32, color: Color) -> io::Result<()> {
-    let scale = i64::from(wave_height / 6);
-
-    write!(
-        writer,
-        r##"<path d="M{x1},{y1}L{x2},{y2}L{x3},{y3}H{hback}z" fill="{color}" stroke="none"/>"##,
-        x1 = -scale,
-        y1 = scale,
-        x2 = 0,
-        y2 = -scale,
-        x3 = scale,
-        y3 = scale,
-        hback = -scale * 2,
-    )
+fn negedge_arrow(backend: &mut impl Backend, wave_height: u32, color: Color) -> io::Result<()> {
+    let scale = wave_height / 6;
+
+    let path = Path::new()
+        .move_to(-f64::from(scale), -f64::from(scale))
+        .line_to(0, scale)
+        .line_to(scale, -f64::from(scale))
+        .horizontal_by(-f64::from(scale * 2))
+        .close();
+
+    backend.draw_path(&path, &PathStyle::new().fill(color))
 }
 
-fn negedge_arrow(writer: &mut impl io::Write, wave_height: u32, color: Color) -> io::Result<()> {
-    let scale = i64::from(wave_height / 6);
-
-    write!(
-        writer,
-        r##"<path d="M{x1},{y1}L{x2},{y2}L{x3},{y3}H{hback}z" fill="{color}" stroke="none"/>"##,
-        x1 = -scale,
-        y1 = -scale,
-        x2 = 0,
-        y2 = scale,
-        x3 = scale,
-        y3 = -scale,
-        hback = -scale * 2,
-    )
+fn write_cycle_markers(
+    backend: &mut impl Backend,
+    cycle_marker: &CycleEnumerationMarker,
+    num_cycles: u32,
+    dims: &SvgDimensions,
+    y: u32,
+    font_size: u32,
+    color: Color,
+) -> io::Result<()> {
+    let labels = cycle_marker.visible_labels(num_cycles);
+
+    if labels.is_empty() {
+        return Ok(());
+    }
+
+    let alignment_offset = match cycle_marker.get_alignment() {
+        CycleMarkerAlignment::Center => dims.cycle_width() / 2,
+        CycleMarkerAlignment::Edge => 0,
+    };
+
+    backend.begin_group((0., 0.))?;
+    for (i, label) in labels {
+        let x = dims.schema_x() + dims.cycle_width() * i + alignment_offset;
+
+        backend.draw_text(
+            (f64::from(x), f64::from(y)),
+            &RichText::from(label),
+            &TextLayout::new(font_size, color).centered(),
+        )?;
+    }
+    backend.end_group()
 }
 
 impl<'a> AssembledFigure<'a> {
@@ -113,6 +151,71 @@ impl<'a> AssembledFigure<'a> {
         &self,
         writer: &mut impl io::Write,
         options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(
+            &mut SvgBackend::new(writer).id_prefix(&options.id_prefix),
+            options,
+        )
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as a PDF document.
+    #[inline]
+    pub fn write_pdf(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_pdf_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as a PDF document with a set of options.
+    pub fn write_pdf_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut PdfBackend::new(writer), options)
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as an Encapsulated PostScript file.
+    #[inline]
+    pub fn write_eps(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_eps_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as an Encapsulated PostScript file with a set
+    /// of options.
+    pub fn write_eps_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut EpsBackend::new(writer), options)
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as a TikZ picture for LaTeX documents.
+    #[inline]
+    pub fn write_tikz(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        self.write_tikz_with_options(writer, &Options::default())
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as a TikZ picture for LaTeX documents with a
+    /// set of options.
+    pub fn write_tikz_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+    ) -> io::Result<()> {
+        self.render_with_options(&mut TikzBackend::new(writer), options)
+    }
+
+    /// Render a [`AssembledFigure`] with a [`Backend`].
+    #[inline]
+    pub fn render(&self, backend: &mut impl Backend) -> io::Result<()> {
+        self.render_with_options(backend, &Options::default())
+    }
+
+    /// Render a [`AssembledFigure`] with a [`Backend`] and a set of options.
+    pub fn render_with_options(
+        &self,
+        backend: &mut impl Backend,
+        options: &Options,
     ) -> io::Result<()> {
         let Options {
             background,
@@ -139,137 +242,69 @@ impl<'a> AssembledFigure<'a> {
         let cycle_width = u32::from(cycle_width);
 
         let font = Font::default();
-        let font_family = font
-            .get_font_family_name()
-            .unwrap_or_else(|| "helvetica".to_string());
 
         let dims = SvgDimensions::new(self, font, options);
 
-        write!(
-            writer,
-            r#"<svg version="1.1" xmlns="http://www.w3.org/2000/svg" xmlns:xlink="http://www.w3.org/1999/xlink" viewport="0 0 {figure_width} {figure_height}" overflow="hidden" width="{figure_width}" height="{figure_height}">"#,
-            figure_width = dims.figure_width(),
-            figure_height = dims.figure_height(),
-        )?;
-
-        // Definitions
-        write!(writer, "<defs>")?;
-        if self.definitions.has_undefined {
-            write!(
-                writer,
-                r##"<pattern id="x-bg" patternUnits="userSpaceOnUse" width="4" height="10" patternTransform="rotate(45)">"##,
-            )?;
-
-            if let Some(background) = options.undefined_background {
-                write!(
-                    writer,
-                    r##"<rect x="0" y="0" width="4" height="10" fill="{background}"/>"##
-                )?;
-            }
-
-            write!(
-                writer,
-                r##"<line x1="0" y="0" x2="0" y2="10" stroke="{color}" stroke-width="1"/></pattern>"##,
-                color = signal.undefined_color
-            )?;
-        }
-
-        if self.definitions.has_posedge_marker {
-            write!(writer, r##"<g id="pei">"##)?;
-            posedge_arrow(writer, signal_height, signal.path_color)?;
-            write!(writer, r##"</g>"##)?;
-        }
-
-        if self.definitions.has_negedge_marker {
-            write!(writer, r##"<g id="nei">"##)?;
-            negedge_arrow(writer, signal_height, signal.path_color)?;
-            write!(writer, r##"</g>"##)?;
-        }
-
-        if self.definitions.has_gaps {
-            write!(writer, r##"<g id="gap">"##)?;
-            gap(
-                writer,
-                self.path_assemble_options.signal_height,
-                signal.gap_color,
-                signal.gap_background_color,
-            )?;
-            write!(writer, r##"</g>"##)?;
-        }
-
-        write!(
-            writer,
-            r##"<g id="cl"><path fill="none" d="M0,0v{schema_height}" stroke-width="1" stroke-dasharray="2" stroke="{color}"/></g>"##,
-            color = signal.hint_line_color,
-            schema_height = dims.schema_height(),
+        backend.begin(
+            f64::from(dims.figure_width()),
+            f64::from(dims.figure_height()),
+            *background,
         )?;
-        write!(writer, "</defs>")?;
-
-        // Background
-        if let Some(background) = background {
-            write!(
-                writer,
-                r##"<rect width="100%" height="100%" fill="{background}"/>"##
-            )?;
-        }
 
         // Header Text
         if let Some(title) = self.header_text {
-            let title_font_size = header.font_size;
-            let title_color = header.color;
-
-            write!(
-                writer,
-                r##"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{title_font_size}" fill="{title_color}" letter-spacing="0"><tspan>{text}</tspan></text>"##,
-                x = dims.header_x() + dims.header_width() / 2,
-                y = dims.header_y() + dims.header_height() / 2,
-                text = escape_str(title),
+            backend.draw_text(
+                (
+                    f64::from(dims.header_x() + dims.header_width() / 2),
+                    f64::from(dims.header_y() + dims.header_height() / 2),
+                ),
+                title,
+                &TextLayout::new(header.font_size, header.color).centered(),
             )?;
         }
 
         // Top Cycle Enumeration Markers
         if let Some(cycle_marker) = self.top_cycle_marker {
-            let start = cycle_marker.start();
-            let every = cycle_marker.every();
-
-            let marker_font_size = header.cycle_marker_fontsize;
-            let marker_color = header.cycle_marker_color;
-            let end = start + self.num_cycles;
-
-            if every != 0 {
-                write!(writer, "<g>")?;
-                for offset in (start..end).step_by(every as usize) {
-                    write!(
-                        writer,
-                        r##"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{marker_font_size}" fill="{marker_color}" letter-spacing="0"><tspan>{offset}</tspan></text>"##,
-                        x = dims.schema_x()
-                            + dims.cycle_width() * (offset - start)
-                            + dims.cycle_width() / 2,
-                        y = dims.header_y() + dims.header_height(),
-                    )?;
-                }
-                write!(writer, "</g>")?;
-            }
+            write_cycle_markers(
+                backend,
+                cycle_marker,
+                self.num_cycles,
+                &dims,
+                dims.header_y() + dims.header_height(),
+                header.cycle_marker_fontsize,
+                header.cycle_marker_color,
+            )?;
         }
 
         // Cycle Hint Lines
-        write!(writer, "<g>")?;
+        backend.begin_group((0., 0.))?;
         for i in 0..=self.num_cycles {
-            write!(
-                writer,
-                r##"<use transform="translate({x},{y})" xlink:href="#cl"/>"##,
-                x = dims.schema_x() + i * dims.cycle_width(),
-                y = dims.schema_y(),
+            let hint_line = Path::new()
+                .move_to(dims.schema_x() + i * dims.cycle_width(), dims.schema_y())
+                .vertical_by(dims.schema_height());
+
+            backend.draw_path(
+                &hint_line,
+                &PathStyle::new().stroke(signal.hint_line_color, 1).dash(2),
             )?;
         }
-        write!(writer, "</g>")?;
+        backend.end_group()?;
+
+        // Highlights
+        if !self.highlights.is_empty() {
+            backend.begin_group((0., 0.))?;
+            for highlight in self.highlights {
+                write_highlight(backend, highlight, &dims, options, &font)?;
+            }
+            backend.end_group()?;
+        }
 
         // Group Indicators
         if !self.group_markers.is_empty() {
             let label_font_size = group_indicator.label_fontsize;
             let label_color = group_indicator.label_color;
 
-            write!(writer, "<g>")?;
+            backend.begin_group((0., 0.))?;
             for group in self.group_markers.iter() {
                 if group.is_empty() {
                     continue;
@@ -302,27 +337,43 @@ impl<'a> AssembledFigure<'a> {
                 if let Some(label) = group.label() {
                     let x = x - group_indicator.label_fontsize / 2;
 
-                    write!(
-                        writer,
-                        r##"<g transform="translate({x},{y})"><text text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{label_font_size}" fill="{label_color}" letter-spacing="0" transform="rotate(270)"><tspan>{text}</tspan></text></g>"##,
-                        y = y + height / 2,
-                        text = escape_str(label),
+                    backend.draw_text(
+                        (f64::from(x), f64::from(y + height / 2)),
+                        label,
+                        &TextLayout::new(label_font_size, label_color)
+                            .centered()
+                            .rotation(270),
                     )?;
                 }
 
-                write!(
-                    writer,
-                    r##"<path fill="none" d="M{x},{y}m{w},0c-3,0 -{w},1 -{w},{w}v{h}c0,3 1,{w} {w},{w}" stroke="{color}"/>"##,
-                    color = group_indicator.color,
-                    h = height - group_indicator.width * 2,
-                    w = group_indicator.width,
+                let w = f64::from(group_indicator.width);
+                let indicator = Path::new()
+                    .move_to(x, y)
+                    .move_by(w, 0)
+                    .curve_by((-3., 0.), (-w, 1.), (-w, w))
+                    .vertical_by(height - group_indicator.width * 2)
+                    .curve_by((0., 3.), (1., w), (w, w));
+
+                backend.draw_path(
+                    &indicator,
+                    &PathStyle::new().stroke(group_indicator.color, 1),
                 )?;
             }
-            write!(writer, "</g>")?;
+            backend.end_group()?;
+        }
+
+        // Shaded Timing Windows
+        let timing_markers = self.line_edge_markers.timing_markers();
+        if timing_markers.iter().any(|marker| marker.is_shaded()) {
+            backend.begin_group((0., 0.))?;
+            for marker in timing_markers.iter().filter(|marker| marker.is_shaded()) {
+                write_timing_shade(backend, marker, &dims, options)?;
+            }
+            backend.end_group()?;
         }
 
         // Signal Lines
-        write!(writer, "<g>")?;
+        backend.begin_group((0., 0.))?;
         for (i, line) in self.lines.iter().enumerate() {
             if line.is_empty() {}
 
@@ -337,84 +388,71 @@ impl<'a> AssembledFigure<'a> {
             };
             let y = dims.signal_top(i);
 
-            write!(writer, r##"<g transform="translate({x},{y})">"##)?;
+            backend.begin_group((f64::from(x), f64::from(y)))?;
 
             if !line.text.is_empty() {
-                let name_font_size = signal.name_font_size;
-                let name_color = signal.name_color;
-
-                write!(
-                    writer,
-                    r##"<g transform="translate(0,{y})"><text dominant-baseline="middle" font-family="{font_family}" font-size="{name_font_size}" fill="{name_color}" letter-spacing="0"><tspan>{text}</tspan></text></g>"##,
-                    y = signal_height / 2,
-                    text = escape_str(line.text),
+                backend.draw_text(
+                    (0., f64::from(signal_height / 2)),
+                    line.text,
+                    &TextLayout::new(signal.name_font_size, signal.name_color)
+                        .baseline(TextBaseline::Middle),
                 )?;
             }
 
             if dims.has_textbox() {
-                write!(
-                    writer,
-                    r##"<g transform="translate({schema_x})">"##,
-                    schema_x = dims.schema_x() - dims.textbox_x()
-                )?;
-                write_signal(&line.path, writer, options, self.hscale)?;
-                write!(writer, r##"</g>"##)?;
+                backend.begin_group((f64::from(dims.schema_x() - dims.textbox_x()), 0.))?;
+                write_signal(&line.path, backend, options, self.hscale)?;
+                backend.end_group()?;
             } else {
-                write_signal(&line.path, writer, options, self.hscale)?;
+                write_signal(&line.path, backend, options, self.hscale)?;
             }
 
-            write!(writer, r##"</g>"##)?;
+            backend.end_group()?;
+        }
+        backend.end_group()?;
+
+        // Cursors
+        if !self.cursors.is_empty() {
+            backend.begin_group((0., 0.))?;
+            for cursor in self.cursors {
+                write_cursor(backend, cursor, &dims, options, &font)?;
+            }
+            backend.end_group()?;
         }
-        write!(writer, "</g>")?;
 
         // Footer Text
         if let Some(footer_text) = self.footer_text {
-            let footer_font_size = footer.font_size;
-            let footer_color = footer.color;
-
-            write!(
-                writer,
-                r##"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{footer_font_size}" fill="{footer_color}" letter-spacing="0"><tspan>{text}</tspan></text>"##,
-                x = dims.footer_width() / 2,
-                y = dims.footer_y() + dims.footer_height() / 2,
-                text = escape_str(footer_text),
+            backend.draw_text(
+                (
+                    f64::from(dims.footer_width() / 2),
+                    f64::from(dims.footer_y() + dims.footer_height() / 2),
+                ),
+                footer_text,
+                &TextLayout::new(footer.font_size, footer.color).centered(),
             )?;
         }
 
         // Bottom Cycle Enumeration Markers
         if let Some(cycle_marker) = self.bottom_cycle_marker {
-            let start = cycle_marker.start();
-            let every = cycle_marker.every();
-
-            let marker_font_size = footer.cycle_marker_fontsize;
-            let marker_color = footer.cycle_marker_color;
-
-            let end = start + self.num_cycles;
-
-            if every != 0 {
-                write!(writer, "<g>")?;
-                for offset in (start..end).step_by(every as usize) {
-                    write!(
-                        writer,
-                        r##"<text x="{x}" y="{y}" text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{marker_font_size}" fill="{marker_color}" letter-spacing="0"><tspan>{offset}</tspan></text>"##,
-                        x = dims.schema_x()
-                            + dims.cycle_width() * (offset - start)
-                            + dims.cycle_width() / 2,
-                        y = dims.footer_y()
-                    )?;
-                }
-                write!(writer, "</g>")?;
-            }
+            write_cycle_markers(
+                backend,
+                cycle_marker,
+                self.num_cycles,
+                &dims,
+                dims.footer_y(),
+                footer.cycle_marker_fontsize,
+                footer.cycle_marker_color,
+            )?;
         }
 
         // Edge markers
         if !self.line_edge_markers.lines().is_empty() {
             let mut middles = Vec::with_capacity(self.line_edge_markers.lines().len());
 
-            write!(writer, "<g>")?;
+            backend.begin_group((0., 0.))?;
             for line_edge in self.line_edge_markers.lines() {
                 middles.push(write_line_edge(
-                    writer,
+                    backend,
                     line_edge.clone(),
                     &dims,
                     options,
@@ -428,28 +466,37 @@ impl<'a> AssembledFigure<'a> {
                 .iter()
                 .zip(middles.into_iter())
             {
-                write_line_edge_markers(
-                    writer,
-                    line_edge.clone(),
-                    middle,
+                write_line_edge_markers(backend, line_edge.clone(), middle, &dims, options, &font)?;
+            }
+            backend.end_group()?;
+        }
+
+        // Timing Dimension Lines
+        if !timing_markers.is_empty() {
+            backend.begin_group((0., 0.))?;
+            for (i, marker) in timing_markers.iter().enumerate() {
+                write_timing_marker(
+                    backend,
+                    marker,
+                    dims.timing_line_y(i),
                     &dims,
                     options,
                     &font,
                 )?;
             }
-            write!(writer, "</g>")?;
+            backend.end_group()?;
         }
 
         // Edge separate text markers
         if !self.line_edge_markers.text_nodes().is_empty() {
-            write!(writer, "<g>")?;
+            backend.begin_group((0., 0.))?;
             for text_node in self.line_edge_markers.text_nodes() {
-                let text = text_node.text().to_string();
+                let text = RichText::from(text_node.text().to_string());
                 let x = dims.schema_x() + text_node.at().x().width_offset(cycle_width);
                 let y = dims.signal_top(text_node.at().y()) + signal_height / 2;
 
                 write_edge_text(
-                    writer,
+                    backend,
                     (x.into(), y.into()),
                     &text,
                     edge.node_font_size,
@@ -458,16 +505,38 @@ impl<'a> AssembledFigure<'a> {
                     &font,
                 )?;
             }
-            write!(writer, "</g>")?;
+            backend.end_group()?;
         }
 
-        write!(writer, "</svg>")?;
+        backend.finish()
+    }
 
-        Ok(())
+    /// Render a [`AssembledFigure`] into a `writer` as a PNG image.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    #[inline]
+    pub fn write_png(&self, writer: &mut impl io::Write, scale: f32) -> io::Result<()> {
+        self.write_png_with_options(writer, &Options::default(), scale)
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as a PNG image with a set of options.
+    ///
+    /// The `scale` is the number of pixels per SVG unit.
+    #[cfg(feature = "png")]
+    pub fn write_png_with_options(
+        &self,
+        writer: &mut impl io::Write,
+        options: &Options,
+        scale: f32,
+    ) -> io::Result<()> {
+        let mut svg = Vec::new();
+        self.write_svg_with_options(&mut svg, options)?;
+        crate::png::write_png(&svg, writer, scale)
     }
 }
 
-fn draw_dashed_horizontal_line(writer: &mut impl io::Write, dx: i32) -> io::Result<()> {
+fn draw_dashed_horizontal_line(mut path: Path, dx: i32) -> Path {
     let mut cx = 0i32;
 
     loop {
@@ -475,23 +544,47 @@ fn draw_dashed_horizontal_line(writer: &mut impl io::Write, dx: i32) -> io::Resu
             break;
         }
 
-        write!(writer, "h{signed_len}", signed_len = dx.signum() * 4)?;
+        path = path.horizontal_by(dx.signum() * 4);
         cx = i32::min(dx.abs(), cx + 4);
 
         if cx.abs() >= dx.abs() {
             break;
         }
 
-        write!(writer, "m{signed_len},0", signed_len = dx.signum() * 4)?;
+        path = path.move_by(dx.signum() * 4, 0);
         cx = i32::min(dx.abs(), cx + 4);
     }
 
-    Ok(())
+    path
+}
+
+/// Build the path of the commands of a segment. Vertical lines without a stroke are moved over
+/// when `skip_no_stroke` is set.
+fn segment_path(x: i32, y: i32, actions: &[PathCommand], skip_no_stroke: bool) -> Path {
+    let mut path = Path::new().move_to(x, y);
+
+    for action in actions {
+        path = match *action {
+            PathCommand::LineVerticalNoStroke(dy) if skip_no_stroke => path.move_by(0, dy),
+            PathCommand::LineVerticalNoStroke(dy) => path.vertical_by(dy),
+            PathCommand::LineVertical(dy) => path.vertical_by(dy),
+            PathCommand::LineHorizontal(dx) => path.horizontal_by(dx),
+            PathCommand::DashedLineHorizontal(dx) => draw_dashed_horizontal_line(path, dx),
+            PathCommand::Line(dx, dy) => path.line_by(dx, dy),
+            PathCommand::Curve(cdx1, cdy1, cdx2, cdy2, dx, dy) => path.curve_by(
+                (cdx1.into(), cdy1.into()),
+                (cdx2.into(), cdy2.into()),
+                (dx.into(), dy.into()),
+            ),
+        };
+    }
+
+    path
 }
 
 fn write_signal(
     wave_path: &AssembledSignalPath,
-    writer: &mut impl io::Write,
+    backend: &mut impl Backend,
     options: &Options,
     hscale: u16,
 ) -> io::Result<()> {
@@ -504,84 +597,57 @@ fn write_signal(
     let signal_height = u32::from(*signal_height);
     let cycle_width = u32::from(*cycle_width);
 
+    let signal_options = &options.signal;
+
     for segment in wave_path.segments() {
         let x = segment.x();
         let y = segment.y();
 
-        write!(writer, r##"<path fill=""##)?;
-        match segment.background() {
-            Some(PathSegmentBackground::B2) => write!(writer, "{}", options.backgrounds[0])?,
-            Some(PathSegmentBackground::B3) => write!(writer, "{}", options.backgrounds[1])?,
-            Some(PathSegmentBackground::B4) => write!(writer, "{}", options.backgrounds[2])?,
-            Some(PathSegmentBackground::B5) => write!(writer, "{}", options.backgrounds[3])?,
-            Some(PathSegmentBackground::B6) => write!(writer, "{}", options.backgrounds[4])?,
-            Some(PathSegmentBackground::B7) => write!(writer, "{}", options.backgrounds[5])?,
-            Some(PathSegmentBackground::B8) => write!(writer, "{}", options.backgrounds[6])?,
-            Some(PathSegmentBackground::B9) => write!(writer, "{}", options.backgrounds[7])?,
-            Some(PathSegmentBackground::Undefined) => write!(writer, "url(#x-bg)")?,
-            None => write!(writer, "none")?,
-        }
-        write!(writer, r#"" d=""#)?;
-        write!(writer, "M{x},{y}")?;
-        for action in segment.actions() {
-            match action {
-                PathCommand::LineVerticalNoStroke(dy) => write!(writer, "v{dy}"),
-                PathCommand::LineVertical(dy) => write!(writer, "v{dy}"),
-                PathCommand::LineHorizontal(dx) => write!(writer, "h{dx}"),
-                PathCommand::DashedLineHorizontal(dx) => draw_dashed_horizontal_line(writer, *dx),
-                PathCommand::Line(dx, dy) => write!(writer, "l{dx},{dy}"),
-                PathCommand::Curve(cdx1, cdy1, cdx2, cdy2, dx, dy) => {
-                    write!(writer, "c{cdx1},{cdy1} {cdx2},{cdy2} {dx},{dy}")
-                }
-            }?
+        let fill = match segment.background() {
+            Some(PathSegmentBackground::B2) => Some(Fill::from(options.backgrounds[0])),
+            Some(PathSegmentBackground::B3) => Some(Fill::from(options.backgrounds[1])),
+            Some(PathSegmentBackground::B4) => Some(Fill::from(options.backgrounds[2])),
+            Some(PathSegmentBackground::B5) => Some(Fill::from(options.backgrounds[3])),
+            Some(PathSegmentBackground::B6) => Some(Fill::from(options.backgrounds[4])),
+            Some(PathSegmentBackground::B7) => Some(Fill::from(options.backgrounds[5])),
+            Some(PathSegmentBackground::B8) => Some(Fill::from(options.backgrounds[6])),
+            Some(PathSegmentBackground::B9) => Some(Fill::from(options.backgrounds[7])),
+            Some(PathSegmentBackground::Undefined) => Some(Fill::Hatched {
+                color: signal_options.undefined_color,
+                background: options.undefined_background,
+            }),
+            None => None,
+        };
+
+        let mut path = segment_path(x, y, segment.actions(), false);
+        if fill.is_some() {
+            path = path.close();
         }
 
-        if segment.background().is_some() {
-            write!(writer, r##"z"##)?;
-        }
+        let mut style = PathStyle::new();
+        style.fill = fill;
 
         // If there is a `no_stroke` element, we need to divide up the filling and the
         // stroking.
-        if !segment.is_fully_stroked() {
-            write!(writer, r##"" stroke="none"/>"##)?;
-
-            write!(writer, r##"<path fill="none" d=""##)?;
-            write!(writer, "M{x},{y}")?;
-            for action in segment.actions() {
-                match action {
-                    PathCommand::LineVerticalNoStroke(dy) => write!(writer, "m0,{dy}"),
-                    PathCommand::LineVertical(dy) => write!(writer, "v{dy}"),
-                    PathCommand::LineHorizontal(dx) => write!(writer, "h{dx}"),
-                    PathCommand::DashedLineHorizontal(dx) => {
-                        draw_dashed_horizontal_line(writer, *dx)
-                    }
-                    PathCommand::Line(dx, dy) => write!(writer, "l{dx},{dy}"),
-                    PathCommand::Curve(cdx1, cdy1, cdx2, cdy2, dx, dy) => {
-                        write!(writer, "c{cdx1},{cdy1} {cdx2},{cdy2} {dx},{dy}")
-                    }
-                }?
-            }
+        if segment.is_fully_stroked() {
+            backend.draw_path(&path, &style.stroke(signal_options.path_color, 1))?;
+        } else {
+            backend.draw_path(&path, &style)?;
+            backend.draw_path(
+                &segment_path(x, y, segment.actions(), true),
+                &PathStyle::new().stroke(signal_options.path_color, 1),
+            )?;
         }
-        write!(
-            writer,
-            r##"" stroke-width="1" stroke="{path_color}"/>"##,
-            path_color = options.signal.path_color
-        )?;
 
         if let Some(marker_text) = segment.marker_text() {
-            write!(
-                writer,
-                r##"<g transform="translate({x},{y})"><text text-anchor="middle" dominant-baseline="middle" font-family="{font_family}" font-size="{font_size}" fill="{color}" letter-spacing="0"><tspan>{text}</tspan></text></g>"##,
-                font_family = Font::default()
-                    .get_font_family_name()
-                    .as_ref()
-                    .map(|s| &s[..])
-                    .unwrap_or("Helvetica"),
-                font_size = options.signal.marker_font_size,
-                text = marker_text,
-                color = options.signal.marker_color,
-                x = segment.x() + segment.width() / 2,
-                y = signal_height / 2,
+            backend.draw_text(
+                (
+                    f64::from(segment.x() + segment.width() / 2),
+                    f64::from(signal_height / 2),
+                ),
+                marker_text,
+                &TextLayout::new(signal_options.marker_font_size, signal_options.marker_color)
+                    .centered(),
             )?;
         }
 
@@ -591,30 +657,30 @@ fn write_signal(
                 .width_offset(cycle_width * u32::from(hscale));
             let y = signal_height / 2;
 
+            backend.begin_group((f64::from(x), f64::from(y)))?;
             match clock_edge_marker.edge() {
                 ClockEdge::Positive => {
-                    write!(
-                        writer,
-                        r##"<use transform="translate({x},{y})" xlink:href="#pei"/>"##,
-                    )?;
+                    posedge_arrow(backend, signal_height, signal_options.path_color)?
                 }
                 ClockEdge::Negative => {
-                    write!(
-                        writer,
-                        r##"<use transform="translate({x},{y})" xlink:href="#nei"/>"##,
-                    )?;
+                    negedge_arrow(backend, signal_height, signal_options.path_color)?
                 }
             };
+            backend.end_group()?;
         }
 
-        for gap in segment.gaps() {
-            let x = gap.width_offset(cycle_width * u32::from(hscale));
+        for gap_offset in segment.gaps() {
+            let x = gap_offset.width_offset(cycle_width * u32::from(hscale));
             let y = signal_height / 2;
 
-            write!(
-                writer,
-                r##"<use transform="translate({x},{y})" xlink:href="#gap"/>"##,
+            backend.begin_group((f64::from(x), f64::from(y)))?;
+            gap(
+                backend,
+                wave_path.options().signal_height,
+                signal_options.gap_color,
+                signal_options.gap_background_color,
             )?;
+            backend.end_group()?;
         }
     }
 
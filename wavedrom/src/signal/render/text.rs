@@ -0,0 +1,397 @@
+use std::io;
+
+use crate::signal::markers::{CycleEnumerationMarker, CycleMarkerAlignment};
+use crate::signal::{AssembledFigure, AssembledLine, CycleState, SignalPath};
+
+/// The number of characters of a cycle with a horizontal scale of `1`
+const CYCLE_CHARS: u32 = 6;
+/// The smallest number of characters of a cycle, which still fits a clock period
+const MIN_CYCLE_CHARS: u32 = 2;
+
+/// The characters that are used to write a [`AssembledFigure`] as text
+///
+/// This is unrelated to the [`TextStyle`][crate::text::TextStyle] of rich text.
+///
+/// | State       | [`TextStyle::Ascii`] | [`TextStyle::Unicode`] |
+/// | ----------- | -------------------- | ---------------------- |
+/// | High        | `---`                | `‾‾‾`                  |
+/// | Low         | `___`                | `___`                  |
+/// | Z           | `~~~`                | `───`                  |
+/// | Rising      | `/`                  | `╱`                    |
+/// | Falling     | `\`                  | `╲`                    |
+/// | Data        | `X=data=`            | `╳═data═`              |
+/// | Undefined   | `xxx`                | `░░░`                  |
+/// | Gap         | `\|`                 | `╎`                    |
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    /// Only use ASCII characters
+    #[default]
+    Ascii,
+    /// Use box-drawing characters
+    Unicode,
+}
+
+/// The drawn level of a signal in a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Level {
+    Low,
+    Middle,
+    High,
+    /// The data box with an index
+    Data(usize),
+    Undefined,
+}
+
+impl Level {
+    fn height(self) -> Option<u8> {
+        match self {
+            Level::Low => Some(0),
+            Level::Middle => Some(1),
+            Level::High => Some(2),
+            Level::Data(_) | Level::Undefined => None,
+        }
+    }
+}
+
+impl TextStyle {
+    fn fill(self, level: Level) -> char {
+        match (self, level) {
+            (_, Level::Low) => '_',
+            (Self::Ascii, Level::Middle) => '~',
+            (Self::Unicode, Level::Middle) => '─',
+            (Self::Ascii, Level::High) => '-',
+            (Self::Unicode, Level::High) => '‾',
+            (Self::Ascii, Level::Data(_)) => '=',
+            (Self::Unicode, Level::Data(_)) => '═',
+            (Self::Ascii, Level::Undefined) => 'x',
+            (Self::Unicode, Level::Undefined) => '░',
+        }
+    }
+
+    fn transition(self, from: Level, to: Level) -> char {
+        match (self, from.height(), to.height()) {
+            (Self::Ascii, Some(from), Some(to)) if from < to => '/',
+            (Self::Unicode, Some(from), Some(to)) if from < to => '╱',
+            (Self::Ascii, Some(_), Some(_)) => '\\',
+            (Self::Unicode, Some(_), Some(_)) => '╲',
+            (Self::Ascii, _, _) => 'X',
+            (Self::Unicode, _, _) => '╳',
+        }
+    }
+
+    fn gap(self) -> char {
+        match self {
+            Self::Ascii => '|',
+            Self::Unicode => '╎',
+        }
+    }
+
+    /// Cut a `label` to at most `width` characters.
+    fn truncate(self, label: &str, width: usize) -> String {
+        if label.chars().count() <= width {
+            return label.to_string();
+        }
+
+        match self {
+            Self::Ascii => label.chars().take(width).collect(),
+            Self::Unicode => {
+                let mut truncated: String = label.chars().take(width.saturating_sub(1)).collect();
+                truncated.push('…');
+                truncated
+            }
+        }
+    }
+}
+
+impl<'a> AssembledFigure<'a> {
+    /// Render a [`AssembledFigure`] into a `writer` as plain text.
+    ///
+    /// Every signal is written on one line with its name in front of it. This also writes the
+    /// header, footer, group labels and cycle enumeration markers. Edges, timing annotations,
+    /// highlights and cursors are left out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wavedrom::signal::{Signal, SignalFigure, TextStyle};
+    ///
+    /// let figure = SignalFigure::new().add_signals([
+    ///     Signal::with_cycle_str("p...").name("clk"),
+    ///     Signal::with_cycle_str("0.1.").name("req"),
+    /// ]);
+    ///
+    /// let mut text = Vec::new();
+    /// figure.assemble().write_text(&mut text, TextStyle::Ascii)?;
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(text).unwrap(),
+    ///     concat!(
+    ///         "clk ---\\__/--\\__/--\\__/--\\__\n",
+    ///         "req ____________/-----------\n",
+    ///     ),
+    /// );
+    /// # <Result<(), std::io::Error>>::Ok(())
+    /// ```
+    #[inline]
+    pub fn write_text(&self, writer: &mut impl io::Write, style: TextStyle) -> io::Result<()> {
+        self.write_text_with_width(writer, style, u32::MAX)
+    }
+
+    /// Render a [`AssembledFigure`] into a `writer` as plain text that is at most `width`
+    /// characters wide.
+    ///
+    /// The cycles are made narrower to fit the `width`. Lines that are still too wide are cut off.
+    pub fn write_text_with_width(
+        &self,
+        writer: &mut impl io::Write,
+        style: TextStyle,
+        width: u32,
+    ) -> io::Result<()> {
+        let width = usize::try_from(width).unwrap_or(usize::MAX);
+
+        // The nesting depth of the groups around every line
+        let mut depths = vec![0; self.lines.len()];
+        for group in &self.group_markers {
+            for depth in &mut depths[group.start() as usize..group.end() as usize] {
+                *depth += 1;
+            }
+        }
+
+        let names = self
+            .lines
+            .iter()
+            .zip(&depths)
+            .map(|(line, depth)| format!("{}{}", "  ".repeat(*depth), line.text.to_plain_string()))
+            .collect::<Vec<_>>();
+        let name_width = names
+            .iter()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or_default();
+        let name_width = if name_width == 0 { 0 } else { name_width + 1 };
+
+        let num_cycles = usize::try_from(self.num_cycles)
+            .unwrap_or(usize::MAX)
+            .max(1);
+        let max_cycle_chars = width.saturating_sub(name_width) / num_cycles;
+        let cycle_chars = (CYCLE_CHARS * u32::from(self.hscale))
+            .min(u32::try_from(max_cycle_chars).unwrap_or(u32::MAX))
+            .max(MIN_CYCLE_CHARS);
+
+        let figure_width = name_width + num_cycles * cycle_chars as usize;
+        let mut rows = Vec::new();
+
+        if let Some(header) = self.header_text {
+            rows.push(format!("{:^figure_width$}", header.to_plain_string()));
+        }
+        if let Some(marker) = self.top_cycle_marker {
+            rows.push(self.cycle_marker_row(marker, name_width, cycle_chars));
+        }
+
+        let mut group_markers = self.group_markers.iter().collect::<Vec<_>>();
+        group_markers.sort_by_key(|group| (group.start(), group.depth()));
+        let mut group_markers = group_markers.into_iter().peekable();
+
+        for (i, line) in self.lines.iter().enumerate() {
+            while let Some(group) = group_markers.next_if(|group| group.start() as usize <= i) {
+                if let Some(label) = group.label() {
+                    let indent = "  ".repeat(group.depth().saturating_sub(1) as usize);
+                    rows.push(format!("{indent}{}", label.to_plain_string()));
+                }
+            }
+
+            let wave = line_wave(line, style, self.num_cycles, cycle_chars);
+            rows.push(format!("{:name_width$}{wave}", names[i]));
+        }
+
+        if let Some(marker) = self.bottom_cycle_marker {
+            rows.push(self.cycle_marker_row(marker, name_width, cycle_chars));
+        }
+        if let Some(footer) = self.footer_text {
+            rows.push(format!("{:^figure_width$}", footer.to_plain_string()));
+        }
+
+        for row in rows {
+            let row = row.chars().take(width).collect::<String>();
+            writeln!(writer, "{}", row.trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the row with the labels of a [`CycleEnumerationMarker`]. Labels that would
+    /// overlap with the previous label are left out.
+    fn cycle_marker_row(
+        &self,
+        marker: &CycleEnumerationMarker,
+        name_width: usize,
+        cycle_chars: u32,
+    ) -> String {
+        let alignment_offset = match marker.get_alignment() {
+            CycleMarkerAlignment::Center => cycle_chars / 2,
+            CycleMarkerAlignment::Edge => 0,
+        };
+
+        let mut row = " ".repeat(name_width);
+        for (i, label) in marker.visible_labels(self.num_cycles) {
+            let center = name_width + (i * cycle_chars + alignment_offset) as usize;
+            let start = center.saturating_sub(label.chars().count() / 2);
+
+            let length = row.chars().count();
+            if length > start || (length == start && length > name_width) {
+                continue;
+            }
+
+            row.push_str(&" ".repeat(start - length));
+            row.push_str(&label);
+        }
+
+        row
+    }
+}
+
+/// Returns the wave of an [`AssembledLine`] as `num_cycles * cycle_chars` characters.
+fn line_wave(line: &AssembledLine, style: TextStyle, num_cycles: u32, cycle_chars: u32) -> String {
+    let signal = line.signal;
+    let data = signal.box_contents();
+
+    let num_columns = (num_cycles * cycle_chars) as usize;
+    let column = |offset: crate::signal::CycleOffset| {
+        (offset.width_offset(cycle_chars) as usize).min(num_columns)
+    };
+
+    let mut levels = vec![None; num_columns];
+    let mut gaps = Vec::new();
+
+    let path = SignalPath::new(
+        signal.cycles(),
+        &data,
+        signal.get_period(),
+        signal.get_phase(),
+    );
+    let mut offsets = path.state_offsets().peekable();
+
+    let mut prev = None;
+    let mut num_boxes = 0;
+    for &state in signal.cycles() {
+        let (Some(start), Some(&end)) = (offsets.next(), offsets.peek()) else {
+            break;
+        };
+        let (start, end) = (column(start), column(end));
+
+        if state == CycleState::Gap {
+            gaps.push((start + end) / 2);
+        }
+
+        let state = match state {
+            CycleState::Continue | CycleState::Gap => prev,
+            CycleState::Data
+            | CycleState::Box2
+            | CycleState::Box3
+            | CycleState::Box4
+            | CycleState::Box5
+            | CycleState::Box6
+            | CycleState::Box7
+            | CycleState::Box8
+            | CycleState::Box9 => {
+                num_boxes += 1;
+                Some(state)
+            }
+            state => Some(state),
+        };
+        prev = state;
+
+        let Some(state) = state else {
+            levels[start..end].fill(Some(Level::Undefined));
+            continue;
+        };
+
+        let middle = (start + end) / 2;
+        let (first_half, second_half) = match state {
+            CycleState::Top
+            | CycleState::Up
+            | CycleState::HighUnmarked
+            | CycleState::HighMarked => (Level::High, Level::High),
+            CycleState::Bottom
+            | CycleState::Down
+            | CycleState::LowUnmarked
+            | CycleState::LowMarked => (Level::Low, Level::Low),
+            CycleState::Middle => (Level::Middle, Level::Middle),
+            CycleState::PosedgeClockUnmarked | CycleState::PosedgeClockMarked => {
+                (Level::High, Level::Low)
+            }
+            CycleState::NegedgeClockUnmarked | CycleState::NegedgeClockMarked => {
+                (Level::Low, Level::High)
+            }
+            state if state.is_data() => (Level::Data(num_boxes - 1), Level::Data(num_boxes - 1)),
+            _ => (Level::Undefined, Level::Undefined),
+        };
+
+        levels[start..middle].fill(Some(first_half));
+        levels[middle..end].fill(Some(second_half));
+    }
+
+    let mut wave = levels
+        .iter()
+        .enumerate()
+        .map(
+            |(i, level)| match (i.checked_sub(1).and_then(|i| levels[i]), *level) {
+                (_, None) => ' ',
+                (Some(prev), Some(level)) if prev != level => style.transition(prev, level),
+                (_, Some(level)) => style.fill(level),
+            },
+        )
+        .collect::<Vec<_>>();
+
+    // Write the data labels over the fill of their boxes
+    for (i, label) in data.iter().enumerate().take(num_boxes) {
+        let Some(start) = wave.iter().zip(&levels).position(|(c, level)| {
+            *level == Some(Level::Data(i)) && *c == style.fill(Level::Data(i))
+        }) else {
+            continue;
+        };
+        let end = levels[start..]
+            .iter()
+            .position(|level| *level != Some(Level::Data(i)))
+            .map_or(levels.len(), |length| start + length);
+
+        let room = end - start;
+        let label = label.to_plain_string();
+        let label = style.truncate(&label, room);
+        let label_length = label.chars().count();
+
+        let label_start = start + (room - label_length) / 2;
+        for (c, column) in label.chars().zip(label_start..) {
+            wave[column] = c;
+        }
+    }
+
+    for gap in gaps {
+        if gap < wave.len() {
+            wave[gap] = style.gap();
+        }
+    }
+
+    wave.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{Signal, SignalFigure};
+
+    #[test]
+    fn narrow() {
+        let figure = SignalFigure::new().add_signals([Signal::with_cycle_str("x=.=")
+            .name("bus")
+            .add_data_fields(["address", "data"])]);
+
+        let mut text = Vec::new();
+        figure
+            .assemble()
+            .write_text_with_width(&mut text, TextStyle::Unicode, 20)
+            .unwrap();
+
+        assert_eq!(String::from_utf8(text).unwrap(), "bus ░░░░╳address╳da…\n");
+    }
+}
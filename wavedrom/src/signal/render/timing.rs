@@ -0,0 +1,174 @@
+use std::io;
+
+use super::dimensions::SvgDimensions;
+use super::edges::write_edge_text;
+
+use crate::backend::{Backend, Path, PathStyle};
+use crate::signal::timing::TimingMarker;
+use crate::{Font, Options};
+
+/// Returns the `x` of the start and end node of the [`TimingMarker`].
+fn marker_xs(marker: &TimingMarker, dims: &SvgDimensions) -> (u32, u32) {
+    (
+        dims.schema_x() + marker.from().x().width_offset(dims.cycle_width()),
+        dims.schema_x() + marker.to().x().width_offset(dims.cycle_width()),
+    )
+}
+
+/// Returns the length of the arrow heads for the `arrow_size` of the options.
+///
+/// An even size keeps the arrow heads symmetric around the line.
+#[inline]
+fn arrow_head_size(arrow_size: u32) -> u32 {
+    arrow_size / 2 * 2
+}
+
+/// Returns whether the arrow heads are put outside of the dimension line from `start` to `end`,
+/// because they do not fit between the extension lines.
+#[inline]
+fn has_outside_arrows(start: u32, end: u32, arrow_size: u32) -> bool {
+    end - start < 2 * arrow_head_size(arrow_size)
+}
+
+/// Returns the middle `x` of a label with `label_width` for the dimension line from `start` to
+/// `end`.
+///
+/// A label that does not fit between the arrow heads is put to the right of the dimension line.
+pub fn label_middle(start: u32, end: u32, label_width: u32, arrow_size: u32) -> u32 {
+    let size = arrow_head_size(arrow_size);
+
+    if end - start >= label_width + 2 * size {
+        start + (end - start) / 2
+    } else {
+        end + 2 * size + label_width / 2
+    }
+}
+
+/// Returns the horizontal range that a dimension line from `start` to `end` occupies together
+/// with its label.
+pub fn occupied_range(start: u32, end: u32, label_width: u32, arrow_size: u32) -> (u32, u32) {
+    let size = arrow_head_size(arrow_size);
+    let (line_start, line_end) = if has_outside_arrows(start, end, arrow_size) {
+        (start.saturating_sub(2 * size), end + 2 * size)
+    } else {
+        (start, end)
+    };
+
+    if label_width == 0 {
+        return (line_start, line_end);
+    }
+
+    let middle = label_middle(start, end, label_width, arrow_size);
+    (
+        u32::min(line_start, middle.saturating_sub(label_width / 2)),
+        u32::max(line_end, middle + (label_width + 1) / 2),
+    )
+}
+
+pub fn write_timing_shade(
+    backend: &mut impl Backend,
+    marker: &TimingMarker,
+    dims: &SvgDimensions,
+    options: &Options,
+) -> io::Result<()> {
+    let timing = &options.signal.timing;
+
+    let (from_x, to_x) = marker_xs(marker, dims);
+    let top = u32::min(marker.from().y(), marker.to().y());
+    let bottom = u32::max(marker.from().y(), marker.to().y());
+
+    let y = dims.signal_top(top);
+
+    backend.draw_path(
+        &Path::rect(
+            u32::min(from_x, to_x),
+            y,
+            from_x.abs_diff(to_x),
+            dims.signal_top(bottom) + dims.wave_height() - y,
+        ),
+        &PathStyle::new()
+            .fill(timing.shade_color)
+            .fill_opacity(f64::from(timing.shade_opacity.min(100)) / 100.),
+    )
+}
+
+pub fn write_timing_marker(
+    backend: &mut impl Backend,
+    marker: &TimingMarker,
+    line_y: u32,
+    dims: &SvgDimensions,
+    options: &Options,
+    font: &Font,
+) -> io::Result<()> {
+    let timing = &options.signal.timing;
+    let signal_height = dims.wave_height();
+
+    let (from_x, to_x) = marker_xs(marker, dims);
+    let (start_x, end_x) = (u32::min(from_x, to_x), u32::max(from_x, to_x));
+
+    // Extension lines run from the middle of the signal past the dimension line
+    let extension_end = line_y + timing.arrow_size / 2;
+    backend.draw_path(
+        &Path::new()
+            .move_to(
+                from_x,
+                dims.signal_top(marker.from().y()) + signal_height / 2,
+            )
+            .vertical_to(extension_end)
+            .move_to(to_x, dims.signal_top(marker.to().y()) + signal_height / 2)
+            .vertical_to(extension_end),
+        &PathStyle::new().stroke(timing.extension_color, 1).dash(2),
+    )?;
+
+    let size = arrow_head_size(timing.arrow_size);
+    let half = size / 2;
+
+    if start_x != end_x {
+        // Outside arrow heads point inwards from beyond the extension lines
+        let (line_start, line_end, direction) =
+            if has_outside_arrows(start_x, end_x, timing.arrow_size) {
+                (start_x.saturating_sub(2 * size), end_x + 2 * size, -1.)
+            } else {
+                (start_x, end_x, 1.)
+            };
+
+        let size = f64::from(size);
+        let half = f64::from(half);
+
+        backend.draw_path(
+            &Path::new()
+                .move_to(line_start, line_y)
+                .horizontal_to(line_end),
+            &PathStyle::new().stroke(timing.line_color, 1),
+        )?;
+        backend.draw_path(
+            &Path::new()
+                .move_to(start_x, line_y)
+                .line_by(direction * size, -half)
+                .vertical_by(size)
+                .close()
+                .move_to(end_x, line_y)
+                .line_by(-direction * size, -half)
+                .vertical_by(size)
+                .close(),
+            &PathStyle::new().fill(timing.line_color),
+        )?;
+    }
+
+    if let Some(label) = marker.label() {
+        let label_width = font.get_rich_text_width(label, timing.font_size);
+        let middle = label_middle(start_x, end_x, label_width, timing.arrow_size);
+
+        write_edge_text(
+            backend,
+            (f64::from(middle), f64::from(line_y)),
+            label,
+            timing.font_size,
+            timing.text_color,
+            timing.text_background_color,
+            font,
+        )?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,135 @@
+//! Timing annotations put dimension lines with a label between two nodes of a diagram.
+//!
+//! Where an edge is a generic arrow between two nodes, a timing annotation measures the time
+//! between two nodes. It is drawn as a horizontal dimension line below the signals with extension
+//! lines to the referenced transitions. This is used to annotate constraints such as the setup
+//! time (`tSU`), the hold time (`tH`) or the clock-to-output delay (`tCO`) of a signal.
+//!
+//! The window between the two nodes can optionally be shaded to highlight it over the signals
+//! that it refers to.
+//!
+//! In [WaveJson][crate::wavejson] the timing annotations are given under the `timing` property
+//! array at the root JSON level, e.g. `timing: [{ from: "a", to: "b", label: "tSU", shade: true
+//! }]`.
+
+use crate::text::RichText;
+
+use super::edges::InSignalPosition;
+
+/// The definition for a timing annotation between two nodes
+///
+/// # Examples
+///
+/// ```
+/// use wavedrom::signal::timing::TimingAnnotation;
+///
+/// let setup = TimingAnnotation::new('a', 'b').label("tSU").shaded(true);
+/// assert_eq!(setup.get_label().map(|l| l.to_plain_string()).as_deref(), Some("tSU"));
+/// ```
+#[derive(Debug, Clone)]
+#[cfg_attr(test, derive(PartialEq))]
+pub struct TimingAnnotation {
+    from: char,
+    to: char,
+    label: Option<RichText>,
+    shaded: bool,
+}
+
+/// A timing annotation of which the nodes have been placed in the signal schema
+#[derive(Debug, Clone)]
+pub struct TimingMarker<'a> {
+    from: InSignalPosition,
+    to: InSignalPosition,
+    label: Option<&'a RichText>,
+    shaded: bool,
+}
+
+impl TimingAnnotation {
+    /// Create a new unlabeled [`TimingAnnotation`] between the nodes `from` and `to`.
+    #[inline]
+    pub fn new(from: char, to: char) -> Self {
+        Self {
+            from,
+            to,
+            label: None,
+            shaded: false,
+        }
+    }
+
+    /// Give a label to the [`TimingAnnotation`] (e.g. `tSU`).
+    #[inline]
+    pub fn label(mut self, label: impl Into<RichText>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// Set whether the window between the nodes is shaded.
+    #[inline]
+    pub fn shaded(mut self, shaded: bool) -> Self {
+        self.shaded = shaded;
+        self
+    }
+
+    /// Returns the name of the start node
+    #[inline]
+    pub fn from(&self) -> char {
+        self.from
+    }
+
+    /// Returns the name of the end node
+    #[inline]
+    pub fn to(&self) -> char {
+        self.to
+    }
+
+    /// Returns the label of the [`TimingAnnotation`]
+    #[inline]
+    pub fn get_label(&self) -> Option<&RichText> {
+        self.label.as_ref()
+    }
+
+    /// Returns whether the window between the nodes is shaded
+    #[inline]
+    pub fn is_shaded(&self) -> bool {
+        self.shaded
+    }
+}
+
+impl<'a> TimingMarker<'a> {
+    pub(crate) fn new(
+        from: InSignalPosition,
+        to: InSignalPosition,
+        annotation: &'a TimingAnnotation,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            label: annotation.label.as_ref(),
+            shaded: annotation.shaded,
+        }
+    }
+
+    /// The position of the start node
+    #[inline]
+    pub fn from(&self) -> &InSignalPosition {
+        &self.from
+    }
+
+    /// The position of the end node
+    #[inline]
+    pub fn to(&self) -> &InSignalPosition {
+        &self.to
+    }
+
+    /// The label text of the dimension line
+    #[inline]
+    pub fn label(&self) -> Option<&'a RichText> {
+        self.label
+    }
+
+    /// Whether the window between the nodes is shaded
+    #[inline]
+    pub fn is_shaded(&self) -> bool {
+        self.shaded
+    }
+}
@@ -1,12 +1,16 @@
 //! Module with a WaveDrom skin
+use std::sync::OnceLock;
+
 use serde::{Deserialize, Serialize};
 
-use crate::{Options, PartialOptions};
+use crate::{Figure, Options, PartialOptions};
+
+pub mod builtin;
 
 /// The definition for a WaveDrom skin.
 ///
 /// This is a JSON file that defines options for how to assemble and render a WaveDrom figure.
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 #[serde(transparent)]
 pub struct Skin(pub PartialOptions);
 
@@ -32,3 +36,144 @@ impl Skin {
         serde_json::from_str(s)
     }
 }
+
+/// A set of named [`Skin`]s.
+///
+/// A WaveJson file can ask for a skin by name with its `config.skin` value. The registry resolves
+/// that name into the [`Options`] that the figure is rendered with. By default, the registry
+/// contains the [built-in skins][builtin].
+///
+/// # Examples
+///
+/// ```
+/// use wavedrom::Options;
+/// use wavedrom::skin::{Skin, SkinRegistry};
+///
+/// let mut skins = SkinRegistry::default();
+/// skins.register("company", Skin::default());
+///
+/// assert!(skins.get("dark").is_some());
+/// assert!(skins.get("company").is_some());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SkinRegistry {
+    skins: Vec<(String, Skin)>,
+}
+
+impl Default for SkinRegistry {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        for name in builtin::NAMES {
+            if let Some(skin) = builtin::get(name) {
+                registry.register(name, skin);
+            }
+        }
+
+        registry
+    }
+}
+
+impl SkinRegistry {
+    /// Create a new [`SkinRegistry`] that contains the built-in skins.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns a shared [`SkinRegistry`] that contains the built-in skins.
+    ///
+    /// The registry is created the first time it is used. This avoids creating all the built-in
+    /// skins for every rendered figure.
+    pub fn builtin() -> &'static Self {
+        static BUILTIN: OnceLock<SkinRegistry> = OnceLock::new();
+        BUILTIN.get_or_init(Self::default)
+    }
+
+    /// Create a new [`SkinRegistry`] without any skins.
+    #[inline]
+    pub fn empty() -> Self {
+        Self { skins: Vec::new() }
+    }
+
+    /// Add a [`Skin`] under `name`.
+    ///
+    /// If a skin with the same name already exists, it is replaced.
+    pub fn register(&mut self, name: impl Into<String>, skin: Skin) {
+        let name = name.into();
+
+        match self.skins.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, existing)) => *existing = skin,
+            None => self.skins.push((name, skin)),
+        }
+    }
+
+    /// Get the [`Skin`] with `name`.
+    pub fn get(&self, name: &str) -> Option<&Skin> {
+        self.skins
+            .iter()
+            .find_map(|(n, skin)| (n == name).then_some(skin))
+    }
+
+    /// Returns the names of all the skins in the registry.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.skins.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Returns the [`Options`] that `figure` should be rendered with.
+    ///
+    /// This merges the skin that the figure asks for into `options`. If the figure does not ask
+    /// for a skin or the skin is not in the registry, `options` are returned unchanged.
+    pub fn figure_options(&self, figure: &Figure, options: &Options) -> Options {
+        let mut options = options.clone();
+
+        if let Some(skin) = figure.skin().and_then(|name| self.get(name)) {
+            options.merge_in(skin.0.clone());
+        }
+
+        options
+    }
+}
+
+#[cfg(all(test, feature = "json5"))]
+mod tests {
+    use super::*;
+    use crate::Color;
+
+    #[test]
+    fn figure_skin() {
+        let skins = SkinRegistry::default();
+        let options = Options::default();
+
+        let figure = Figure::from_json5("{ signal: [], config: { skin: 'dark' } }").unwrap();
+        let dark = skins.figure_options(&figure, &options);
+        assert_ne!(dark.background, options.background);
+
+        let figure = Figure::from_json5("{ signal: [], config: { skin: 'unknown' } }").unwrap();
+        let unknown = skins.figure_options(&figure, &options);
+        assert_eq!(unknown.background, Some(Color::WHITE));
+    }
+
+    #[test]
+    fn builtin_names() {
+        let skins = SkinRegistry::default();
+
+        for name in builtin::NAMES {
+            assert!(builtin::get(name).is_some());
+            assert!(skins.get(name).is_some());
+        }
+        assert_eq!(skins.names().count(), builtin::NAMES.len());
+    }
+
+    #[test]
+    fn dark_skin_file() {
+        let file = Skin::from_json5(include_str!("../../skins/dark.json5")).unwrap();
+        assert_eq!(format!("{:?}", builtin::dark()), format!("{file:?}"));
+
+        let options = builtin::dark().options();
+        let foreground = "#F5F5F5".parse::<Color>().unwrap();
+        assert_eq!(options.background, Some("#222".parse::<Color>().unwrap()));
+        assert_eq!(options.signal.timing.text_color, foreground);
+        assert_eq!(options.signal.highlight.text_color, foreground);
+    }
+}
@@ -0,0 +1,336 @@
+//! The skins that are built into WaveDrom
+//!
+//! These skins are compiled into the crate and resemble the skins of WaveDrom-JS. They can be
+//! selected by name with [`get`] or through a [`SkinRegistry`][super::SkinRegistry].
+//!
+//! # Examples
+//!
+//! ```
+//! use wavedrom::skin::builtin;
+//!
+//! let options = builtin::get("dark").unwrap().options();
+//! # let _ = options;
+//! ```
+
+use crate::assign::options::PartialAssignOptions;
+use crate::reg::options::PartialRegisterOptions;
+use crate::signal::options::{
+    PartialEdgeOptions, PartialGroupIndicatorOptions, PartialHighlightOptions, PartialPathOptions,
+    PartialSignalOptions, PartialTimingOptions,
+};
+use crate::{Color, PartialFooterOptions, PartialHeaderOptions, PartialOptions};
+
+use super::Skin;
+
+/// The names of all the built-in skins
+pub const NAMES: [&str; 5] = ["default", "dark", "narrow", "lowkey", "print"];
+
+/// Get the built-in skin with `name`.
+///
+/// Returns `None` if there is no built-in skin with that name. All the names can be found in
+/// [`NAMES`].
+pub fn get(name: &str) -> Option<Skin> {
+    Some(match name {
+        "default" => default(),
+        "dark" => dark(),
+        "narrow" => narrow(),
+        "lowkey" => lowkey(),
+        "print" => print(),
+        _ => return None,
+    })
+}
+
+const fn rgb(red: u8, green: u8, blue: u8) -> Color {
+    Color { red, green, blue }
+}
+
+/// The default look. This does not change any of the options.
+pub fn default() -> Skin {
+    Skin(PartialOptions::default())
+}
+
+/// Light text and lines on a dark background.
+pub fn dark() -> Skin {
+    const BACKGROUND: Color = rgb(0x22, 0x22, 0x22);
+    const FOREGROUND: Color = rgb(0xF5, 0xF5, 0xF5);
+    const EDGE: Color = rgb(0xC4, 0xE7, 0xF7);
+
+    Skin(PartialOptions {
+        background: Some(Some(BACKGROUND)),
+        header: Some(PartialHeaderOptions {
+            color: Some(FOREGROUND),
+            cycle_marker_color: Some(FOREGROUND),
+            ..Default::default()
+        }),
+        footer: Some(PartialFooterOptions {
+            color: Some(FOREGROUND),
+            cycle_marker_color: Some(FOREGROUND),
+            ..Default::default()
+        }),
+        signal: Some(PartialSignalOptions {
+            group_indicator: Some(PartialGroupIndicatorOptions {
+                color: Some(FOREGROUND),
+                label_color: Some(FOREGROUND),
+                ..Default::default()
+            }),
+            edge: Some(PartialEdgeOptions {
+                node_text_color: Some(FOREGROUND),
+                node_background_color: Some(BACKGROUND),
+                edge_text_color: Some(FOREGROUND),
+                edge_text_background_color: Some(BACKGROUND),
+                edge_color: Some(EDGE),
+                edge_arrow_color: Some(EDGE),
+                ..Default::default()
+            }),
+            timing: Some(PartialTimingOptions {
+                text_color: Some(FOREGROUND),
+                text_background_color: Some(BACKGROUND),
+                line_color: Some(FOREGROUND),
+                extension_color: Some(rgb(0x88, 0x88, 0x88)),
+                shade_color: Some(EDGE),
+                ..Default::default()
+            }),
+            highlight: Some(PartialHighlightOptions {
+                text_color: Some(FOREGROUND),
+                text_background_color: Some(BACKGROUND),
+                ..Default::default()
+            }),
+            marker_color: Some(FOREGROUND),
+            name_color: Some(FOREGROUND),
+            gap_color: Some(FOREGROUND),
+            gap_background_color: Some(BACKGROUND),
+            path_color: Some(FOREGROUND),
+            hint_line_color: Some(rgb(0x55, 0x55, 0x55)),
+            undefined_color: Some(FOREGROUND),
+            ..Default::default()
+        }),
+        reg: Some(PartialRegisterOptions {
+            text_color: Some(FOREGROUND),
+            line_color: Some(FOREGROUND),
+            type_fills: Some([
+                rgb(0x9D, 0x2D, 0x0C),
+                rgb(0x55, 0x55, 0x55),
+                rgb(0x32, 0x86, 0x40),
+                rgb(0x32, 0x86, 0x40),
+                rgb(0x32, 0x86, 0x40),
+                rgb(0x32, 0x86, 0x40),
+                rgb(0x32, 0x86, 0x40),
+            ]),
+            ..Default::default()
+        }),
+        assign: Some(PartialAssignOptions {
+            label_color: Some(FOREGROUND),
+            line_color: Some(FOREGROUND),
+            gate_fill: Some(BACKGROUND),
+            ..Default::default()
+        }),
+        backgrounds: Some([
+            BACKGROUND,
+            rgb(0x0C, 0x75, 0x9D),
+            rgb(0x32, 0x86, 0x40),
+            rgb(0x9D, 0x2D, 0x0C),
+            rgb(0x3C, 0x4D, 0x9F),
+            rgb(0x13, 0x60, 0x0B),
+            rgb(0x9F, 0x3C, 0x60),
+            rgb(0x90, 0x3C, 0x9F),
+        ]),
+        undefined_background: Some(Some(BACKGROUND)),
+        ..Default::default()
+    })
+}
+
+/// Cycles that are half as wide as the default.
+pub fn narrow() -> Skin {
+    Skin(PartialOptions {
+        signal: Some(PartialSignalOptions {
+            path: Some(PartialPathOptions {
+                cycle_width: Some(24),
+                transition_offset: Some(2),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+}
+
+/// Grey lines and muted data backgrounds that draw less attention.
+pub fn lowkey() -> Skin {
+    const LINE: Color = rgb(0x60, 0x60, 0x60);
+    const TEXT: Color = rgb(0x40, 0x40, 0x40);
+    const EDGE: Color = rgb(0x88, 0x88, 0x88);
+
+    Skin(PartialOptions {
+        header: Some(PartialHeaderOptions {
+            color: Some(TEXT),
+            cycle_marker_color: Some(LINE),
+            ..Default::default()
+        }),
+        footer: Some(PartialFooterOptions {
+            color: Some(TEXT),
+            cycle_marker_color: Some(LINE),
+            ..Default::default()
+        }),
+        signal: Some(PartialSignalOptions {
+            group_indicator: Some(PartialGroupIndicatorOptions {
+                color: Some(LINE),
+                label_color: Some(TEXT),
+                ..Default::default()
+            }),
+            edge: Some(PartialEdgeOptions {
+                node_text_color: Some(TEXT),
+                edge_text_color: Some(TEXT),
+                edge_color: Some(EDGE),
+                edge_arrow_color: Some(EDGE),
+                ..Default::default()
+            }),
+            marker_color: Some(TEXT),
+            name_color: Some(TEXT),
+            gap_color: Some(LINE),
+            path_color: Some(LINE),
+            hint_line_color: Some(rgb(0xEE, 0xEE, 0xEE)),
+            undefined_color: Some(EDGE),
+            ..Default::default()
+        }),
+        reg: Some(PartialRegisterOptions {
+            text_color: Some(TEXT),
+            line_color: Some(LINE),
+            type_fills: Some([
+                rgb(0xF2, 0xDD, 0xDD),
+                rgb(0xEE, 0xEE, 0xEE),
+                rgb(0xDD, 0xF0, 0xE0),
+                rgb(0xDD, 0xF0, 0xE0),
+                rgb(0xDD, 0xF0, 0xE0),
+                rgb(0xDD, 0xF0, 0xE0),
+                rgb(0xDD, 0xF0, 0xE0),
+            ]),
+            ..Default::default()
+        }),
+        assign: Some(PartialAssignOptions {
+            label_color: Some(TEXT),
+            line_color: Some(LINE),
+            ..Default::default()
+        }),
+        backgrounds: Some([
+            Color::WHITE,
+            rgb(0xF2, 0xF2, 0xE6),
+            rgb(0xF2, 0xEB, 0xE3),
+            rgb(0xE6, 0xEE, 0xF2),
+            rgb(0xE6, 0xF0, 0xE8),
+            rgb(0xE8, 0xE9, 0xF2),
+            rgb(0xF0, 0xE8, 0xF2),
+            rgb(0xF5, 0xEE, 0xEE),
+        ]),
+        ..Default::default()
+    })
+}
+
+/// Black on white without colored data backgrounds for printing.
+pub fn print() -> Skin {
+    Skin(PartialOptions {
+        background: Some(Some(Color::WHITE)),
+        header: Some(PartialHeaderOptions {
+            color: Some(Color::BLACK),
+            cycle_marker_color: Some(Color::BLACK),
+            ..Default::default()
+        }),
+        footer: Some(PartialFooterOptions {
+            color: Some(Color::BLACK),
+            cycle_marker_color: Some(Color::BLACK),
+            ..Default::default()
+        }),
+        signal: Some(PartialSignalOptions {
+            group_indicator: Some(PartialGroupIndicatorOptions {
+                color: Some(Color::BLACK),
+                label_color: Some(Color::BLACK),
+                ..Default::default()
+            }),
+            edge: Some(PartialEdgeOptions {
+                node_text_color: Some(Color::BLACK),
+                node_background_color: Some(Color::WHITE),
+                edge_text_color: Some(Color::BLACK),
+                edge_text_background_color: Some(Color::WHITE),
+                edge_color: Some(Color::BLACK),
+                edge_arrow_color: Some(Color::BLACK),
+                ..Default::default()
+            }),
+            timing: Some(PartialTimingOptions {
+                text_color: Some(Color::BLACK),
+                text_background_color: Some(Color::WHITE),
+                line_color: Some(Color::BLACK),
+                extension_color: Some(Color::BLACK),
+                shade_color: Some(rgb(0x80, 0x80, 0x80)),
+                ..Default::default()
+            }),
+            highlight: Some(PartialHighlightOptions {
+                color: Some(rgb(0x80, 0x80, 0x80)),
+                cursor_color: Some(Color::BLACK),
+                text_color: Some(Color::BLACK),
+                text_background_color: Some(Color::WHITE),
+                ..Default::default()
+            }),
+            marker_color: Some(Color::BLACK),
+            name_color: Some(Color::BLACK),
+            gap_color: Some(Color::BLACK),
+            gap_background_color: Some(Color::WHITE),
+            path_color: Some(Color::BLACK),
+            hint_line_color: Some(rgb(0xDD, 0xDD, 0xDD)),
+            undefined_color: Some(Color::BLACK),
+            ..Default::default()
+        }),
+        reg: Some(PartialRegisterOptions {
+            text_color: Some(Color::BLACK),
+            line_color: Some(Color::BLACK),
+            type_fills: Some([
+                rgb(0xA0, 0xA0, 0xA0),
+                rgb(0xE0, 0xE0, 0xE0),
+                rgb(0xC0, 0xC0, 0xC0),
+                rgb(0xC0, 0xC0, 0xC0),
+                rgb(0xC0, 0xC0, 0xC0),
+                rgb(0xC0, 0xC0, 0xC0),
+                rgb(0xC0, 0xC0, 0xC0),
+            ]),
+            ..Default::default()
+        }),
+        assign: Some(PartialAssignOptions {
+            label_color: Some(Color::BLACK),
+            line_color: Some(Color::BLACK),
+            gate_fill: Some(Color::WHITE),
+            ..Default::default()
+        }),
+        // Grey levels stay distinguishable on a black and white printer
+        backgrounds: Some([
+            Color::WHITE,
+            rgb(0xE0, 0xE0, 0xE0),
+            rgb(0xC0, 0xC0, 0xC0),
+            rgb(0xA0, 0xA0, 0xA0),
+            Color::WHITE,
+            rgb(0xE0, 0xE0, 0xE0),
+            rgb(0xC0, 0xC0, 0xC0),
+            rgb(0xA0, 0xA0, 0xA0),
+        ]),
+        undefined_background: Some(Some(Color::WHITE)),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_name() {
+        for name in NAMES {
+            let skin = get(name).unwrap_or_else(|| panic!("no built-in skin named `{name}`"));
+            let _ = skin.options();
+        }
+        assert!(get("unknown").is_none());
+
+        // The dark skin changes the background and the default skin changes nothing
+        assert_eq!(dark().options().background, Some(rgb(0x22, 0x22, 0x22)));
+        assert_eq!(
+            format!("{:?}", default().options()),
+            format!("{:?}", crate::Options::default())
+        );
+    }
+}
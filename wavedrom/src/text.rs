@@ -0,0 +1,289 @@
+//! Text that consists of multiple differently styled spans
+//!
+//! WaveJson allows text to be given as [JsonML][jsonml]. For example,
+//! `["tspan", { class: "h3" }, "Hello ", ["tspan", { "font-weight": "bold" }, "World"]]` is a
+//! text with two spans where the second span is also bold. This is represented with a
+//! [`RichText`].
+//!
+//! # Examples
+//!
+//! ```
+//! use wavedrom::signal::Signal;
+//! use wavedrom::text::{RichText, TextStyle};
+//!
+//! // An active-low reset signal
+//! let name = RichText::new().span("RESET", TextStyle::new().overline());
+//! let signal = Signal::with_cycle_str("1.0..1").name(name);
+//! # let _ = signal;
+//! ```
+//!
+//! [jsonml]: http://www.jsonml.org/
+
+use std::io;
+
+use crate::escape::escape_str;
+
+/// A text made up of a sequence of styled [`TextSpan`]s
+///
+/// A plain string can be turned into a [`RichText`] with a single unstyled span with
+/// [`RichText::from`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RichText {
+    spans: Vec<TextSpan>,
+}
+
+/// A part of a [`RichText`] with one style
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextSpan {
+    text: String,
+    style: TextStyle,
+}
+
+/// The style of a [`TextSpan`]
+///
+/// Every property that is `None` is inherited from the surrounding text.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TextStyle {
+    /// The font size in pixels
+    pub font_size: Option<u32>,
+    /// The font weight (e.g. `bold` or `700`)
+    pub font_weight: Option<String>,
+    /// The font style (e.g. `italic`)
+    pub font_style: Option<String>,
+    /// The text color as a CSS color (e.g. `#F00` or `red`)
+    pub fill: Option<String>,
+    /// The text decoration (e.g. `underline` or `overline`)
+    pub text_decoration: Option<String>,
+    /// The baseline shift (e.g. `sub` or `super`)
+    pub baseline_shift: Option<String>,
+}
+
+impl RichText {
+    /// Create a new empty [`RichText`].
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a span of `text` with `style` to the end of the [`RichText`].
+    pub fn span(mut self, text: impl Into<String>, style: TextStyle) -> Self {
+        self.push_span(text, style);
+        self
+    }
+
+    /// Add a span of `text` with `style` to the end of the [`RichText`].
+    pub fn push_span(&mut self, text: impl Into<String>, style: TextStyle) {
+        let text = text.into();
+
+        if text.is_empty() {
+            return;
+        }
+
+        // Merge spans with the same style to keep the output small
+        if let Some(last) = self.spans.last_mut() {
+            if last.style == style {
+                last.text.push_str(&text);
+                return;
+            }
+        }
+
+        self.spans.push(TextSpan { text, style });
+    }
+
+    /// Returns the spans of the [`RichText`].
+    #[inline]
+    pub fn spans(&self) -> &[TextSpan] {
+        &self.spans
+    }
+
+    /// Returns whether the [`RichText`] contains no text.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.spans.is_empty()
+    }
+
+    /// Returns the text without any of the styling.
+    pub fn to_plain_string(&self) -> String {
+        self.spans.iter().map(|span| span.text.as_str()).collect()
+    }
+
+    /// Write the spans of the [`RichText`] as SVG `<tspan>` elements.
+    ///
+    /// This is meant to be placed inside a `<text>` element.
+    pub fn write_svg_spans(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        for span in &self.spans {
+            span.write_svg(writer)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl TextSpan {
+    /// Returns the text of the [`TextSpan`].
+    #[inline]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns the style of the [`TextSpan`].
+    #[inline]
+    pub fn style(&self) -> &TextStyle {
+        &self.style
+    }
+
+    fn write_svg(&self, writer: &mut impl io::Write) -> io::Result<()> {
+        let TextStyle {
+            font_size,
+            font_weight,
+            font_style,
+            fill,
+            text_decoration,
+            baseline_shift,
+        } = &self.style;
+
+        write!(writer, "<tspan")?;
+
+        if let Some(font_size) = font_size {
+            write!(writer, r#" font-size="{font_size}""#)?;
+        }
+
+        let attributes = [
+            ("font-weight", font_weight),
+            ("font-style", font_style),
+            ("fill", fill),
+            ("text-decoration", text_decoration),
+            ("baseline-shift", baseline_shift),
+        ];
+        for (name, value) in attributes {
+            if let Some(value) = value {
+                write!(writer, r#" {name}="{value}""#, value = escape_str(value))?;
+            }
+        }
+
+        write!(writer, ">{text}</tspan>", text = escape_str(&self.text))
+    }
+}
+
+impl TextStyle {
+    /// Create a new [`TextStyle`] that inherits everything from the surrounding text.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the font weight to bold.
+    #[inline]
+    pub fn bold(mut self) -> Self {
+        self.font_weight = Some("bold".to_string());
+        self
+    }
+
+    /// Set the font style to italic.
+    #[inline]
+    pub fn italic(mut self) -> Self {
+        self.font_style = Some("italic".to_string());
+        self
+    }
+
+    /// Draw a line over the text. This is commonly used for active-low signals.
+    #[inline]
+    pub fn overline(mut self) -> Self {
+        self.text_decoration = Some("overline".to_string());
+        self
+    }
+
+    /// Shift the text below the baseline.
+    #[inline]
+    pub fn subscript(mut self) -> Self {
+        self.baseline_shift = Some("sub".to_string());
+        self
+    }
+
+    /// Shift the text above the baseline.
+    #[inline]
+    pub fn superscript(mut self) -> Self {
+        self.baseline_shift = Some("super".to_string());
+        self
+    }
+
+    /// Apply a WaveDrom class (e.g. `h1` or `muted`) to the [`TextStyle`].
+    ///
+    /// Unknown classes are ignored.
+    pub fn apply_class(&mut self, class: &str) {
+        match class {
+            "h1" => self.font_size = Some(33),
+            "h2" => self.font_size = Some(27),
+            "h3" => self.font_size = Some(20),
+            "h4" => self.font_size = Some(14),
+            "h5" => self.font_size = Some(11),
+            "h6" => self.font_size = Some(8),
+            "muted" => self.fill = Some("#AAA".to_string()),
+            "warning" => self.fill = Some("#F6B900".to_string()),
+            "error" => self.fill = Some("#F60000".to_string()),
+            "info" => self.fill = Some("#0041C4".to_string()),
+            "success" => self.fill = Some("#00AB00".to_string()),
+            _ => {}
+        }
+    }
+
+    /// Apply an SVG presentation attribute (e.g. `font-weight`) to the [`TextStyle`].
+    ///
+    /// The `class` attribute is split into [WaveDrom classes][TextStyle::apply_class]. Unknown
+    /// attributes are ignored.
+    pub fn apply_attribute(&mut self, name: &str, value: &str) {
+        let value = value.to_string();
+
+        match name {
+            "class" => value.split_whitespace().for_each(|c| self.apply_class(c)),
+            "font-size" => {
+                if let Ok(font_size) = value.trim_end_matches("px").parse() {
+                    self.font_size = Some(font_size);
+                }
+            }
+            "font-weight" => self.font_weight = Some(value),
+            "font-style" => self.font_style = Some(value),
+            "fill" => self.fill = Some(value),
+            "text-decoration" => self.text_decoration = Some(value),
+            "baseline-shift" => self.baseline_shift = Some(value),
+            _ => {}
+        }
+    }
+
+    /// Returns a [`TextStyle`] where all the properties that are not set in `self` are taken from
+    /// `parent`.
+    pub fn inherit(&self, parent: &TextStyle) -> TextStyle {
+        TextStyle {
+            font_size: self.font_size.or(parent.font_size),
+            font_weight: self
+                .font_weight
+                .clone()
+                .or_else(|| parent.font_weight.clone()),
+            font_style: self
+                .font_style
+                .clone()
+                .or_else(|| parent.font_style.clone()),
+            fill: self.fill.clone().or_else(|| parent.fill.clone()),
+            text_decoration: self
+                .text_decoration
+                .clone()
+                .or_else(|| parent.text_decoration.clone()),
+            baseline_shift: self
+                .baseline_shift
+                .clone()
+                .or_else(|| parent.baseline_shift.clone()),
+        }
+    }
+}
+
+impl From<String> for RichText {
+    fn from(text: String) -> Self {
+        Self::new().span(text, TextStyle::default())
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        Self::from(text.to_string())
+    }
+}
@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use crate::assign::{AssignFigure, Assignment, GateExpr, GateKind};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AssignJson {
+    pub assign: Vec<AssignItem>,
+}
+
+/// An assignment given as `[output, expression]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AssignItem(pub String, pub AssignExpr);
+
+/// A gate expression given as either an input name or `[operator, ...inputs]`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum AssignExpr {
+    Input(String),
+    Gate(Vec<AssignExpr>),
+}
+
+impl From<AssignExpr> for GateExpr {
+    fn from(value: AssignExpr) -> Self {
+        match value {
+            AssignExpr::Input(name) => GateExpr::Input(name),
+            AssignExpr::Gate(items) => {
+                let mut items = items.into_iter();
+
+                match items.next() {
+                    Some(AssignExpr::Input(op)) => {
+                        GateExpr::gate(GateKind::from_operator(&op), items.map(GateExpr::from))
+                    }
+                    // There is no operator, so we display the expression as an unnamed box.
+                    Some(first) => GateExpr::gate(
+                        GateKind::Named(String::new()),
+                        std::iter::once(first).chain(items).map(GateExpr::from),
+                    ),
+                    None => GateExpr::gate(GateKind::Named(String::new()), []),
+                }
+            }
+        }
+    }
+}
+
+impl From<AssignJson> for AssignFigure {
+    fn from(value: AssignJson) -> Self {
+        AssignFigure::with(
+            value
+                .assign
+                .into_iter()
+                .map(|AssignItem(output, expr)| Assignment::new(output, expr.into()))
+                .collect(),
+        )
+    }
+}
+
+impl From<&GateExpr> for AssignExpr {
+    fn from(value: &GateExpr) -> Self {
+        match value {
+            GateExpr::Input(name) => AssignExpr::Input(name.clone()),
+            GateExpr::Gate(gate) => AssignExpr::Gate(
+                std::iter::once(AssignExpr::Input(gate.kind().operator().to_string()))
+                    .chain(gate.inputs().iter().map(AssignExpr::from))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl From<&AssignFigure> for AssignJson {
+    fn from(value: &AssignFigure) -> Self {
+        AssignJson {
+            assign: value
+                .assignments()
+                .iter()
+                .map(|assignment| {
+                    AssignItem(assignment.output().to_string(), assignment.expr().into())
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,538 @@
+//! Turning a [`json5::Error`] into a positioned [`Error`].
+//!
+//! Most of the WaveJson types are `#[serde(untagged)]`. When a value does not match any of the
+//! variants of such an enum, serde only reports that no variant matched, and the location points
+//! to the surrounding container. To give a useful error, we find the offending value ourselves and
+//! deserialize it again as the variant that its shape indicates.
+
+use std::collections::BTreeMap;
+
+use serde::de::DeserializeOwned;
+
+use crate::error::Error;
+
+use super::assign::{AssignExpr, AssignJson};
+use super::reg::{RegFieldString, RegFontWeight, RegJson};
+use super::signal::{CycleLabelJson, SignalGroupItem, SignalJson, SignalObject};
+use super::span::{location_of, offset_of, JsonPath, PathSegment, SpanNode, SpanTree, ValueKind};
+use super::text::{JsonMl, JsonMlAttribute, JsonMlElement, JsonMlItem};
+
+/// The top-level keys that determine the type of figure.
+const FIGURE_KEYS: [&str; 3] = ["signal", "reg", "assign"];
+
+/// The keys of fields that contain a non-negative integer.
+const INTEGER_FIELDS: [&str; 8] = [
+    "every", "hscale", "bits", "type", "lanes", "vspace", "hspace", "offset",
+];
+
+pub(crate) fn diagnose(src: &str, error: json5::Error) -> Error {
+    let tree = SpanTree::parse(src);
+
+    let json5::Error::Message { msg, location } = error;
+    let mut offset = location.map_or(0, |location| offset_of(src, location));
+
+    // Syntax errors are formatted by pest, which already includes a snippet of the source. Pest
+    // often reports a confusing expectation, so prefer the error of our own parser.
+    if msg.trim_start().starts_with("-->") {
+        if let Some((offset, description)) = &tree.syntax_error {
+            let message = format!("syntax error: {description}");
+            return build_error(src, &tree, message, *offset, None);
+        }
+
+        let description = msg
+            .lines()
+            .find_map(|line| line.trim_start().strip_prefix("= "))
+            .unwrap_or("invalid syntax");
+
+        return build_error(
+            src,
+            &tree,
+            format!("syntax error: {description}"),
+            offset,
+            None,
+        );
+    }
+
+    let mut msg = msg;
+
+    if untagged_enum_name(&msg) == Some("WaveJson") {
+        let Some(root) = &tree.root else {
+            return build_error(src, &tree, msg, offset, None);
+        };
+
+        if root.kind != ValueKind::Object {
+            return build_error(
+                src,
+                &tree,
+                format!("invalid {}, expected a WaveJson object", root.kind),
+                root.span.start,
+                Some("a WaveJson file is an object such as `{ signal: [ ... ] }`".to_string()),
+            );
+        }
+
+        let keys = root
+            .children
+            .iter()
+            .filter_map(|(segment, _)| match segment {
+                PathSegment::Key(key) => Some(key.as_str()),
+                PathSegment::Index(_) => None,
+            });
+
+        let result = match keys.clone().find(|key| FIGURE_KEYS.contains(key)) {
+            Some("signal") => check::<SignalJson>(src),
+            Some("reg") => check::<RegJson>(src),
+            Some("assign") => check::<AssignJson>(src),
+            _ => {
+                let hint = keys
+                    .filter_map(|key| {
+                        FIGURE_KEYS
+                            .iter()
+                            .map(|figure_key| (edit_distance(key, figure_key), key, figure_key))
+                            .filter(|(distance, _, _)| *distance <= 2)
+                            .min()
+                    })
+                    .min()
+                    .map_or_else(
+                        || {
+                            "a signal diagram has a `signal` key, a register a `reg` key and a \
+                            schematic an `assign` key"
+                                .to_string()
+                        },
+                        |(_, key, figure_key)| format!("`{key}` should probably be `{figure_key}`"),
+                    );
+
+                return build_error(
+                    src,
+                    &tree,
+                    "expected a `signal`, `reg` or `assign` key".to_string(),
+                    root.span.start,
+                    Some(hint),
+                );
+            }
+        };
+
+        if let Err(json5::Error::Message {
+            msg: figure_msg,
+            location,
+        }) = result
+        {
+            msg = figure_msg;
+            offset = location.map_or(0, |location| offset_of(src, location));
+        }
+    }
+
+    // Descend into the untagged enums until we find the value that actually caused the error.
+    while let Some(untagged) = untagged_enum_name(&msg).and_then(UntaggedEnum::from_name) {
+        let Some((path, container)) = tree.value_at(offset) else {
+            break;
+        };
+
+        // The error points to the value itself for object fields and to the container for
+        // array elements
+        let itself = path
+            .last()
+            .filter(|segment| matches!(segment, PathSegment::Key(_)) && untagged.is_field(segment))
+            .map(|segment| (segment, container));
+
+        let culprit = itself
+            .into_iter()
+            .chain(
+                container
+                    .children
+                    .iter()
+                    .map(|(segment, child)| (segment, child)),
+            )
+            .filter(|(segment, _)| untagged.is_field(segment))
+            .find_map(|(_, child)| {
+                untagged
+                    .check(&src[child.span.clone()], child.kind)
+                    .err()
+                    .map(|failure| (child, failure))
+            });
+
+        let Some((child, failure)) = culprit else {
+            break;
+        };
+
+        match failure {
+            Failure::Json(json5::Error::Message {
+                msg: child_msg,
+                location,
+            }) => {
+                msg = child_msg;
+                offset = child.span.start
+                    + location.map_or(0, |location| offset_of(&src[child.span.clone()], location));
+            }
+            Failure::Shape => {
+                return build_error(
+                    src,
+                    &tree,
+                    format!("invalid {}, expected {}", child.kind, untagged.expected()),
+                    child.span.start,
+                    Some(untagged.hint().to_string()),
+                );
+            }
+        }
+    }
+
+    if untagged_enum_name(&msg).is_some() {
+        if let Some(node) = tree
+            .value_at(offset)
+            .and_then(|(_, node)| find_invalid_integer(src, node))
+        {
+            let message = format!(
+                "invalid value: {}, expected a non-negative integer",
+                &src[node.span.clone()]
+            );
+            return build_error(src, &tree, message, node.span.start, None);
+        }
+    }
+
+    build_error(src, &tree, msg, offset, None)
+}
+
+/// Find a number in an integer field that is negative or fractional.
+///
+/// `json5` silently casts such numbers when deserializing directly into an integer, while the
+/// untagged enums reject them. Therefore, these values are not found by deserializing again.
+fn find_invalid_integer<'a>(src: &str, node: &'a SpanNode) -> Option<&'a SpanNode> {
+    node.children.iter().find_map(|(segment, child)| {
+        let is_integer_field =
+            matches!(segment, PathSegment::Key(key) if INTEGER_FIELDS.contains(&key.as_str()));
+
+        if is_integer_field && child.kind == ValueKind::Number {
+            let text = src[child.span.clone()].trim_start_matches('+');
+            let is_valid = text.bytes().all(|b| b.is_ascii_digit())
+                || (text.starts_with("0x") || text.starts_with("0X"))
+                    && text[2..].bytes().all(|b| b.is_ascii_hexdigit());
+
+            if !is_valid {
+                return Some(child);
+            }
+        }
+
+        find_invalid_integer(src, child)
+    })
+}
+
+fn build_error(
+    src: &str,
+    tree: &SpanTree,
+    message: String,
+    offset: usize,
+    hint: Option<String>,
+) -> Error {
+    let path = tree
+        .value_at(offset)
+        .map(|(path, _)| path)
+        .unwrap_or_default();
+
+    let hint = hint.or_else(|| field_hint(&message, &path));
+    let path = (!path.is_empty()).then(|| JsonPath(&path).to_string());
+
+    let location = location_of(src, offset);
+    let source_line = src
+        .lines()
+        .nth(location.line - 1)
+        .unwrap_or_default()
+        .to_string();
+
+    Error::new(message, location, path, hint, source_line)
+}
+
+/// Give a hint for a value with the wrong type based on the name of its field.
+fn field_hint(message: &str, path: &[PathSegment]) -> Option<String> {
+    if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'))
+    {
+        return (field == "bits").then(|| {
+            "every register field needs a width, e.g. `{ bits: 8, name: \"data\" }`".to_string()
+        });
+    }
+
+    if message.contains("AssignItem") {
+        return Some(
+            "an assignment is written as `[output, expression]`, e.g. `[\"out\", [\"&\", \"a\", \"b\"]]`"
+                .to_string(),
+        );
+    }
+
+    let PathSegment::Key(key) = path.last()? else {
+        return None;
+    };
+    let is_highlight = matches!(path.first(), Some(PathSegment::Key(key)) if key == "highlight");
+
+    let hint = match key.as_str() {
+        "wave" => {
+            "`wave` should be a string of wave characters, e.g. `wave: \"p.01.x\"`".to_string()
+        }
+        "node" => "`node` should be a string of node names, e.g. `node: \".a..b\"`".to_string(),
+        "name" | "text" | "label" | "skin" => {
+            format!("`{key}` should be a string, e.g. `{key}: \"abc\"`")
+        }
+        "edge" => "`edge` should be an array of edges, e.g. `edge: [\"a~>b\"]`".to_string(),
+        "timing" => "`timing` should be an array of timing annotations, e.g. \
+            `timing: [{ from: \"a\", to: \"b\", label: \"tSU\" }]`"
+            .to_string(),
+        "shade" => "`shade` should be a boolean, e.g. `shade: true`".to_string(),
+        "color" => "`color` should be a color string, e.g. `color: \"#F00\"`".to_string(),
+        "highlight" => "`highlight` should be an array of highlights, e.g. \
+            `highlight: [{ from: 2, to: 3.5, label: \"bug\" }]`"
+            .to_string(),
+        "cursor" => {
+            "`cursor` should be an array of cursors, e.g. `cursor: [{ at: 4, label: \"here\" }]`"
+                .to_string()
+        }
+        "from" | "to" if is_highlight => {
+            format!("`{key}` should be a cycle number, e.g. `{key}: 2.5`")
+        }
+        "from" | "to" => format!("`{key}` should be a single node name, e.g. `{key}: \"a\"`"),
+        "period" | "phase" | "at" => format!("`{key}` should be a number, e.g. `{key}: 2`"),
+        key if INTEGER_FIELDS.contains(&key) => {
+            format!("`{key}` should be a non-negative integer, e.g. `{key}: 1`")
+        }
+        _ => return None,
+    };
+
+    Some(hint)
+}
+
+/// Returns the name of the enum if the message is serde's error for an untagged enum.
+fn untagged_enum_name(message: &str) -> Option<&str> {
+    message.strip_prefix("data did not match any variant of untagged enum ")
+}
+
+fn check<T: DeserializeOwned>(src: &str) -> Result<(), json5::Error> {
+    json5::from_str::<T>(src).map(|_| ())
+}
+
+/// The reason a value did not match an untagged enum.
+enum Failure {
+    /// The value has the shape of a variant, but that variant failed to deserialize.
+    Json(json5::Error),
+    /// The value does not have the shape of any variant.
+    Shape,
+}
+
+/// The untagged enums of the WaveJson format.
+#[derive(Debug, Clone, Copy)]
+enum UntaggedEnum {
+    SignalItem,
+    SignalGroupItem,
+    SignalData,
+    EdgeJson,
+    RegFieldString,
+    RegItemAttribute,
+    RegFontWeight,
+    AssignExpr,
+    JsonMl,
+    JsonMlItem,
+    CycleLabelsJson,
+    CycleLabelJson,
+}
+
+impl UntaggedEnum {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "SignalItem" => Self::SignalItem,
+            "SignalGroupItem" => Self::SignalGroupItem,
+            "SignalData" => Self::SignalData,
+            "EdgeJson" => Self::EdgeJson,
+            "RegFieldString" => Self::RegFieldString,
+            "RegItemAttribute" => Self::RegItemAttribute,
+            "RegFontWeight" => Self::RegFontWeight,
+            "AssignExpr" => Self::AssignExpr,
+            "JsonMl" => Self::JsonMl,
+            "JsonMlItem" => Self::JsonMlItem,
+            "CycleLabelsJson" => Self::CycleLabelsJson,
+            "CycleLabelJson" => Self::CycleLabelJson,
+            _ => return None,
+        })
+    }
+
+    /// Whether a child of the container that reported the error can be of this enum.
+    fn is_field(self, segment: &PathSegment) -> bool {
+        let PathSegment::Key(key) = segment else {
+            return !matches!(
+                self,
+                Self::SignalData
+                    | Self::RegItemAttribute
+                    | Self::RegFontWeight
+                    | Self::JsonMl
+                    | Self::CycleLabelsJson
+            );
+        };
+
+        match self {
+            Self::SignalData => key == "data",
+            Self::RegFieldString => key == "name",
+            Self::RegItemAttribute => key == "attr",
+            Self::RegFontWeight => key == "fontweight",
+            Self::JsonMl => key == "text" || key == "name" || key == "label",
+            Self::CycleLabelsJson => key == "tick" || key == "tock",
+            Self::SignalItem
+            | Self::SignalGroupItem
+            | Self::EdgeJson
+            | Self::AssignExpr
+            | Self::JsonMlItem
+            | Self::CycleLabelJson => false,
+        }
+    }
+
+    /// Deserialize a value as the variant that its shape indicates.
+    fn check(self, src: &str, kind: ValueKind) -> Result<(), Failure> {
+        use ValueKind::*;
+
+        let result = match (self, kind) {
+            // A group item that is a valid `["tspan", ...]` label is not the culprit
+            (Self::SignalGroupItem, Array) => {
+                check::<JsonMlElement>(src).or_else(|_| check::<Vec<SignalGroupItem>>(src))
+            }
+            (Self::SignalItem, Array) => check::<Vec<SignalGroupItem>>(src),
+            (Self::SignalItem | Self::SignalGroupItem, Object) => check::<SignalObject>(src),
+            (Self::SignalGroupItem | Self::SignalData | Self::EdgeJson, String) => Ok(()),
+            (Self::AssignExpr, String) => Ok(()),
+            (Self::SignalData, Array) => check::<Vec<JsonMl>>(src),
+            (Self::EdgeJson, Array) => check::<(std::string::String, JsonMl)>(src),
+            (Self::RegFieldString | Self::RegItemAttribute, String | Number) => {
+                check::<RegFieldString>(src)
+            }
+            (Self::RegItemAttribute, Array) => check::<Vec<RegFieldString>>(src),
+            (Self::RegFontWeight, String | Number) => check::<RegFontWeight>(src),
+            (Self::AssignExpr, Array) => check::<Vec<AssignExpr>>(src),
+            (Self::JsonMl | Self::JsonMlItem, String) => Ok(()),
+            (Self::JsonMl | Self::JsonMlItem, Array) => check::<Vec<JsonMlItem>>(src),
+            (Self::JsonMlItem, Object) => {
+                check::<BTreeMap<std::string::String, JsonMlAttribute>>(src)
+            }
+            (Self::CycleLabelsJson | Self::CycleLabelJson, String | Number) => Ok(()),
+            (Self::CycleLabelsJson, Array) => check::<Vec<CycleLabelJson>>(src),
+            _ => return Err(Failure::Shape),
+        };
+
+        result.map_err(Failure::Json)
+    }
+
+    fn expected(self) -> &'static str {
+        match self {
+            Self::SignalItem => "a signal object or a group",
+            Self::SignalGroupItem => "a group label, a signal object or a nested group",
+            Self::SignalData => "a string or an array of texts",
+            Self::EdgeJson => "an edge string or an `[edge, label]` array",
+            Self::RegFieldString => "a string or a non-negative integer",
+            Self::RegItemAttribute => "a string, a non-negative integer or an array of those",
+            Self::RegFontWeight => "a string or a non-negative integer",
+            Self::AssignExpr => "an input name or an `[operator, ...inputs]` array",
+            Self::JsonMl => "a string or a `[\"tspan\", ...]` array",
+            Self::JsonMlItem => "a string, an attribute object or a `[\"tspan\", ...]` array",
+            Self::CycleLabelsJson => "a number, a string or an array of labels",
+            Self::CycleLabelJson => "a number or a string",
+        }
+    }
+
+    fn hint(self) -> &'static str {
+        match self {
+            Self::SignalItem | Self::SignalGroupItem => {
+                "a signal is written as `{ name: \"clk\", wave: \"p...\" }` and a group as \
+                `[\"label\", { ... }, { ... }]`"
+            }
+            Self::SignalData => "`data` is written as `data: \"A B\"` or `data: [\"A\", \"B\"]`",
+            Self::EdgeJson => {
+                "an edge is written as `\"a~>b label\"` or \
+                `[\"a~>b\", [\"tspan\", { \"font-weight\": \"bold\" }, \"label\"]]`"
+            }
+            Self::RegFieldString => "a field name is written as `name: \"data\"` or `name: 0b101`",
+            Self::RegItemAttribute => {
+                "an attribute is written as `attr: \"RO\"` or `attr: [\"RO\", 0]`"
+            }
+            Self::RegFontWeight => {
+                "a font weight is written as `fontweight: \"bold\"` or `fontweight: 700`"
+            }
+            Self::AssignExpr => {
+                "an expression is written as an input like `\"a\"` or a gate like \
+                `[\"&\", \"a\", [\"~\", \"b\"]]`"
+            }
+            Self::JsonMl | Self::JsonMlItem => {
+                "a text is written as `text: \"abc\"` or \
+                `text: [\"tspan\", { class: \"h3\" }, \"abc\"]`"
+            }
+            Self::CycleLabelsJson | Self::CycleLabelJson => {
+                "cycle labels are written as `tick: 0`, `tick: \"A B C\"` or `tick: [0, 0.5]`"
+            }
+        }
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wavejson::WaveJson;
+
+    #[test]
+    fn untagged_signal_item() {
+        let src =
+            "{ signal: [\n    { name: 'a', wave: '01' },\n    { name: 'b', period: '2' },\n] }";
+        let err = WaveJson::from_json5(src).unwrap_err();
+
+        assert_eq!(err.path(), Some("signal[1].period"));
+        assert_eq!((err.location().line, err.location().column), (3, 26));
+        assert!(err.hint().is_some());
+    }
+
+    #[test]
+    fn nested_group() {
+        let src = "{ signal: [ ['group', { wave: 1 }] ] }";
+        let err = WaveJson::from_json5(src).unwrap_err();
+
+        assert_eq!(err.path(), Some("signal[0][1].wave"));
+    }
+
+    #[test]
+    fn missing_figure_key() {
+        let src = "{ signals: [] }";
+        let err = WaveJson::from_json5(src).unwrap_err();
+
+        assert_eq!(err.path(), None);
+        assert_eq!(err.hint(), Some("`signals` should probably be `signal`"));
+    }
+
+    #[test]
+    fn syntax_error() {
+        let src = "{ signal: [\n  { name: 'a' wave: '1' }\n] }";
+        let err = WaveJson::from_json5(src).unwrap_err();
+
+        assert_eq!(err.message(), "syntax error: expected `,` or `}`");
+        assert_eq!((err.location().line, err.location().column), (2, 15));
+        assert_eq!(err.path(), Some("signal[0]"));
+    }
+
+    #[test]
+    #[cfg(feature = "serde_json")]
+    fn json_error() {
+        let src = "{ \"signal\": [\n  { \"name\": \"a\", \"wave\": 1 }\n] }";
+
+        let json_err = WaveJson::from_json(src).unwrap_err();
+        let json5_err = WaveJson::from_json5(src).unwrap_err();
+
+        assert_eq!(json_err, json5_err);
+        assert_eq!(json_err.path(), Some("signal[0].wave"));
+    }
+}
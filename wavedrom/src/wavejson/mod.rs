@@ -3,51 +3,200 @@
 
 use serde::{Deserialize, Serialize};
 
+use self::assign::AssignJson;
 use self::reg::RegJson;
 use self::signal::SignalJson;
 
+use crate::signal::lint::Lint;
 use crate::Figure;
 
+pub mod assign;
 pub mod reg;
 pub mod signal;
+pub mod text;
+
+#[cfg(feature = "json5")]
+mod diagnostic;
+#[cfg(feature = "json5")]
+mod snippet;
+#[cfg(feature = "json5")]
+mod span;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
 pub enum WaveJson {
-    Signal(SignalJson),
+    Signal(Box<SignalJson>),
     Register(RegJson),
+    Assign(AssignJson),
 }
 
 impl Figure {
     #[cfg(feature = "serde_json")]
-    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
+    pub fn from_json(s: &str) -> Result<Self, crate::Error> {
         WaveJson::from_json(s).map(Into::into)
     }
 
     #[cfg(feature = "json5")]
-    pub fn from_json5(s: &str) -> Result<Self, json5::Error> {
+    pub fn from_json5(s: &str) -> Result<Self, crate::Error> {
         WaveJson::from_json5(s).map(Into::into)
     }
+
+    /// Convert the [`Figure`] back into [`WaveJson`].
+    ///
+    /// Turning the result into a [`Figure`] again gives the same figure. This allows figures that
+    /// are built in code to be saved with [`WaveJson::to_json5`] and edited by hand.
+    pub fn to_wavejson(&self) -> WaveJson {
+        WaveJson::from(self)
+    }
+}
+
+/// Turn a [`serde_json::Error`] into a positioned [`Error`][crate::Error].
+///
+/// JSON is a subset of JSON5. Therefore, if the JSON5 diagnostics are available, they give the same
+/// error as [`WaveJson::from_json5`] would.
+#[cfg(feature = "serde_json")]
+fn json_error(src: &str, error: serde_json::Error) -> crate::Error {
+    #[cfg(feature = "json5")]
+    if let Err(err) = json5::from_str::<WaveJson>(src) {
+        return diagnostic::diagnose(src, err);
+    }
+
+    crate::Error::from_serde_json(src, &error)
 }
 
 impl WaveJson {
     #[cfg(feature = "serde_json")]
-    pub fn from_json(s: &str) -> Result<Self, serde_json::Error> {
-        serde_json::from_str(s)
+    pub fn from_json(s: &str) -> Result<Self, crate::Error> {
+        serde_json::from_str(s).map_err(|err| json_error(s, err))
     }
 
     #[cfg(feature = "json5")]
-    pub fn from_json5(s: &str) -> Result<Self, json5::Error> {
-        json5::from_str(s)
+    pub fn from_json5(s: &str) -> Result<Self, crate::Error> {
+        json5::from_str(s).map_err(|err| diagnostic::diagnose(s, err))
+    }
+
+    #[cfg(feature = "serde_json")]
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    #[cfg(feature = "json5")]
+    pub fn to_json5(&self) -> Result<String, json5::Error> {
+        json5::to_string(self)
+    }
+}
+
+impl WaveJson {
+    /// Find mistakes in the WaveJson that are silently accepted when it is turned into a
+    /// [`Figure`].
+    ///
+    /// At the moment, only signal diagrams are checked. For more information, look at
+    /// [`SignalJson::validate`].
+    pub fn validate(&self) -> Vec<Lint> {
+        match self {
+            WaveJson::Signal(signal_json) => signal_json.validate(),
+            WaveJson::Register(_) | WaveJson::Assign(_) => Vec::new(),
+        }
+    }
+}
+
+impl From<&Figure> for WaveJson {
+    fn from(value: &Figure) -> Self {
+        match value {
+            Figure::Signal(figure) => WaveJson::Signal(Box::new(figure.into())),
+            Figure::Register(figure) => WaveJson::Register(figure.into()),
+            Figure::Assign(figure) => WaveJson::Assign(figure.into()),
+        }
     }
 }
 
 impl From<WaveJson> for Figure {
     fn from(value: WaveJson) -> Self {
         match value {
-            WaveJson::Signal(signal_json) => Figure::Signal(signal_json.into()),
+            WaveJson::Signal(signal_json) => Figure::Signal((*signal_json).into()),
             WaveJson::Register(register_json) => Figure::Register(register_json.into()),
+            WaveJson::Assign(assign_json) => Figure::Assign(assign_json.into()),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json5"))]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+
+    fn render(figure: &Figure) -> Vec<u8> {
+        let mut writer = Vec::new();
+        match figure {
+            Figure::Signal(figure) => figure.assemble().write_svg(&mut writer).unwrap(),
+            Figure::Register(figure) => figure.write_svg(&mut writer).unwrap(),
+            Figure::Assign(figure) => figure.write_svg(&mut writer).unwrap(),
+        }
+        writer
+    }
+
+    /// Check that converting `figure` to WaveJson and back gives the same figure.
+    fn assert_round_trip(figure: &Figure) {
+        let wavejson = figure.to_wavejson().to_json5().unwrap();
+        let roundtrip = Figure::from_json5(&wavejson).unwrap();
+
+        assert_eq!(wavejson, roundtrip.to_wavejson().to_json5().unwrap());
+        assert_eq!(
+            String::from_utf8(render(figure)).unwrap(),
+            String::from_utf8(render(&roundtrip)).unwrap(),
+            "{wavejson}",
+        );
+    }
+
+    fn visit_examples(dir: &Path, f: &mut impl FnMut(&Path)) {
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                visit_examples(&path, f);
+            } else if path.extension().is_some_and(|ext| ext == "json5") {
+                f(&path);
+            }
+        }
+    }
+
+    #[test]
+    fn examples_round_trip() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../tests");
+        visit_examples(&dir, &mut |path| {
+            let source = std::fs::read_to_string(path).unwrap();
+            let figure = Figure::from_json5(&source).unwrap();
+            assert_round_trip(&figure);
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "arbitrary")]
+    fn arbitrary_round_trip() {
+        use arbitrary::{Arbitrary, Unstructured};
+
+        // A xorshift generator keeps the test deterministic without extra dependencies
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut bytes = vec![0; 1024];
+
+        for _ in 0..1000 {
+            for byte in bytes.iter_mut() {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                *byte = state as u8;
+            }
+
+            let Ok(wavejson) = WaveJson::arbitrary(&mut Unstructured::new(&bytes)) else {
+                continue;
+            };
+
+            let figure = Figure::from(wavejson);
+            let wavejson = figure.to_wavejson();
+            let roundtrip = Figure::from(wavejson.clone()).to_wavejson();
+
+            assert_eq!(wavejson.to_json5().unwrap(), roundtrip.to_json5().unwrap());
         }
     }
 }
@@ -6,6 +6,7 @@ use crate::reg::{Lane, LaneBitRange, RegisterFigure, FieldString};
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RegJson {
     pub reg: Vec<RegItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<RegJsonConfig>,
 }
 
@@ -13,9 +14,11 @@ pub struct RegJson {
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RegItem {
     bits: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
     name: Option<RegFieldString>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     attr: Option<RegItemAttribute>,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
     variant: Option<u32>,
 }
 
@@ -30,20 +33,41 @@ pub enum RegFieldString {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct RegJsonConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
     vspace: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hspace: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = arbitrary_lanes))]
     lanes: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     bits: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     fontsize: Option<u32>,
-    fontweight: Option<u32>,
-    fontfamily: Option<u32>,
-    compact: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fontweight: Option<RegFontWeight>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fontfamily: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    compact: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     hflip: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     vflip: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     uneven: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     offset: Option<u32>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum RegFontWeight {
+    Number(u32),
+    Text(String),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(untagged)]
@@ -52,6 +76,15 @@ pub enum RegItemAttribute {
     Multiple(Vec<RegFieldString>),
 }
 
+/// Every lane is allocated, so a huge number of lanes runs out of memory
+#[cfg(feature = "arbitrary")]
+fn arbitrary_lanes(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Option<u32>> {
+    Ok(match u.arbitrary()? {
+        true => Some(u.int_in_range(0..=64)?),
+        false => None,
+    })
+}
+
 fn create_lane_bitrange(num_bits: u32, item: &RegItem) -> LaneBitRange {
     let attributes = item
         .attr
@@ -91,14 +124,25 @@ impl From<RegJson> for RegisterFigure {
             .config
             .as_ref()
             .and_then(|config| config.bits)
-            .unwrap_or_else(|| value.reg.iter().map(|range| range.bits).sum());
+            .unwrap_or_else(|| {
+                value
+                    .reg
+                    .iter()
+                    .fold(0, |bits, range| u32::saturating_add(bits, range.bits))
+            });
+
+        let uneven = value
+            .config
+            .as_ref()
+            .and_then(|config| config.uneven)
+            .unwrap_or_default();
 
         let mut lanes = if num_lanes == 1 {
             let mut lane = Lane::new();
 
             let mut allocated_bits = 0;
             for item in value.reg {
-                let bits = if allocated_bits + item.bits > num_bits {
+                let bits = if item.bits > num_bits - allocated_bits {
                     num_bits - allocated_bits
                 } else {
                     item.bits
@@ -167,7 +211,10 @@ impl From<RegJson> for RegisterFigure {
 
             if lanes.len() as u32 != num_lanes {
                 if lane_allocated_bits != 0 {
-                    lane = lane.pad(bits_per_lane - lane_allocated_bits);
+                    // Uneven lanes only contain the bits that are left over
+                    if !uneven {
+                        lane = lane.pad(bits_per_lane - lane_allocated_bits);
+                    }
                     lanes.push(lane);
                 }
 
@@ -192,14 +239,171 @@ impl From<RegJson> for RegisterFigure {
         }
 
         let mut figure = RegisterFigure::with(lanes);
-    
-        if let Some(vspace) = value.config.as_ref().and_then(|c| c.vspace) {
+
+        let Some(config) = value.config else {
+            return figure;
+        };
+
+        if let Some(vspace) = config.vspace {
             figure = figure.vspace(vspace);
         }
-        if let Some(hspace) = value.config.as_ref().and_then(|c| c.hspace) {
+        if let Some(hspace) = config.hspace {
             figure = figure.hspace(hspace);
         }
+        if let Some(fontsize) = config.fontsize {
+            figure = figure.fontsize(fontsize);
+        }
+        if let Some(fontweight) = config.fontweight {
+            figure = figure.font_weight(match fontweight {
+                RegFontWeight::Number(n) => n.to_string(),
+                RegFontWeight::Text(s) => s,
+            });
+        }
+        if let Some(fontfamily) = config.fontfamily {
+            figure = figure.font_family(fontfamily);
+        }
+        if let Some(offset) = config.offset {
+            figure = figure.bit_offset(offset);
+        }
 
         figure
+            .compact(config.compact.unwrap_or_default())
+            .hflip(config.hflip.unwrap_or_default())
+    }
+}
+
+impl From<&FieldString> for RegFieldString {
+    fn from(value: &FieldString) -> Self {
+        match value {
+            FieldString::Text(s) => RegFieldString::Text(s.clone()),
+            FieldString::Binary(b) => RegFieldString::Binary(*b),
+        }
+    }
+}
+
+impl From<&LaneBitRange> for RegItem {
+    fn from(value: &LaneBitRange) -> Self {
+        let attr = match value.attributes() {
+            [] => None,
+            [attr] => Some(RegItemAttribute::One(attr.into())),
+            attrs => Some(RegItemAttribute::Multiple(
+                attrs.iter().map(RegFieldString::from).collect(),
+            )),
+        };
+
+        RegItem {
+            bits: value.length(),
+            name: value.get_name().map(RegFieldString::from),
+            attr,
+            variant: (value.variant() != 0).then_some(value.variant()),
+        }
+    }
+}
+
+impl From<&RegisterFigure> for RegJson {
+    fn from(value: &RegisterFigure) -> Self {
+        let lanes = value.lanes();
+
+        // Lanes are stored from the top down, so flipped lanes start with the highest bit
+        let vflip = lanes.len() > 1
+            && lanes.first().map(Lane::get_start_bit) > lanes.last().map(Lane::get_start_bit);
+        let lanes = if vflip {
+            lanes.iter().rev().collect::<Vec<_>>()
+        } else {
+            lanes.iter().collect::<Vec<_>>()
+        };
+
+        // The padding at the end of the figure follows from the number of bits. Padding in
+        // between has to be given as unnamed fields.
+        let last_filled_lane = lanes
+            .iter()
+            .rposition(|lane| !lane.bit_ranges().is_empty())
+            .unwrap_or(0);
+
+        let mut reg = Vec::new();
+        for (i, lane) in lanes.iter().enumerate() {
+            let mut allocated_bits = 0;
+            for bit_range in lane.bit_ranges() {
+                // Empty fields are dropped when the figure has multiple lanes
+                if lanes.len() > 1 && bit_range.length() == 0 {
+                    continue;
+                }
+
+                allocated_bits += bit_range.length();
+                reg.push(RegItem::from(bit_range));
+            }
+
+            if i < last_filled_lane && allocated_bits < lane.width() {
+                reg.push(RegItem {
+                    bits: lane.width() - allocated_bits,
+                    name: None,
+                    attr: None,
+                    variant: None,
+                });
+            }
+        }
+
+        let bits_per_lane = lanes.iter().map(|lane| lane.width()).max().unwrap_or(0);
+        let uneven = lanes.iter().any(|lane| lane.width() < bits_per_lane);
+
+        // The lane width is the number of bits divided by the number of lanes rounded up. An
+        // uneven lane that is followed by empty lanes does not count all its bits, so the number
+        // of bits is raised until it gives the same lane width.
+        let num_bits = lanes
+            .iter()
+            .fold(0, |bits, lane| u32::saturating_add(bits, lane.width()));
+        let num_bits = if bits_per_lane == 0 {
+            num_bits
+        } else {
+            u32::max(
+                num_bits,
+                (bits_per_lane - 1)
+                    .saturating_mul(lanes.len() as u32)
+                    .saturating_add(1),
+            )
+        };
+        let num_allocated_bits = reg
+            .iter()
+            .fold(0, |bits, item| u32::saturating_add(bits, item.bits));
+
+        let config = RegJsonConfig {
+            vspace: value.get_vspace(),
+            hspace: value.get_hspace(),
+            lanes: (lanes.len() > 1).then_some(lanes.len() as u32),
+            bits: (num_bits != num_allocated_bits).then_some(num_bits),
+            fontsize: value.get_fontsize(),
+            fontweight: value
+                .get_font_weight()
+                .map(|weight| RegFontWeight::Text(weight.to_string())),
+            fontfamily: value.get_font_family().map(str::to_string),
+            compact: value.get_compact().then_some(true),
+            hflip: value.get_hflip().then_some(true),
+            vflip: vflip.then_some(true),
+            uneven: uneven.then_some(true),
+            offset: (value.get_bit_offset() != 0).then_some(value.get_bit_offset()),
+        };
+
+        let is_default_config = matches!(
+            config,
+            RegJsonConfig {
+                vspace: None,
+                hspace: None,
+                lanes: None,
+                bits: None,
+                fontsize: None,
+                fontweight: None,
+                fontfamily: None,
+                compact: None,
+                hflip: None,
+                vflip: None,
+                uneven: None,
+                offset: None,
+            }
+        );
+
+        RegJson {
+            reg,
+            config: (!is_default_config).then_some(config),
+        }
     }
 }
@@ -1,17 +1,38 @@
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
-use crate::signal::markers::CycleEnumerationMarker;
+use crate::signal::edges::EdgeDefinition;
+use crate::signal::lint::{Lint, LintKind, LintLocation};
+use crate::signal::markers::{
+    Cursor, CycleEnumerationMarker, CycleLabels, CycleMarkerAlignment, Highlight,
+};
+use crate::signal::timing::TimingAnnotation;
 use crate::signal::{CycleOffset, CycleState};
 use crate::signal::{Signal, SignalFigure, SignalFigureSection, SignalFigureSectionGroup};
+use crate::text::RichText;
+use crate::Color;
+
+use super::text::{is_plain, JsonMl, JsonMlElement};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SignalJson {
     pub signal: Vec<SignalItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub head: Option<Head>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub foot: Option<Foot>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub config: Option<Config>,
-    pub edge: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub edge: Option<Vec<EdgeJson>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Vec<TimingJson>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub highlight: Option<Vec<HighlightJson>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cursor: Option<Vec<CursorJson>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,18 +48,25 @@ pub enum SignalItem {
 #[serde(untagged)]
 pub enum SignalGroupItem {
     String(String),
+    Text(JsonMlElement),
     Item(SignalItem),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct SignalObject {
-    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<JsonMl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub wave: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub data: Option<SignalData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub node: Option<String>,
-    pub period: Option<f32>,
-    pub phase: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub period: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phase: Option<f64>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -46,60 +74,242 @@ pub struct SignalObject {
 #[serde(untagged)]
 pub enum SignalData {
     One(String),
-    Multiple(Vec<String>),
+    Multiple(Vec<JsonMl>),
+}
+
+/// An edge given as `"a~>b label"` or as `["a~>b", label]` where the label can be [`JsonMl`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum EdgeJson {
+    Plain(String),
+    Labeled(String, JsonMl),
+}
+
+/// A timing annotation given as `{ from: "a", to: "b", label: "tSU", shade: true }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TimingJson {
+    pub from: char,
+    pub to: char,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<JsonMl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shade: Option<bool>,
+}
+
+/// A highlight given as `{ from: 2, to: 3.5, color: "#F00", label: "bug" }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct HighlightJson {
+    pub from: f64,
+    pub to: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub color: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<JsonMl>,
+}
+
+/// A cursor given as `{ at: 4, label: "here" }`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct CursorJson {
+    pub at: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<JsonMl>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Head {
-    pub text: Option<String>,
-    pub tick: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<JsonMl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick: Option<CycleLabelsJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tock: Option<CycleLabelsJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub every: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Foot {
-    pub text: Option<String>,
-    pub tock: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<JsonMl>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick: Option<CycleLabelsJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tock: Option<CycleLabelsJson>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub every: Option<u32>,
 }
 
+/// The labels of a `tick` or `tock`
+///
+/// This is either the number of the first label, a string of space separated labels or a list of
+/// labels. A list with one number is the number of the first label and a list with two numbers is
+/// the number of the first label and the step between labels in units of the step.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum CycleLabelsJson {
+    Start(f64),
+    Labels(String),
+    List(Vec<CycleLabelJson>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum CycleLabelJson {
+    Number(f64),
+    Text(String),
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Config {
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub hscale: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub skin: Option<String>,
 }
 
+impl SignalJson {
+    /// Find mistakes in the WaveJson that are silently accepted when it is turned into a
+    /// [`SignalFigure`].
+    ///
+    /// This contains all the lints of [`SignalFigure::validate`] together with the mistakes that
+    /// are lost in the conversion, such as unknown wave characters and edges that cannot be parsed.
+    pub fn validate(&self) -> Vec<Lint> {
+        let mut lints = Vec::new();
+
+        for (i, item) in self.signal.iter().enumerate() {
+            validate_item(item, &mut vec![i], &mut lints);
+        }
+
+        // The figure only contains the edges that could be parsed, so we need to map its edge
+        // indices back to the WaveJson indices.
+        let mut valid_edges = Vec::new();
+        for (i, edge) in self.edge.iter().flatten().enumerate() {
+            let edge = match edge {
+                EdgeJson::Plain(edge) | EdgeJson::Labeled(edge, _) => edge,
+            };
+
+            if edge.parse::<EdgeDefinition>().is_ok() {
+                valid_edges.push(i);
+            } else {
+                lints.push(Lint::new(
+                    LintKind::InvalidEdge(edge.clone()),
+                    LintLocation::Edge(i),
+                ));
+            }
+        }
+
+        for (i, highlight) in self.highlight.iter().flatten().enumerate() {
+            if let Some(color) = &highlight.color {
+                if Color::from_str(color).is_err() {
+                    lints.push(Lint::new(
+                        LintKind::InvalidColor(color.clone()),
+                        LintLocation::Highlight(i, "color"),
+                    ));
+                }
+            }
+        }
+
+        let figure = SignalFigure::from(self.clone());
+        lints.extend(
+            figure
+                .validate()
+                .into_iter()
+                .map(|lint| match lint.location() {
+                    LintLocation::Edge(i) => {
+                        Lint::new(lint.kind().clone(), LintLocation::Edge(valid_edges[*i]))
+                    }
+                    LintLocation::Signal { .. }
+                    | LintLocation::Timing(_)
+                    | LintLocation::Highlight(..) => lint,
+                }),
+        );
+
+        lints
+    }
+}
+
+fn validate_item(item: &SignalItem, indices: &mut Vec<usize>, lints: &mut Vec<Lint>) {
+    match item {
+        SignalItem::Group(items) => {
+            for (i, item) in items.iter().enumerate() {
+                if let SignalGroupItem::Item(item) = item {
+                    indices.push(i);
+                    validate_item(item, indices, lints);
+                    indices.pop();
+                }
+            }
+        }
+        SignalItem::Item(object) => validate_object(object, indices, lints),
+    }
+}
+
+fn validate_object(object: &SignalObject, indices: &[usize], lints: &mut Vec<Lint>) {
+    for (i, c) in object.wave.iter().flat_map(|wave| wave.chars()).enumerate() {
+        if CycleState::from_char(c).is_none() {
+            lints.push(Lint::new(
+                LintKind::UnknownWaveCharacter(c),
+                LintLocation::signal_at(indices, "wave", i),
+            ));
+        }
+    }
+
+    if let Some(period) = object.period {
+        if !period.is_finite() || period <= 0. {
+            lints.push(Lint::new(
+                LintKind::InvalidPeriod(period),
+                LintLocation::signal(indices, "period"),
+            ));
+        }
+    }
+
+    if let Some(phase) = object.phase {
+        if !phase.is_finite() || phase < 0. {
+            lints.push(Lint::new(
+                LintKind::InvalidPhase(phase),
+                LintLocation::signal(indices, "phase"),
+            ));
+        }
+    }
+}
+
 impl From<SignalJson> for SignalFigure {
     fn from(value: SignalJson) -> Self {
         let (title, top_cycle_marker) = if let Some(head) = value.head {
-            let title = head.text;
-            let top_cycle_marker = match (head.tick, head.every) {
-                (Some(start), Some(every)) => Some(CycleEnumerationMarker::new(start, every)),
-                (Some(start), None) => Some(CycleEnumerationMarker::new(start, 1)),
-                (None, _) => None,
-            };
+            let top_cycle_marker = head
+                .tick
+                .map(|labels| (labels, CycleMarkerAlignment::Edge))
+                .or_else(|| head.tock.map(|labels| (labels, CycleMarkerAlignment::Center)))
+                .and_then(|(labels, alignment)| cycle_marker(labels, alignment, head.every));
 
-            (title, top_cycle_marker)
+            (head.text.map(RichText::from), top_cycle_marker)
         } else {
             (None, None)
         };
         let (footer, bottom_cycle_marker) = if let Some(foot) = value.foot {
-            let footer = foot.text;
-            let bottom_cycle_marker = match (foot.tock, foot.every) {
-                (Some(start), Some(every)) => Some(CycleEnumerationMarker::new(start, every)),
-                (Some(start), None) => Some(CycleEnumerationMarker::new(start, 1)),
-                (None, _) => None,
-            };
+            let bottom_cycle_marker = foot
+                .tock
+                .map(|labels| (labels, CycleMarkerAlignment::Center))
+                .or_else(|| foot.tick.map(|labels| (labels, CycleMarkerAlignment::Edge)))
+                .and_then(|(labels, alignment)| cycle_marker(labels, alignment, foot.every));
 
-            (footer, bottom_cycle_marker)
+            (foot.text.map(RichText::from), bottom_cycle_marker)
         } else {
             (None, None)
         };
 
-        let hscale = value.config.and_then(|config| config.hscale).unwrap_or(1);
+        let (hscale, skin) = value
+            .config
+            .map_or((None, None), |config| (config.hscale, config.skin));
+        let hscale = hscale.unwrap_or(1);
 
         let sections = value
             .signal
@@ -111,13 +321,23 @@ impl From<SignalJson> for SignalFigure {
 
         if let Some(edge) = value.edge {
             for e in edge {
-                if let Ok(def) = e.parse() {
+                let def = match e {
+                    EdgeJson::Plain(e) => e.parse(),
+                    EdgeJson::Labeled(e, label) => e.parse().map(|def: EdgeDefinition| {
+                        let label = Some(RichText::from(label));
+                        EdgeDefinition::new(def.variant(), def.from(), def.to(), label)
+                    }),
+                };
+
+                if let Ok(def) = def {
                     edges.push(def);
                 }
             }
         }
 
-        SignalFigure::with(
+        let timing = value.timing.into_iter().flatten().map(TimingJson::into);
+
+        let figure = SignalFigure::with(
             title,
             footer,
             top_cycle_marker,
@@ -126,9 +346,60 @@ impl From<SignalJson> for SignalFigure {
             sections,
             edges,
         )
+        .add_timing_annotations(timing)
+        .add_highlights(value.highlight.into_iter().flatten().map(Highlight::from))
+        .add_cursors(value.cursor.into_iter().flatten().map(Cursor::from));
+
+        match skin {
+            Some(skin) => figure.skin(skin),
+            None => figure,
+        }
     }
 }
 
+fn cycle_marker(
+    labels: CycleLabelsJson,
+    alignment: CycleMarkerAlignment,
+    every: Option<u32>,
+) -> Option<CycleEnumerationMarker> {
+    let labels = match labels {
+        CycleLabelsJson::Start(start) => CycleLabels::Numbered { start, step: 1. },
+        CycleLabelsJson::Labels(labels) => {
+            cycle_labels(labels.split_whitespace().map(str::to_string).collect())?
+        }
+        CycleLabelsJson::List(labels) => cycle_labels(
+            labels
+                .into_iter()
+                .map(|label| match label {
+                    CycleLabelJson::Number(n) => n.to_string(),
+                    CycleLabelJson::Text(s) => s,
+                })
+                .collect(),
+        )?,
+    };
+
+    Some(CycleEnumerationMarker::with(labels, every.unwrap_or(1)).alignment(alignment))
+}
+
+/// Turn a list of labels into [`CycleLabels`] in the same way as WaveDrom-JS.
+fn cycle_labels(labels: Vec<String>) -> Option<CycleLabels> {
+    let numbers = labels
+        .iter()
+        .map(|label| label.parse::<f64>().ok())
+        .collect::<Option<Vec<f64>>>();
+
+    Some(match (labels.len(), numbers.as_deref()) {
+        (0, _) => return None,
+        (1, Some(&[start])) => CycleLabels::Numbered { start, step: 1. },
+        // The offset is given in units of the step
+        (2, Some(&[offset, step])) => CycleLabels::Numbered {
+            start: offset * step,
+            step,
+        },
+        _ => CycleLabels::Custom(labels),
+    })
+}
+
 impl From<SignalItem> for SignalFigureSection {
     fn from(signal: SignalItem) -> Self {
         match signal {
@@ -140,7 +411,14 @@ impl From<SignalItem> for SignalFigureSection {
                     .filter_map(|item| match item {
                         SignalGroupItem::String(s) => {
                             if label.is_none() {
-                                label = Some(s);
+                                label = Some(RichText::from(s));
+                            }
+
+                            None
+                        }
+                        SignalGroupItem::Text(text) => {
+                            if label.is_none() {
+                                label = Some(RichText::from(text));
                             }
 
                             None
@@ -158,7 +436,7 @@ impl From<SignalItem> for SignalFigureSection {
 
 impl From<SignalObject> for Signal {
     fn from(item: SignalObject) -> Self {
-        let name = item.name.unwrap_or_default();
+        let name = item.name.map(RichText::from).unwrap_or_default();
         let cycles = item
             .wave
             .unwrap_or_default()
@@ -171,16 +449,274 @@ impl From<SignalObject> for Signal {
                 SignalData::One(data) => data
                     .split(char::is_whitespace)
                     .filter(|s| !s.is_empty())
-                    .map(str::to_string)
+                    .map(RichText::from)
                     .collect(),
-                SignalData::Multiple(data) => data,
+                SignalData::Multiple(data) => data.into_iter().map(RichText::from).collect(),
             });
         let node = item.node.unwrap_or_default();
-        let period = item.period.map_or(0, |f| f.ceil() as u16);
-        let phase = item.phase.map_or_else(CycleOffset::default, |f| {
-            CycleOffset::try_from(f).unwrap_or_default()
-        });
+        let period = item
+            .period
+            .map_or_else(CycleOffset::default, CycleOffset::from_capped);
+        let phase = item
+            .phase
+            .map_or_else(CycleOffset::default, CycleOffset::from_capped);
 
         Signal::with(name, cycles, data, node, period, phase)
     }
 }
+
+impl From<&SignalFigure> for SignalJson {
+    fn from(figure: &SignalFigure) -> Self {
+        let header_text = figure.get_header_text();
+        let top_cycle_marker = figure.get_top_cycle_marker();
+        let head = (header_text.is_some() || top_cycle_marker.is_some()).then(|| {
+            let (tick, tock, every) = cycle_marker_json(top_cycle_marker);
+            Head {
+                text: header_text.map(JsonMl::from),
+                tick,
+                tock,
+                every,
+            }
+        });
+
+        let footer_text = figure.get_footer_text();
+        let bottom_cycle_marker = figure.get_bottom_cycle_marker();
+        let foot = (footer_text.is_some() || bottom_cycle_marker.is_some()).then(|| {
+            let (tick, tock, every) = cycle_marker_json(bottom_cycle_marker);
+            Foot {
+                text: footer_text.map(JsonMl::from),
+                tick,
+                tock,
+                every,
+            }
+        });
+
+        let hscale = figure.get_horizontal_scale();
+        let skin = figure.get_skin();
+        let config = (hscale != 1 || skin.is_some()).then(|| Config {
+            hscale: (hscale != 1).then_some(hscale),
+            skin: skin.map(str::to_string),
+        });
+
+        let edge = (!figure.edges().is_empty())
+            .then(|| figure.edges().iter().map(EdgeJson::from).collect());
+
+        let highlight = (!figure.highlights().is_empty()).then(|| {
+            figure
+                .highlights()
+                .iter()
+                .map(HighlightJson::from)
+                .collect()
+        });
+        let cursor = (!figure.cursors().is_empty())
+            .then(|| figure.cursors().iter().map(CursorJson::from).collect());
+
+        let timing = (!figure.timing_annotations().is_empty()).then(|| {
+            figure
+                .timing_annotations()
+                .iter()
+                .map(TimingJson::from)
+                .collect()
+        });
+
+        Self {
+            signal: figure.sections().iter().map(SignalItem::from).collect(),
+            head,
+            foot,
+            config,
+            edge,
+            timing,
+            highlight,
+            cursor,
+        }
+    }
+}
+
+/// Returns the `tick`, `tock` and `every` values for a [`CycleEnumerationMarker`].
+fn cycle_marker_json(
+    marker: Option<&CycleEnumerationMarker>,
+) -> (
+    Option<CycleLabelsJson>,
+    Option<CycleLabelsJson>,
+    Option<u32>,
+) {
+    let Some(marker) = marker else {
+        return (None, None, None);
+    };
+
+    let labels = match marker.labels() {
+        CycleLabels::Numbered { start, step } if *step == 1. => CycleLabelsJson::Start(*start),
+        CycleLabels::Numbered { start, step } => {
+            // The first number is given in units of the step
+            let offset = if *step == 0. { 0. } else { start / step };
+            CycleLabelsJson::List(vec![
+                CycleLabelJson::Number(offset),
+                CycleLabelJson::Number(*step),
+            ])
+        }
+        CycleLabels::Custom(labels)
+            if labels
+                .iter()
+                .all(|label| !label.is_empty() && !label.contains(char::is_whitespace)) =>
+        {
+            CycleLabelsJson::Labels(labels.join(" "))
+        }
+        CycleLabels::Custom(labels) => CycleLabelsJson::List(
+            labels
+                .iter()
+                .map(|label| CycleLabelJson::Text(label.clone()))
+                .collect(),
+        ),
+    };
+
+    let every = (marker.every() != 1).then_some(marker.every());
+
+    match marker.get_alignment() {
+        CycleMarkerAlignment::Edge => (Some(labels), None, every),
+        CycleMarkerAlignment::Center => (None, Some(labels), every),
+    }
+}
+
+impl From<&EdgeDefinition> for EdgeJson {
+    fn from(edge: &EdgeDefinition) -> Self {
+        match edge.label() {
+            // The edge string cannot hold styling and loses leading whitespace of the label
+            Some(label)
+                if !is_plain(label) || label.to_plain_string().starts_with(char::is_whitespace) =>
+            {
+                let edge = format!("{}{}{}", edge.from(), edge.variant(), edge.to());
+                EdgeJson::Labeled(edge, JsonMl::from(label))
+            }
+            _ => EdgeJson::Plain(edge.to_string()),
+        }
+    }
+}
+
+impl From<TimingJson> for TimingAnnotation {
+    fn from(timing: TimingJson) -> Self {
+        let annotation =
+            TimingAnnotation::new(timing.from, timing.to).shaded(timing.shade.unwrap_or(false));
+
+        match timing.label {
+            Some(label) => annotation.label(label),
+            None => annotation,
+        }
+    }
+}
+
+impl From<&TimingAnnotation> for TimingJson {
+    fn from(annotation: &TimingAnnotation) -> Self {
+        Self {
+            from: annotation.from(),
+            to: annotation.to(),
+            label: annotation.get_label().map(JsonMl::from),
+            shade: annotation.is_shaded().then_some(true),
+        }
+    }
+}
+
+/// An invalid color falls back to the default highlight color.
+impl From<HighlightJson> for Highlight {
+    fn from(highlight: HighlightJson) -> Self {
+        let mut marker = Highlight::new(highlight.from..highlight.to);
+
+        if let Some(color) = highlight
+            .color
+            .and_then(|color| Color::from_str(&color).ok())
+        {
+            marker = marker.color(color);
+        }
+
+        match highlight.label {
+            Some(label) => marker.label(label),
+            None => marker,
+        }
+    }
+}
+
+impl From<&Highlight> for HighlightJson {
+    fn from(highlight: &Highlight) -> Self {
+        let range = highlight.range();
+        Self {
+            from: range.start.into(),
+            to: range.end.into(),
+            color: highlight.get_color().map(|color| color.to_string()),
+            label: highlight.get_label().map(JsonMl::from),
+        }
+    }
+}
+
+impl From<CursorJson> for Cursor {
+    fn from(cursor: CursorJson) -> Self {
+        match cursor.label {
+            Some(label) => Cursor::new(cursor.at).label(label),
+            None => Cursor::new(cursor.at),
+        }
+    }
+}
+
+impl From<&Cursor> for CursorJson {
+    fn from(cursor: &Cursor) -> Self {
+        Self {
+            at: cursor.at().into(),
+            label: cursor.get_label().map(JsonMl::from),
+        }
+    }
+}
+
+impl From<&SignalFigureSection> for SignalItem {
+    fn from(section: &SignalFigureSection) -> Self {
+        match section {
+            SignalFigureSection::Signal(signal) => SignalItem::Item(SignalObject::from(signal)),
+            SignalFigureSection::Group(group) => {
+                let label = group.label().map(|label| {
+                    if is_plain(label) {
+                        SignalGroupItem::String(label.to_plain_string())
+                    } else {
+                        SignalGroupItem::Text(JsonMlElement::from(label))
+                    }
+                });
+                let items = group
+                    .items()
+                    .iter()
+                    .map(|item| SignalGroupItem::Item(SignalItem::from(item)));
+
+                SignalItem::Group(label.into_iter().chain(items).collect())
+            }
+        }
+    }
+}
+
+impl From<&Signal> for SignalObject {
+    fn from(signal: &Signal) -> Self {
+        let name = signal.get_name();
+        let cycles = signal.cycles();
+        let data = signal.box_contents();
+        let node = signal.get_nodes();
+        let period = signal.get_period();
+        let phase = signal.get_phase();
+
+        let data = (!data.is_empty()).then(|| {
+            let is_simple = data.iter().all(|field| {
+                let text = field.to_plain_string();
+                is_plain(field) && !text.is_empty() && !text.contains(char::is_whitespace)
+            });
+
+            if is_simple {
+                let fields = data.iter().map(RichText::to_plain_string);
+                SignalData::One(fields.collect::<Vec<_>>().join(" "))
+            } else {
+                SignalData::Multiple(data.iter().map(JsonMl::from).collect())
+            }
+        });
+
+        Self {
+            name: (!name.is_empty()).then(|| JsonMl::from(name)),
+            wave: (!cycles.is_empty()).then(|| cycles.iter().map(|c| c.to_char()).collect()),
+            data,
+            node: (!node.is_empty()).then(|| node.to_string()),
+            period: (period != CycleOffset::new_rounded(1)).then(|| f64::from(period)),
+            phase: (!phase.is_zero()).then(|| f64::from(phase)),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//! Displaying a [`Lint`] together with the line of the WaveJson source that it refers to.
+
+use crate::error::Snippet;
+use crate::signal::lint::{Lint, LintLevel, LintLocation};
+
+use super::span::{location_of, JsonPath, PathSegment, SpanTree, ValueKind};
+
+impl Lint {
+    /// Display the lint together with the line of the WaveJson `src` that it refers to.
+    ///
+    /// If the value that the lint refers to cannot be found in `src`, only the JSON path is shown.
+    pub fn snippet(&self, src: &str) -> Snippet {
+        let location = self.location();
+        let path = match location {
+            LintLocation::Signal {
+                indices, property, ..
+            } => std::iter::once(PathSegment::Key("signal".to_string()))
+                .chain(indices.iter().copied().map(PathSegment::Index))
+                .chain(std::iter::once(PathSegment::Key(property.to_string())))
+                .collect::<Vec<_>>(),
+            LintLocation::Edge(i) => {
+                vec![PathSegment::Key("edge".to_string()), PathSegment::Index(*i)]
+            }
+            LintLocation::Timing(i) => {
+                vec![
+                    PathSegment::Key("timing".to_string()),
+                    PathSegment::Index(*i),
+                ]
+            }
+            LintLocation::Highlight(i, property) => vec![
+                PathSegment::Key("highlight".to_string()),
+                PathSegment::Index(*i),
+                PathSegment::Key(property.to_string()),
+            ],
+        };
+
+        let message = format!("{}", self.kind());
+        let level = match self.level() {
+            LintLevel::Warning => "warning",
+            LintLevel::Error => "error",
+        };
+
+        let tree = SpanTree::parse(src);
+        let Some(node) = tree.value_by_path(&path) else {
+            return Snippet::new(level, message, None, Some(location.path()), String::new());
+        };
+
+        let mut offset = node.span.start;
+        if let (Some(position), ValueKind::String) = (location.position(), node.kind) {
+            // Skip the opening quote and the characters before the position
+            offset += 1;
+            offset += src[offset..node.span.end]
+                .char_indices()
+                .nth(position)
+                .map_or(0, |(i, _)| i);
+        }
+
+        let location = location_of(src, offset);
+        let source_line = src
+            .lines()
+            .nth(location.line - 1)
+            .unwrap_or_default()
+            .to_string();
+
+        Snippet::new(
+            level,
+            message,
+            Some(location),
+            Some(JsonPath(&path).to_string()),
+            source_line,
+        )
+    }
+}
@@ -0,0 +1,326 @@
+//! The positions of the values in a JSON5 document.
+//!
+//! The `json5` crate only gives the location of an error. To find the value that an error or
+//! lint refers to, we parse the structure of the document ourselves.
+
+use std::fmt::{self, Display};
+use std::ops::Range;
+
+use crate::error::Location;
+
+/// A segment of the path to a value in a JSON document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) enum PathSegment {
+    Key(String),
+    Index(usize),
+}
+
+pub(super) struct JsonPath<'a>(pub(super) &'a [PathSegment]);
+
+impl Display for JsonPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                PathSegment::Key(key) if i == 0 => write!(f, "{key}")?,
+                PathSegment::Key(key) => write!(f, ".{key}")?,
+                PathSegment::Index(index) => write!(f, "[{index}]")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ValueKind {
+    Object,
+    Array,
+    String,
+    Number,
+    Other,
+}
+
+impl Display for ValueKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Object => "object",
+            Self::Array => "array",
+            Self::String => "string",
+            Self::Number => "number",
+            Self::Other => "value",
+        })
+    }
+}
+
+/// A JSON5 value with the byte range it spans in the source.
+#[derive(Debug)]
+pub(super) struct SpanNode {
+    pub(super) kind: ValueKind,
+    pub(super) span: Range<usize>,
+    pub(super) children: Vec<(PathSegment, SpanNode)>,
+}
+
+/// The structure of a JSON5 document with the positions of all values.
+///
+/// This is a lenient parser. When it encounters invalid syntax, it stops and keeps what it has
+/// parsed up until that point.
+#[derive(Debug)]
+pub(super) struct SpanTree {
+    pub(super) root: Option<SpanNode>,
+    /// The offset and description of the first syntax error
+    pub(super) syntax_error: Option<(usize, &'static str)>,
+}
+
+impl SpanTree {
+    pub(super) fn parse(src: &str) -> Self {
+        let mut parser = SpanParser {
+            src,
+            pos: 0,
+            error: None,
+        };
+
+        parser.skip_trivia();
+        let root = (parser.pos < src.len()).then(|| parser.value().0);
+
+        Self {
+            root,
+            syntax_error: parser.error,
+        }
+    }
+
+    /// Returns the value at the end of `path`.
+    pub(super) fn value_by_path(&self, path: &[PathSegment]) -> Option<&SpanNode> {
+        path.iter().try_fold(self.root.as_ref()?, |node, segment| {
+            node.children
+                .iter()
+                .find(|(child_segment, _)| child_segment == segment)
+                .map(|(_, child)| child)
+        })
+    }
+
+    /// Returns the path to and the deepest value that contains `offset`.
+    pub(super) fn value_at(&self, offset: usize) -> Option<(Vec<PathSegment>, &SpanNode)> {
+        let mut node = self.root.as_ref()?;
+        let mut path = Vec::new();
+
+        while let Some((segment, child)) = node
+            .children
+            .iter()
+            .find(|(_, child)| child.span.start <= offset && offset < child.span.end)
+        {
+            path.push(segment.clone());
+            node = child;
+        }
+
+        Some((path, node))
+    }
+}
+
+struct SpanParser<'a> {
+    src: &'a str,
+    pos: usize,
+    error: Option<(usize, &'static str)>,
+}
+
+impl SpanParser<'_> {
+    /// Record a syntax error at `offset` and return that parsing is incomplete.
+    fn fail(&mut self, offset: usize, description: &'static str) -> bool {
+        self.error.get_or_insert((offset, description));
+        false
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.src.as_bytes().get(self.pos).copied()
+    }
+
+    fn skip_trivia(&mut self) {
+        loop {
+            let rest = &self.src[self.pos..];
+
+            if let Some(c) = rest.chars().next().filter(|c| c.is_whitespace()) {
+                self.pos += c.len_utf8();
+            } else if rest.starts_with("//") {
+                self.pos += rest.find('\n').unwrap_or(rest.len());
+            } else if let Some(comment) = rest.strip_prefix("/*") {
+                self.pos += comment.find("*/").map_or(rest.len(), |end| end + 4);
+            } else {
+                break;
+            }
+        }
+    }
+
+    /// Parse a value and return whether it was complete.
+    fn value(&mut self) -> (SpanNode, bool) {
+        let start = self.pos;
+
+        let (kind, complete) = match self.peek() {
+            Some(b'{') => return self.container(b'}'),
+            Some(b'[') => return self.container(b']'),
+            Some(b'"' | b'\'') => (ValueKind::String, self.string().is_some()),
+            Some(c) => {
+                let kind = if c.is_ascii_digit() || matches!(c, b'-' | b'+' | b'.') {
+                    ValueKind::Number
+                } else {
+                    ValueKind::Other
+                };
+                self.bare_word();
+
+                let complete = self.pos > start || self.fail(start, "expected a value");
+                (kind, complete)
+            }
+            None => (ValueKind::Other, self.fail(start, "expected a value")),
+        };
+
+        let node = SpanNode {
+            kind,
+            span: start..self.pos,
+            children: Vec::new(),
+        };
+
+        (node, complete)
+    }
+
+    fn container(&mut self, close: u8) -> (SpanNode, bool) {
+        let start = self.pos;
+        let is_object = close == b'}';
+        self.pos += 1;
+
+        let (unclosed, expected_separator) = if is_object {
+            ("unclosed object", "expected `,` or `}`")
+        } else {
+            ("unclosed array", "expected `,` or `]`")
+        };
+
+        let mut children = Vec::new();
+        let complete = loop {
+            self.skip_trivia();
+
+            match self.peek() {
+                Some(c) if c == close => {
+                    self.pos += 1;
+                    break true;
+                }
+                None => break self.fail(start, unclosed),
+                _ => {}
+            }
+
+            let segment = if is_object {
+                let key = match self.peek() {
+                    Some(b'"' | b'\'') => self.string(),
+                    _ => {
+                        let key_start = self.pos;
+                        self.bare_word();
+                        (self.pos > key_start).then(|| self.src[key_start..self.pos].to_string())
+                    }
+                };
+                let Some(key) = key else {
+                    break self.fail(self.pos, "expected a key");
+                };
+
+                self.skip_trivia();
+                if self.peek() != Some(b':') {
+                    break self.fail(self.pos, "expected `:` after the key");
+                }
+                self.pos += 1;
+                self.skip_trivia();
+
+                PathSegment::Key(key)
+            } else {
+                PathSegment::Index(children.len())
+            };
+
+            let (child, complete) = self.value();
+            children.push((segment, child));
+
+            if !complete {
+                break false;
+            }
+
+            self.skip_trivia();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(c) if c == close => {}
+                None => break self.fail(start, unclosed),
+                _ => break self.fail(self.pos, expected_separator),
+            }
+        };
+
+        let kind = if is_object {
+            ValueKind::Object
+        } else {
+            ValueKind::Array
+        };
+
+        // Parsing stops at the first syntax error, so an incomplete container extends to the end
+        let end = if complete { self.pos } else { self.src.len() };
+        let node = SpanNode {
+            kind,
+            span: start..end,
+            children,
+        };
+
+        (node, complete)
+    }
+
+    /// Parse a quoted string and return its contents.
+    fn string(&mut self) -> Option<String> {
+        let start = self.pos;
+        let quote = self.peek()?;
+        self.pos += 1;
+
+        let mut contents = String::new();
+        let mut chars = self.src[self.pos..].char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    if let Some((_, escaped)) = chars.next() {
+                        contents.push(escaped);
+                    }
+                }
+                c if c == char::from(quote) => {
+                    self.pos += i + 1;
+                    return Some(contents);
+                }
+                c => contents.push(c),
+            }
+        }
+
+        self.pos = self.src.len();
+        self.fail(start, "unterminated string");
+        None
+    }
+
+    /// Skip an unquoted key, number or literal.
+    fn bare_word(&mut self) {
+        let rest = &self.src[self.pos..];
+        self.pos += rest
+            .find(|c: char| {
+                c.is_whitespace() || matches!(c, ',' | ':' | '[' | ']' | '{' | '}' | '/')
+            })
+            .unwrap_or(rest.len());
+    }
+}
+
+/// Convert a [`json5::Location`] into a byte offset into `src`.
+pub(super) fn offset_of(src: &str, location: json5::Location) -> usize {
+    let line_start = src
+        .split_inclusive('\n')
+        .take(location.line.saturating_sub(1))
+        .map(str::len)
+        .sum::<usize>();
+
+    src[line_start..]
+        .char_indices()
+        .nth(location.column.saturating_sub(1))
+        .map_or(src.len(), |(i, _)| line_start + i)
+}
+
+pub(super) fn location_of(src: &str, offset: usize) -> Location {
+    let before = &src[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+
+    Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
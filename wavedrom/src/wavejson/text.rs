@@ -0,0 +1,205 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::text::{RichText, TextStyle};
+
+/// A text that is either a plain string or [JsonML][jsonml] markup
+///
+/// The markup has the form `["tspan", { attributes }, ...children]` where the attributes are
+/// optional and every child is either a string or markup itself.
+///
+/// [jsonml]: http://www.jsonml.org/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum JsonMl {
+    Text(String),
+    Element(Vec<JsonMlItem>),
+}
+
+/// [JsonML][jsonml] markup that starts with the `tspan` tag
+///
+/// This is used where an array without a tag has another meaning, such as in the items of a
+/// signal group.
+///
+/// [jsonml]: http://www.jsonml.org/
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(try_from = "Vec<JsonMlItem>", into = "Vec<JsonMlItem>")]
+pub struct JsonMlElement(Vec<JsonMlItem>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum JsonMlItem {
+    Text(String),
+    Attributes(BTreeMap<String, JsonMlAttribute>),
+    Element(Vec<JsonMlItem>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(untagged)]
+pub enum JsonMlAttribute {
+    Text(String),
+    Number(f64),
+}
+
+impl From<JsonMl> for RichText {
+    fn from(value: JsonMl) -> Self {
+        match value {
+            JsonMl::Text(text) => RichText::from(text),
+            JsonMl::Element(items) => {
+                let mut text = RichText::new();
+                push_element(&mut text, &items, &TextStyle::default());
+                text
+            }
+        }
+    }
+}
+
+impl TryFrom<Vec<JsonMlItem>> for JsonMlElement {
+    type Error = &'static str;
+
+    fn try_from(items: Vec<JsonMlItem>) -> Result<Self, Self::Error> {
+        match items.first() {
+            Some(JsonMlItem::Text(tag)) if tag == "tspan" => Ok(Self(items)),
+            _ => Err("expected a `[\"tspan\", ...]` array"),
+        }
+    }
+}
+
+impl From<JsonMlElement> for Vec<JsonMlItem> {
+    fn from(element: JsonMlElement) -> Self {
+        element.0
+    }
+}
+
+impl From<JsonMlElement> for RichText {
+    fn from(element: JsonMlElement) -> Self {
+        RichText::from(JsonMl::Element(element.0))
+    }
+}
+
+/// Plain text becomes [`JsonMl::Text`] and styled text becomes a `tspan` with one child per span.
+impl From<&RichText> for JsonMl {
+    fn from(text: &RichText) -> Self {
+        if is_plain(text) {
+            JsonMl::Text(text.to_plain_string())
+        } else {
+            JsonMl::Element(element_items(text))
+        }
+    }
+}
+
+impl From<&RichText> for JsonMlElement {
+    fn from(text: &RichText) -> Self {
+        Self(element_items(text))
+    }
+}
+
+/// Returns whether the `text` has no styling.
+pub(crate) fn is_plain(text: &RichText) -> bool {
+    text.spans()
+        .iter()
+        .all(|span| span.style() == &TextStyle::default())
+}
+
+fn element_items(text: &RichText) -> Vec<JsonMlItem> {
+    let mut items = vec![JsonMlItem::Text("tspan".to_string())];
+
+    for span in text.spans() {
+        if span.style() == &TextStyle::default() {
+            items.push(JsonMlItem::Text(span.text().to_string()));
+            continue;
+        }
+
+        let TextStyle {
+            font_size,
+            font_weight,
+            font_style,
+            fill,
+            text_decoration,
+            baseline_shift,
+        } = span.style();
+
+        let font_size = font_size.map(|font_size| font_size.to_string());
+        let attributes = [
+            ("font-size", &font_size),
+            ("font-weight", font_weight),
+            ("font-style", font_style),
+            ("fill", fill),
+            ("text-decoration", text_decoration),
+            ("baseline-shift", baseline_shift),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| {
+            let value = value.as_ref()?;
+            Some((name.to_string(), JsonMlAttribute::Text(value.clone())))
+        })
+        .collect();
+
+        items.push(JsonMlItem::Element(vec![
+            JsonMlItem::Text("tspan".to_string()),
+            JsonMlItem::Attributes(attributes),
+            JsonMlItem::Text(span.text().to_string()),
+        ]));
+    }
+
+    items
+}
+
+fn push_element(text: &mut RichText, items: &[JsonMlItem], parent: &TextStyle) {
+    // The first item is the tag name, which is always treated as a `tspan`
+    let items = match items.first() {
+        Some(JsonMlItem::Text(_)) => &items[1..],
+        _ => items,
+    };
+
+    let mut style = TextStyle::default();
+    let items = match items.first() {
+        Some(JsonMlItem::Attributes(attributes)) => {
+            for (name, value) in attributes {
+                let value = match value {
+                    JsonMlAttribute::Text(s) => s.clone(),
+                    JsonMlAttribute::Number(n) => n.to_string(),
+                };
+                style.apply_attribute(name, &value);
+            }
+
+            &items[1..]
+        }
+        _ => items,
+    };
+    let style = style.inherit(parent);
+
+    for item in items {
+        match item {
+            JsonMlItem::Text(s) => text.push_span(s.as_str(), style.clone()),
+            JsonMlItem::Element(items) => push_element(text, items, &style),
+            JsonMlItem::Attributes(_) => {}
+        }
+    }
+}
+
+#[cfg(all(test, feature = "json5"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nested_styles() {
+        let src = r#"["tspan", { class: "h3" }, "a", ["tspan", { "font-weight": "bold" }, "b"]]"#;
+        let text = RichText::from(json5::from_str::<JsonMl>(src).unwrap());
+
+        let heading = TextStyle {
+            font_size: Some(20),
+            ..Default::default()
+        };
+        let bold = TextStyle {
+            font_weight: Some("bold".to_string()),
+            ..heading.clone()
+        };
+        assert_eq!(text, RichText::new().span("a", heading).span("b", bold));
+    }
+}